@@ -3,13 +3,42 @@ use anyhow::Result;
 use dashmap::DashMap;
 use lychee_lib::{CacheStatus, Status, Uri};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    thread,
+};
+
+/// Prefix of the file names of cache shards
+const SHARD_PREFIX: &str = "shard-";
 
 /// Describes a response status that can be serialized to disk
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct CacheValue {
     pub(crate) status: CacheStatus,
     pub(crate) timestamp: Timestamp,
+    /// Final URI of a redirected link, which can be checked directly instead
+    /// of following the redirects again (see `--cache-redirects`)
+    #[serde(default)]
+    pub(crate) redirect: Option<Uri>,
+    /// When the redirects to `redirect` were followed
+    #[serde(default)]
+    pub(crate) redirect_timestamp: Option<Timestamp>,
+    /// The status is older than the maximum cache age, so the entry is only
+    /// kept for its redirect
+    #[serde(skip)]
+    pub(crate) expired: bool,
+}
+
+impl CacheValue {
+    /// Remember the final URI of a redirected link, which was reached at
+    /// `timestamp`
+    pub(crate) fn with_redirect(mut self, redirect: Option<(Uri, Timestamp)>) -> Self {
+        (self.redirect, self.redirect_timestamp) = redirect.unzip();
+        self
+    }
 }
 
 impl From<&Status> for CacheValue {
@@ -18,6 +47,9 @@ impl From<&Status> for CacheValue {
         CacheValue {
             status: s.into(),
             timestamp,
+            redirect: None,
+            redirect_timestamp: None,
+            expired: false,
         }
     }
 }
@@ -32,8 +64,32 @@ pub(crate) trait StoreExt {
     /// Store the cache under the given path. Update access timestamps
     fn store<T: AsRef<Path>>(&self, path: T) -> Result<()>;
 
-    /// Load cache from path. Discard entries older than `max_age_secs`
-    fn load<T: AsRef<Path>>(path: T, max_age_secs: u64) -> Result<Cache>;
+    /// Load cache from path. Discard entries older than `max_age_secs`,
+    /// unless their redirect is younger than `max_redirect_age_secs`
+    fn load<T: AsRef<Path>>(
+        path: T,
+        max_age_secs: u64,
+        max_redirect_age_secs: u64,
+    ) -> Result<Cache>;
+
+    /// Store the cache split into `shards` files in the directory `dir`.
+    /// Entries get assigned to a shard by a hash of their URI, and the
+    /// shards are written in parallel. The shards of earlier runs only get
+    /// replaced once all new shards were written successfully
+    fn store_sharded<T: AsRef<Path>>(&self, dir: T, shards: usize) -> Result<()>;
+
+    /// Load all cache shards from the directory `dir` in parallel. Discard
+    /// entries like [`StoreExt::load`]
+    fn load_sharded<T: AsRef<Path>>(
+        dir: T,
+        max_age_secs: u64,
+        max_redirect_age_secs: u64,
+    ) -> Result<Cache>;
+
+    /// Remove all entries for URIs which are not in `uris` (e.g. because they
+    /// are no longer referenced by any input). Returns the number of removed
+    /// entries
+    fn prune(&self, uris: &HashSet<Uri>) -> usize;
 }
 
 impl StoreExt for Cache {
@@ -47,21 +103,258 @@ impl StoreExt for Cache {
         Ok(())
     }
 
-    fn load<T: AsRef<Path>>(path: T, max_age_secs: u64) -> Result<Cache> {
-        let mut rdr = csv::ReaderBuilder::new()
-            .has_headers(false)
-            .from_path(path)?;
-
+    fn load<T: AsRef<Path>>(
+        path: T,
+        max_age_secs: u64,
+        max_redirect_age_secs: u64,
+    ) -> Result<Cache> {
         let map = DashMap::new();
-        let current_ts = timestamp();
-        for result in rdr.deserialize() {
-            let (uri, value): (Uri, CacheValue) = result?;
-            // Discard entries older than `max_age_secs`.
-            // This allows gradually updating the cache over multiple runs.
-            if current_ts - value.timestamp < max_age_secs {
-                map.insert(uri, value);
+        load_into(&map, path.as_ref(), max_age_secs, max_redirect_age_secs)?;
+        Ok(map)
+    }
+
+    fn store_sharded<T: AsRef<Path>>(&self, dir: T, shards: usize) -> Result<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let shards = shards.max(1);
+        let mut buckets = vec![Vec::new(); shards];
+        for entry in self {
+            buckets[shard(entry.key(), shards)].push((entry.key().clone(), entry.value().clone()));
+        }
+        // Write to temporary files first, so that the shards of earlier runs
+        // stay intact if writing fails
+        let temporary: Vec<PathBuf> = (0..shards)
+            .map(|i| dir.join(format!(".{SHARD_PREFIX}{i}.tmp")))
+            .collect();
+        let written = thread::scope(|scope| {
+            let handles: Vec<_> = buckets
+                .into_iter()
+                .zip(&temporary)
+                .map(|(bucket, path)| {
+                    scope.spawn(move || -> Result<()> {
+                        let mut wtr = csv::WriterBuilder::new()
+                            .has_headers(false)
+                            .from_path(path)?;
+                        for entry in bucket {
+                            wtr.serialize(entry)?;
+                        }
+                        wtr.flush()?;
+                        Ok(())
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().expect("cannot store cache shard"))
+        });
+        if let Err(e) = written {
+            for path in &temporary {
+                let _ = fs::remove_file(path);
             }
+            return Err(e);
         }
+
+        let current: Vec<PathBuf> = (0..shards)
+            .map(|i| dir.join(format!("{SHARD_PREFIX}{i}")))
+            .collect();
+        for (from, to) in temporary.iter().zip(&current) {
+            fs::rename(from, to)?;
+        }
+        // Remove the remaining shards of earlier runs, which might have used more
+        for path in shard_paths(dir)? {
+            if !current.contains(&path) {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn load_sharded<T: AsRef<Path>>(
+        dir: T,
+        max_age_secs: u64,
+        max_redirect_age_secs: u64,
+    ) -> Result<Cache> {
+        let map = DashMap::new();
+        let paths = shard_paths(dir.as_ref())?;
+        thread::scope(|scope| {
+            let handles: Vec<_> = paths
+                .iter()
+                .map(|path| {
+                    scope.spawn(|| load_into(&map, path, max_age_secs, max_redirect_age_secs))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().expect("cannot load cache shard"))
+        })?;
         Ok(map)
     }
+
+    fn prune(&self, uris: &HashSet<Uri>) -> usize {
+        let before = self.len();
+        self.retain(|uri, _| uris.contains(uri));
+        before - self.len()
+    }
+}
+
+/// Load the cache entries from the file at `path` into `map`
+fn load_into(
+    map: &Cache,
+    path: &Path,
+    max_age_secs: u64,
+    max_redirect_age_secs: u64,
+) -> Result<()> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(path)?;
+
+    let current_ts = timestamp();
+    for result in rdr.deserialize() {
+        let (uri, mut value): (Uri, CacheValue) = result?;
+        let redirect_expired = value
+            .redirect_timestamp
+            .is_none_or(|ts| current_ts - ts >= max_redirect_age_secs);
+        if redirect_expired {
+            value = value.with_redirect(None);
+        }
+        // Discard entries older than `max_age_secs`, unless their redirect
+        // can still be used.
+        // This allows gradually updating the cache over multiple runs.
+        value.expired = current_ts - value.timestamp >= max_age_secs;
+        if !value.expired || value.redirect.is_some() {
+            map.insert(uri, value);
+        }
+    }
+    Ok(())
+}
+
+/// Get the shard of the cache (out of `shards`) which stores `uri`
+#[allow(clippy::cast_possible_truncation)]
+fn shard(uri: &Uri, shards: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    uri.hash(&mut hasher);
+    // Truncating the hash on 32-bit platforms doesn't matter
+    hasher.finish() as usize % shards
+}
+
+/// Get the paths of all cache shards in `dir`
+fn shard_paths(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_shard = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(SHARD_PREFIX));
+        if is_shard && path.is_file() {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use lychee_lib::CacheStatus;
+
+    use super::*;
+
+    fn value() -> CacheValue {
+        (&Status::Ok(http::StatusCode::OK)).into()
+    }
+
+    #[test]
+    fn test_prune() {
+        let current = Uri::try_from("https://example.com/current").unwrap();
+        let stale = Uri::try_from("https://example.com/stale").unwrap();
+        let cache = Cache::new();
+        cache.insert(current.clone(), value());
+        cache.insert(stale.clone(), value());
+        cache.insert(Uri::try_from("https://example.org/").unwrap(), value());
+
+        let removed = cache.prune(&HashSet::from([current.clone()]));
+
+        assert_eq!(removed, 2);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains_key(&current));
+        assert!(!cache.contains_key(&stale));
+
+        // Pruning again is a no-op
+        assert_eq!(cache.prune(&HashSet::from([current])), 0);
+    }
+
+    #[test]
+    fn test_sharded() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new();
+        for i in 0..100 {
+            let uri = Uri::try_from(format!("https://example.com/{i}")).unwrap();
+            cache.insert(uri, value());
+        }
+        cache.store_sharded(dir.path(), 8).unwrap();
+        assert_eq!(shard_paths(dir.path()).unwrap().len(), 8);
+
+        let loaded = Cache::load_sharded(dir.path(), 60, 0).unwrap();
+        assert_eq!(loaded.len(), cache.len());
+        for entry in &cache {
+            let value = loaded.get(entry.key()).unwrap();
+            assert_eq!(value.status, entry.value().status);
+            assert_eq!(value.timestamp, entry.value().timestamp);
+        }
+
+        // Storing with fewer shards replaces all shards of earlier runs
+        cache.store_sharded(dir.path(), 2).unwrap();
+        assert_eq!(shard_paths(dir.path()).unwrap().len(), 2);
+        assert_eq!(Cache::load_sharded(dir.path(), 60, 0).unwrap().len(), 100);
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_load_redirects() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache");
+        let uri = |path: &str| Uri::try_from(format!("https://example.com/{path}")).unwrap();
+        let old = timestamp() - 3600;
+        let expired = |redirect: Option<(Uri, Timestamp)>| CacheValue {
+            timestamp: old,
+            ..value().with_redirect(redirect)
+        };
+
+        let cache = Cache::new();
+        cache.insert(uri("fresh"), value());
+        cache.insert(uri("expired"), expired(None));
+        cache.insert(
+            uri("redirect"),
+            expired(Some((uri("target"), timestamp() - 60))),
+        );
+        cache.insert(uri("old-redirect"), expired(Some((uri("target"), old))));
+        cache.store(&path).unwrap();
+
+        // Entries with an expired status are only kept for a fresh redirect
+        let loaded = Cache::load(&path, 600, 600).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert!(!loaded.get(&uri("fresh")).unwrap().expired);
+        let redirect = loaded.get(&uri("redirect")).unwrap();
+        assert!(redirect.expired);
+        assert_eq!(redirect.redirect, Some(uri("target")));
+
+        // Redirects are not kept by default
+        assert_eq!(Cache::load(&path, 600, 0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_load_without_redirect_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache");
+        fs::write(&path, format!("https://example.com/,200,{}\n", timestamp())).unwrap();
+        let loaded = Cache::load(&path, 600, 600).unwrap();
+        let value = loaded
+            .get(&Uri::try_from("https://example.com/").unwrap())
+            .unwrap();
+        assert_eq!(value.status, CacheStatus::Ok(200));
+        assert_eq!(value.redirect, None);
+    }
 }
@@ -1,21 +1,99 @@
-use crate::options::Config;
-use crate::parse::{parse_basic_auth, parse_duration_secs, parse_headers, parse_remaps};
-use anyhow::{Context, Result};
+use crate::options::{Config, Format};
+use crate::parse::{
+    env_var, parse_basic_auth, parse_content_types, parse_duration_secs, parse_expected_status,
+    parse_header_names, parse_headers, parse_host_concurrency, parse_optional_statuscodes,
+    parse_remaps, parse_scheme_timeouts, parse_uri_timeouts, parse_variables,
+};
+use anyhow::{bail, Context, Result};
 use headers::HeaderMapExt;
 use http::StatusCode;
-use lychee_lib::{Client, ClientBuilder};
-use regex::RegexSet;
-use std::{collections::HashSet, str::FromStr};
+use lychee_lib::{Base, Client, ClientBuilder, DEFAULT_WAYBACK_API};
+use regex::{Regex, RegexSet};
+use std::{collections::HashSet, str::FromStr, time::Duration};
+
+/// Check the config for errors (e.g. invalid regular expressions, status
+/// codes or headers) before starting, so that all of them get reported at
+/// once instead of failing on the first one
+pub(crate) fn validate(cfg: &Config) -> Result<()> {
+    let mut errors: Vec<anyhow::Error> = vec![];
+    let mut check = |result: Result<()>| {
+        if let Err(e) = result {
+            errors.push(e);
+        }
+    };
+
+    for header in &cfg.headers {
+        check(
+            parse_headers(&[header], env_var)
+                .map(drop)
+                .with_context(|| format!("Invalid header `{header}`")),
+        );
+    }
+    if let Some(auth) = &cfg.basic_auth {
+        check(parse_basic_auth(auth, env_var).map(drop));
+    }
+    check(parse_scheme_timeouts(&cfg.scheme_timeout).map(drop));
+    check(parse_uri_timeouts(&cfg.uri_timeout).map(drop));
+    check(parse_expected_status(&cfg.expect_status).map(drop));
+    check(parse_host_concurrency(&cfg.host_concurrency).map(drop));
+    check(parse_content_types(&cfg.content_type).map(drop));
+    check(parse_header_names(&cfg.capture_header).map(drop));
+    check(
+        reqwest::Method::from_str(&cfg.method.to_uppercase())
+            .map(drop)
+            .with_context(|| format!("Invalid request method `{}`", cfg.method)),
+    );
+    check(parse_remaps(&cfg.remap).map(drop));
+    check(parse_variables(&cfg.var, env_var).map(drop));
+    for (kind, patterns) in [("include", &cfg.include), ("exclude", &cfg.exclude)] {
+        for pattern in patterns {
+            check(
+                Regex::new(pattern)
+                    .map(drop)
+                    .with_context(|| format!("Invalid {kind} pattern `{pattern}`")),
+            );
+        }
+    }
+    for code in cfg.accept.iter().flatten() {
+        check(
+            StatusCode::from_u16(*code)
+                .map(drop)
+                .with_context(|| format!("Invalid accepted status code `{code}`")),
+        );
+    }
+    for code in cfg.retry_on.iter().flatten() {
+        check(
+            StatusCode::from_u16(*code)
+                .map(drop)
+                .with_context(|| format!("Invalid retried status code `{code}`")),
+        );
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let errors: Vec<String> = errors
+        .iter()
+        .map(|e| format!("  - {}", format!("{e:#}").replace('\n', "\n    ")))
+        .collect();
+    bail!("Invalid configuration:\n{}", errors.join("\n"))
+}
 
 /// Creates a client according to the command-line config
 pub(crate) fn create(cfg: &Config) -> Result<Client> {
-    let mut headers = parse_headers(&cfg.headers)?;
+    let mut headers = parse_headers(&cfg.headers, env_var)?;
     if let Some(auth) = &cfg.basic_auth {
-        let auth_header = parse_basic_auth(auth)?;
+        let auth_header = parse_basic_auth(auth, env_var)?;
         headers.typed_insert(auth_header);
     }
 
     let timeout = parse_duration_secs(cfg.timeout);
+    let scheme_timeouts = parse_scheme_timeouts(&cfg.scheme_timeout)?;
+    let uri_timeouts = parse_uri_timeouts(&cfg.uri_timeout)?;
+    let expected_status = parse_expected_status(&cfg.expect_status)?;
+    let host_concurrency = parse_host_concurrency(&cfg.host_concurrency)?;
+    let content_types = parse_content_types(&cfg.content_type)?;
+    let capture_headers = parse_header_names(&cfg.capture_header)?;
     let retry_wait_time = parse_duration_secs(cfg.retry_wait_time);
     let method: reqwest::Method = reqwest::Method::from_str(&cfg.method.to_uppercase())?;
 
@@ -30,16 +108,8 @@ pub(crate) fn create(cfg: &Config) -> Result<Client> {
         cfg.scheme.clone()
     };
 
-    let accepted = match cfg.accept {
-        Some(ref accepted) => {
-            let accepted: Result<HashSet<_>, _> = accepted
-                .iter()
-                .map(|code| StatusCode::from_u16(*code))
-                .collect();
-            Some(accepted?)
-        }
-        None => None,
-    };
+    let accepted = parse_optional_statuscodes(cfg.accept.as_ref())?;
+    let retry_on = parse_optional_statuscodes(cfg.retry_on.as_ref())?;
 
     ClientBuilder::builder()
         .remaps(remaps)
@@ -50,17 +120,69 @@ pub(crate) fn create(cfg: &Config) -> Result<Client> {
         .exclude_link_local_ips(cfg.exclude_link_local)
         .exclude_loopback_ips(cfg.exclude_loopback)
         .exclude_mail(cfg.exclude_mail)
+        .exclude_hosts(cfg.exclude_host.iter().cloned().collect::<HashSet<_>>())
+        .include_hosts(cfg.include_host.iter().cloned().collect::<HashSet<_>>())
         .max_redirects(cfg.max_redirects)
         .user_agent(cfg.user_agent.clone())
         .allow_insecure(cfg.insecure)
+        .http2(cfg.http2)
         .custom_headers(headers)
         .method(method)
         .timeout(timeout)
+        .scheme_timeouts(scheme_timeouts)
+        .uri_timeouts(uri_timeouts)
+        .expected_status(expected_status)
+        .host_concurrency(host_concurrency)
         .retry_wait_time(retry_wait_time)
+        .retry_jitter(cfg.retry_jitter.map(Into::into))
+        .retry_on(retry_on)
+        .host_delay(cfg.host_delay.map(Duration::from_millis))
         .github_token(cfg.github_token.clone())
         .schemes(HashSet::from_iter(schemes))
         .accepted(accepted)
         .require_https(cfg.require_https)
+        .hsts(cfg.hsts)
+        .deny_mixed_content(cfg.deny_mixed_content)
+        .deny_cross_host_redirects(cfg.deny_cross_host_redirects)
+        .exact_redirect_hosts(cfg.exact_redirect_hosts)
+        .require_final_https(cfg.require_final_https)
+        .accept_self_redirects(cfg.accept_self_redirects)
+        // The redirects report and the suggestions need to know where
+        // redirected links ended up
+        .warn_on_redirects(
+            cfg.warn_on_redirects
+                || cfg.suggestions.is_some()
+                || matches!(cfg.format, Format::Redirects),
+        )
+        .warn_on_trailing_slash_redirects(cfg.trailing_slash.is_some())
+        .warn_on_disposable_mail(cfg.warn_on_disposable_mail)
+        .disposable_mail_domains(
+            cfg.disposable_domain
+                .iter()
+                .cloned()
+                .collect::<HashSet<_>>(),
+        )
+        .wayback_api(
+            (cfg.wayback || cfg.wayback_as_warning)
+                .then(|| reqwest::Url::parse(DEFAULT_WAYBACK_API).expect("valid Wayback API URL")),
+        )
+        .wayback_as_warning(cfg.wayback_as_warning)
+        .sniff_content(cfg.sniff_content)
+        .verify_content_length(cfg.verify_content_length)
+        .content_types(content_types)
+        .timeout_as_success(cfg.timeout_as_success)
+        .cors_origin(cfg.cors_origin.clone())
+        .warmup(cfg.warmup)
+        .send_referer(cfg.referer)
+        .referer_base(match &cfg.base {
+            Some(Base::Remote(url)) => Some(url.clone()),
+            _ => None,
+        })
+        .capture_headers(capture_headers)
+        .exclude_self_links(cfg.exclude_self_links)
+        .check_self_link_anchors(cfg.check_self_link_anchors)
+        .max_response_size(cfg.max_response_size)
+        .max_concurrency(cfg.max_concurrency)
         .build()
         .client()
         .context("Failed to create request client")
@@ -1,20 +1,34 @@
+use std::collections::HashSet;
+use std::future::Future;
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use indicatif::ProgressBar;
 use indicatif::ProgressStyle;
-use lychee_lib::Result;
-use lychee_lib::Status;
+use lychee_lib::{CacheStatus, Status};
+use lychee_lib::{ErrorKind, Result};
 use tokio::sync::mpsc;
+use tokio::time::Instant;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 
 use crate::formatters::response::ResponseFormatter;
-use crate::{cache::Cache, stats::ResponseStats, ExitCode};
-use lychee_lib::{Client, Request, Response};
+use crate::options::{Config, MailCase};
+use crate::{
+    cache::{Cache, CacheValue, StoreExt},
+    stats::ResponseStats,
+    time::{timestamp, Timestamp},
+    ExitCode,
+};
+use lychee_lib::{Client, Request, Response, Uri};
 
 use super::CommandParams;
 
+/// Verbosity level (`-vv`) to print the time each check took
+const TIMING_VERBOSITY: u8 = 2;
+
 pub(crate) async fn check<S>(
     params: CommandParams<S>,
 ) -> Result<(ResponseStats, Arc<Cache>, ExitCode)>
@@ -28,15 +42,31 @@ where
     let cache_ref = params.cache.clone();
 
     let client = params.client;
+    let timeout_as_success = client.timeout_as_success();
     let cache = params.cache;
+    let recheck_failures = params.cfg.recheck_failures;
+    let mail_case = params.cfg.ignore_mail_case;
+    let deadline = params
+        .cfg
+        .time_budget
+        .map(|budget| (Instant::now() + budget, budget));
+    let limit = params.cfg.max_checks.map(CheckLimit::new);
     // Start receiving requests
-    tokio::spawn(async move {
+    let checks = tokio::spawn(async move {
         futures::StreamExt::for_each_concurrent(
             ReceiverStream::new(recv_req),
             max_concurrency,
-            |request: Result<Request>| async {
-                let request = request.expect("cannot read request");
-                let response = handle(&client, cache.clone(), request).await;
+            |request: std::result::Result<Request, Response>| async {
+                let response = timed(handle_within_budget(
+                    &client,
+                    cache.clone(),
+                    request,
+                    recheck_failures,
+                    mail_case,
+                    limit.as_ref(),
+                    deadline,
+                ))
+                .await;
 
                 send_resp
                     .send(response)
@@ -47,53 +77,46 @@ where
         .await;
     });
 
-    let pb = if params.cfg.no_progress {
-        None
-    } else {
-        let bar = ProgressBar::new_spinner().with_style(ProgressStyle::default_bar().template(
-            "{spinner:.red.bright} {pos}/{len:.dim} [{elapsed_precise}] {bar:25} {wide_msg}",
-        ));
-        bar.set_length(0);
-        bar.set_message("Extracting links");
-        // 10 updates per second = report status at _most_ every 100ms
-        bar.set_draw_rate(10);
-        // report status _at least_ every 500ms
-        bar.enable_steady_tick(500);
-        Some(bar)
-    };
+    let pb = (!params.cfg.no_progress).then(progress_bar);
 
     let bar = pb.clone();
 
     let formatter = Arc::new(params.formatter);
 
+    // Set once a check failed in fail-fast mode
+    let cancelled = Arc::new(AtomicBool::new(false));
+
     let show_results_task = tokio::spawn({
         let verbose = params.cfg.verbose;
+        let fail_fast = params.cfg.fail_fast;
+        let cancelled = cancelled.clone();
         async move {
-            while let Some(response) = recv_resp.recv().await {
-                show_progress(&mut io::stdout(), &pb, &response, &formatter, verbose)?;
+            let mut stdout = io::stdout();
+            while let Some((response, elapsed)) = recv_resp.recv().await {
+                show_progress(&mut stdout, &pb, &response, elapsed, &formatter, verbose)?;
+                let failed = response.status().is_failure();
                 stats.add(response);
+                if fail_fast && failed {
+                    // Stop all outstanding checks. Wait for the task to end
+                    // before closing the response channel it sends to.
+                    cancelled.store(true, Ordering::Relaxed);
+                    checks.abort();
+                    let _ = checks.await;
+                    break;
+                }
             }
             Ok((pb, stats))
         }
     });
 
-    let requests = params.requests;
-    tokio::pin!(requests);
-
-    while let Some(request) = requests.next().await {
-        let request = request?;
-        if let Some(pb) = &bar {
-            pb.inc_length(1);
-            pb.set_message(request.to_string());
-        };
-        send_req
-            .send(Ok(request))
-            .await
-            .expect("Cannot send request");
-    }
-    // required for the receiver task to end, which closes send_resp, which allows
-    // the show_results_task to finish
-    drop(send_req);
+    let uris = send_requests(
+        params.requests,
+        send_req,
+        bar.as_ref(),
+        &cancelled,
+        &params.cfg,
+    )
+    .await?;
 
     let result: Result<(_, _)> = show_results_task.await?;
     let (pb, stats) = result?;
@@ -104,7 +127,13 @@ where
         pb.finish_and_clear();
     }
 
-    let code = if stats.is_success() {
+    // A cancelled run didn't collect all links, so the cache entries of the
+    // others would get lost
+    if params.cfg.prune_cache && !cancelled.load(Ordering::Relaxed) {
+        prune_cache(&cache_ref, &uris, params.cfg.verbose);
+    }
+
+    let code = if stats.is_success(timeout_as_success) {
         ExitCode::Success
     } else {
         ExitCode::LinkCheckFailure
@@ -112,64 +141,312 @@ where
     Ok((stats, cache_ref, code))
 }
 
+/// Send the collected requests to the checks, until all requests are sent or
+/// the checks got cancelled
+///
+/// Errors of single links or inputs get sent as failed responses, all other
+/// errors stop the run. With `--ignore-mail-case`, mail addresses of an input
+/// which only differ in case are only sent once. With `--prune-cache`, the
+/// cache keys of all URIs are returned, to remove all other entries from the
+/// cache.
+async fn send_requests<S>(
+    requests: S,
+    send_req: mpsc::Sender<std::result::Result<Request, Response>>,
+    bar: Option<&ProgressBar>,
+    cancelled: &AtomicBool,
+    cfg: &Config,
+) -> Result<HashSet<Uri>>
+where
+    S: futures::Stream<Item = Result<Request>>,
+{
+    tokio::pin!(requests);
+    let mut uris = HashSet::new();
+    let mut mails = HashSet::new();
+    while let Some(request) = requests.next().await {
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        let request = request
+            .map(Ok)
+            .or_else(|e| Response::from_input_error(e).map(Err))?;
+        if let (Ok(request), Some(mail_case)) = (&request, cfg.ignore_mail_case) {
+            let key = cache_key(&request.uri, Some(mail_case));
+            if request.uri.is_mail() && !mails.insert((request.source.clone(), key)) {
+                continue;
+            }
+        }
+        if let Some(pb) = bar {
+            pb.inc_length(1);
+        }
+        if let Ok(request) = &request {
+            if cfg.prune_cache {
+                uris.insert(cache_key(&request.uri, cfg.ignore_mail_case));
+            }
+            if let Some(pb) = bar {
+                pb.set_message(request.to_string());
+            }
+        }
+        if send_req.send(request).await.is_err() {
+            // The checks were cancelled in fail-fast mode
+            break;
+        }
+    }
+    // Dropping `send_req` ends the checks, which closes the response
+    // channel, which allows showing the results to finish
+    Ok(uris)
+}
+
+/// Remove all entries for URIs which were not found in the inputs from the
+/// cache (`--prune-cache`)
+fn prune_cache(cache: &Cache, uris: &HashSet<Uri>, verbose: u8) {
+    let pruned = cache.prune(uris);
+    if verbose > 0 {
+        eprintln!("Pruned {pruned} stale cache entries");
+    }
+}
+
+/// Create the progress bar, which starts with a spinner while links are being
+/// extracted
+fn progress_bar() -> ProgressBar {
+    let bar = ProgressBar::new_spinner().with_style(ProgressStyle::default_bar().template(
+        "{spinner:.red.bright} {pos}/{len:.dim} [{elapsed_precise}] {bar:25} {wide_msg}",
+    ));
+    bar.set_length(0);
+    bar.set_message("Extracting links");
+    // 10 updates per second = report status at _most_ every 100ms
+    bar.set_draw_rate(10);
+    // report status _at least_ every 500ms
+    bar.enable_steady_tick(500);
+    bar
+}
+
+/// Get the key of a URI in the cache
+///
+/// With `mail_case`, mail addresses which only differ in case share a key.
+fn cache_key(uri: &Uri, mail_case: Option<MailCase>) -> Uri {
+    match mail_case {
+        Some(mail_case) => uri.normalize_mail(mail_case == MailCase::All),
+        None => uri.clone(),
+    }
+}
+
+/// Maximum number of checks in a run (`--max-checks`)
+struct CheckLimit {
+    max: usize,
+    used: AtomicUsize,
+}
+
+impl CheckLimit {
+    const fn new(max: usize) -> Self {
+        Self {
+            max,
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    /// Count a check, unless the limit is reached already
+    fn acquire(&self) -> bool {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                (used < self.max).then_some(used + 1)
+            })
+            .is_ok()
+    }
+}
+
 /// Handle a single request
-async fn handle(client: &Client, cache: Arc<Cache>, request: Request) -> Response {
+///
+/// If `recheck_failures` is set, cached failures get checked again instead
+/// of being reused. Once the `limit` of checks is reached, requests which
+/// are not cached get skipped.
+async fn handle(
+    client: &Client,
+    cache: Arc<Cache>,
+    request: Request,
+    recheck_failures: bool,
+    mail_case: Option<MailCase>,
+    limit: Option<&CheckLimit>,
+) -> Response {
     let uri = request.uri.clone();
-    if let Some(v) = cache.get(&uri) {
+    let key = cache_key(&uri, mail_case);
+    let entry = cache.get(&key).map(|v| v.value().clone());
+    let cached = entry
+        .as_ref()
+        .filter(|v| !v.expired)
+        .map(|v| v.status)
+        .filter(|status| !(recheck_failures && matches!(status, CacheStatus::Error(_))));
+    if let Some(cached) = cached {
         // Found a cached request
         // Overwrite cache status in case the URI is excluded in the
         // current run
         let status = if client.is_excluded(&uri) {
             Status::Excluded
         } else {
-            Status::from(v.value().status)
+            Status::from(cached)
         };
         return Response::new(uri.clone(), status, request.source);
     }
 
+    if let Some(limit) = limit.filter(|limit| !limit.acquire()) {
+        let reason = ErrorKind::CheckLimitReached(limit.max);
+        return Response::new(uri, Status::Skipped(Box::new(reason)), request.source);
+    }
+
     // Request was not cached; run a normal check
     //
     // This can panic when the Url could not be parsed to a Uri.
     // See https://github.com/servo/rust-url/issues/554
     // See https://github.com/seanmonstar/reqwest/issues/668
     // TODO: Handle error as soon as https://github.com/seanmonstar/reqwest/pull/1399 got merged
-    let response = client.check(request).await.expect("cannot check URI");
+    let cached_redirect = entry.and_then(|v| v.redirect.zip(v.redirect_timestamp));
+    let (response, redirect) = match cached_redirect {
+        Some((target, timestamp)) => match check_redirect_target(client, &request, &target).await {
+            Some(response) => (response, Some((target, timestamp))),
+            None => check_uncached(client, request).await,
+        },
+        None => check_uncached(client, request).await,
+    };
 
     // - Never cache filesystem access as it is fast already so caching has no
     //   benefit.
     // - Skip caching unsupported URLs as they might be supported in a
     //   future run.
     // - Skip caching excluded links; they might not be excluded in the next run
+    // - Skip caching warnings, which have no cached equivalent
     let status = response.status();
-    if !uri.is_file() && !status.is_excluded() && !status.is_unsupported() {
-        cache.insert(uri, status.into());
+    if !uri.is_file() && !status.is_excluded() && !status.is_unsupported() && !status.is_warning() {
+        cache.insert(key, CacheValue::from(status).with_redirect(redirect));
     }
     response
 }
 
+/// Check a request, and get the final URI if it got redirected successfully
+/// along with the current time, so that it can be cached
+async fn check_uncached(client: &Client, request: Request) -> (Response, Option<(Uri, Timestamp)>) {
+    let response = client.check(request).await.expect("cannot check URI");
+    let redirect = response
+        .1
+        .redirect
+        .clone()
+        .filter(|_| response.status().is_success())
+        .map(|target| (target, timestamp()));
+    (response, redirect)
+}
+
+/// Check the cached final URI of a redirected link directly, without
+/// following the redirects again (see `--cache-redirects`). The response is
+/// reported for the original URI.
+///
+/// Returns `None` if the target is not valid (anymore), so that the link gets
+/// checked as usual, in case it redirects elsewhere by now.
+async fn check_redirect_target(
+    client: &Client,
+    request: &Request,
+    target: &Uri,
+) -> Option<Response> {
+    let target_request = Request::new(
+        target.clone(),
+        request.source.clone(),
+        request.element.clone(),
+        request.attribute.clone(),
+    );
+    let mut response = client.check(target_request).await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.1.uri = request.uri.clone();
+    response.1.redirect = Some(target.clone());
+    Some(response)
+}
+
+/// Handle a single request until the deadline of the time budget (if any)
+///
+/// A check which doesn't complete in time gets cancelled. Once the budget is
+/// used up, all further requests are skipped without being checked. Links or
+/// inputs which can't be checked at all come with their failed response
+/// already.
+async fn handle_within_budget(
+    client: &Client,
+    cache: Arc<Cache>,
+    request: std::result::Result<Request, Response>,
+    recheck_failures: bool,
+    mail_case: Option<MailCase>,
+    limit: Option<&CheckLimit>,
+    deadline: Option<(Instant, Duration)>,
+) -> Response {
+    let request = match request {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+    let Some((deadline, budget)) = deadline else {
+        return handle(client, cache, request, recheck_failures, mail_case, limit).await;
+    };
+    let (uri, source) = (request.uri.clone(), request.source.clone());
+    let check = handle(client, cache, request, recheck_failures, mail_case, limit);
+    tokio::time::timeout_at(deadline, check)
+        .await
+        .unwrap_or_else(|_| {
+            let reason = ErrorKind::TimeBudgetExceeded(budget);
+            Response::new(uri, Status::Skipped(Box::new(reason)), source)
+        })
+}
+
+/// Run `future` and measure how long it took
+async fn timed<F: Future>(future: F) -> (F::Output, Duration) {
+    let start = Instant::now();
+    let output = future.await;
+    (output, start.elapsed())
+}
+
+/// Print a response as it completes
+///
+/// With `-v`, every response gets printed, otherwise only failures. With
+/// `-vv`, every response gets printed with the time its check took instead.
 fn show_progress(
     output: &mut dyn Write,
     progress_bar: &Option<ProgressBar>,
     response: &Response,
+    elapsed: Duration,
     formatter: &Arc<Box<dyn ResponseFormatter>>,
-    verbose: bool,
+    verbose: u8,
 ) -> Result<()> {
     let out = formatter.write_response(response)?;
+    let line = if verbose >= TIMING_VERBOSITY {
+        timed_line(response, elapsed)
+    } else {
+        out.clone()
+    };
     if let Some(pb) = progress_bar {
         pb.inc(1);
-        pb.set_message(out.clone());
-        if verbose {
-            pb.println(out);
+        pb.set_message(out);
+        if verbose > 0 {
+            pb.println(line);
         }
-    } else if verbose || (!response.status().is_success() && !response.status().is_excluded()) {
-        writeln!(output, "{}", out)?;
+    } else if verbose > 0 || (!response.status().is_success() && !response.status().is_excluded()) {
+        writeln!(output, "{}", line)?;
     }
     Ok(())
 }
 
+/// Format a response as status icon, elapsed time and URI, followed by the
+/// time to first byte of website checks,
+/// e.g. `✔ [  120ms] https://example.com/ (TTFB 80ms)`
+fn timed_line(response: &Response, elapsed: Duration) -> String {
+    let ttfb = match response.1.timings.and_then(|timings| timings.ttfb) {
+        Some(ttfb) => format!(" (TTFB {}ms)", ttfb.as_millis()),
+        None => String::new(),
+    };
+    format!(
+        "{} [{:>5}ms] {}{ttfb}",
+        response.status().icon(),
+        elapsed.as_millis(),
+        response.1.uri
+    )
+}
+
 #[cfg(test)]
 mod tests {
-    use lychee_lib::{CacheStatus, InputSource, ResponseBody, Uri};
+    use lychee_lib::{ExclusionSource, InputSource, Timings, Uri};
 
     use crate::formatters;
 
@@ -178,18 +455,175 @@ mod tests {
     #[test]
     fn test_skip_cached_responses_in_progress_output() {
         let mut buf = Vec::new();
-        let response = Response(
+        let response = Response::new(
+            Uri::try_from("http://127.0.0.1").unwrap(),
+            Status::Cached(CacheStatus::Ok(200)),
             InputSource::Stdin,
-            ResponseBody {
-                uri: Uri::try_from("http://127.0.0.1").unwrap(),
-                status: Status::Cached(CacheStatus::Ok(200)),
-            },
-        );
+        )
+        .with_attempts(1);
         let formatter: Arc<Box<dyn ResponseFormatter>> =
             Arc::new(Box::new(formatters::response::Raw::new()));
-        show_progress(&mut buf, &None, &response, &formatter, false).unwrap();
+        show_progress(&mut buf, &None, &response, Duration::ZERO, &formatter, 0).unwrap();
 
         println!("{:?}", String::from_utf8_lossy(&buf));
         assert!(buf.is_empty());
     }
+
+    #[test]
+    fn test_show_timings_in_progress_output() {
+        let formatter: Arc<Box<dyn ResponseFormatter>> =
+            Arc::new(Box::new(formatters::response::Raw::new()));
+        let ok = Response::new(
+            Uri::try_from("https://lychee.cli.rs/ok").unwrap(),
+            Status::Ok(http::StatusCode::OK),
+            InputSource::Stdin,
+        )
+        .with_timings(Some(Timings {
+            ttfb: Some(Duration::from_millis(80)),
+            ..Timings::default()
+        }));
+        let excluded = Response::new(
+            Uri::try_from("https://lychee.cli.rs/excluded").unwrap(),
+            Status::Excluded,
+            InputSource::Stdin,
+        );
+
+        let mut buf = Vec::new();
+        for (response, millis) in [(&ok, 120), (&excluded, 3)] {
+            let elapsed = Duration::from_millis(millis);
+            show_progress(&mut buf, &None, response, elapsed, &formatter, 2).unwrap();
+        }
+        assert_eq!(
+            String::from_utf8_lossy(&buf),
+            "\u{2714} [  120ms] https://lychee.cli.rs/ok (TTFB 80ms)\n\
+             ? [    3ms] https://lychee.cli.rs/excluded\n"
+        );
+
+        // Only the formatted response without the timing at lower levels
+        let mut buf = Vec::new();
+        show_progress(&mut buf, &None, &ok, Duration::ZERO, &formatter, 1).unwrap();
+        assert_eq!(String::from_utf8_lossy(&buf), format!("{}\n", ok.1));
+    }
+
+    #[test]
+    fn test_check_limit() {
+        let limit = CheckLimit::new(2);
+        assert!(limit.acquire());
+        assert!(limit.acquire());
+        assert!(!limit.acquire());
+        assert!(!CheckLimit::new(0).acquire());
+    }
+
+    #[tokio::test]
+    async fn test_exclusion_source() {
+        let cache = Arc::new(Cache::new());
+        let cached = Uri::try_from("https://lychee.cli.rs/cached").unwrap();
+        cache.insert(cached.clone(), (&Status::Excluded).into());
+        let client = lychee_lib::ClientBuilder::builder()
+            .excludes(regex::RegexSet::new([r"lychee\.cli\.rs/config"]).unwrap())
+            .build()
+            .client()
+            .unwrap();
+
+        let check = |uri: &str| {
+            let request = Request::new(Uri::try_from(uri).unwrap(), InputSource::Stdin, None, None);
+            handle(&client, cache.clone(), request, false, None, None)
+        };
+        let response = check("https://lychee.cli.rs/cached").await;
+        assert_eq!(
+            response.status().exclusion_source(),
+            Some(ExclusionSource::Cache)
+        );
+        let response = check("https://lychee.cli.rs/config").await;
+        assert_eq!(
+            response.status().exclusion_source(),
+            Some(ExclusionSource::Config)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cached_redirect() {
+        use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+        // The original link is gone, but the redirect target is still valid
+        let source = MockServer::start().await;
+        let target = MockServer::start().await;
+        Mock::given(path("/new"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&target)
+            .await;
+        let uri = Uri::try_from(format!("{}/old", source.uri())).unwrap();
+        let redirect = |target_path: &str| {
+            let target_uri = Uri::try_from(format!("{}{target_path}", target.uri())).unwrap();
+            let value = CacheValue::from(&Status::Ok(http::StatusCode::OK))
+                .with_redirect(Some((target_uri, timestamp())));
+            CacheValue {
+                expired: true,
+                ..value
+            }
+        };
+        let client = lychee_lib::ClientBuilder::builder()
+            .max_retries(0u64)
+            .build()
+            .client()
+            .unwrap();
+        let check = |cache: Arc<Cache>| {
+            let request = Request::new(uri.clone(), InputSource::Stdin, None, None);
+            handle(&client, cache, request, false, None, None)
+        };
+
+        let cache = Arc::new(Cache::new());
+        cache.insert(uri.clone(), redirect("/new"));
+        let response = check(cache.clone()).await;
+        assert_eq!(response.1.uri, uri);
+        assert_eq!(response.status(), &Status::Ok(http::StatusCode::OK));
+        assert!(source.received_requests().await.unwrap().is_empty());
+        // The redirect is kept for the next run
+        let value = cache.get(&uri).unwrap();
+        assert!(!value.expired);
+        assert!(value.redirect.as_ref().unwrap().as_str().ends_with("/new"));
+        drop(value);
+
+        // Invalid targets get ignored
+        let cache = Arc::new(Cache::new());
+        cache.insert(uri.clone(), redirect("/gone"));
+        let response = check(cache.clone()).await;
+        assert!(response.status().is_failure());
+        assert_eq!(source.received_requests().await.unwrap().len(), 1);
+        assert_eq!(cache.get(&uri).unwrap().redirect, None);
+    }
+
+    #[tokio::test]
+    async fn test_ignore_mail_case() {
+        let cache = Arc::new(Cache::new());
+        let checked = Uri::try_from("foo@example.com").unwrap();
+        cache.insert(
+            cache_key(&checked, Some(MailCase::Domain)),
+            (&Status::Ok(http::StatusCode::OK)).into(),
+        );
+        let client = lychee_lib::ClientBuilder::default().client().unwrap();
+
+        for address in ["foo@Example.COM", "foo@EXAMPLE.com"] {
+            let uri = Uri::try_from(address).unwrap();
+            let request = Request::new(uri.clone(), InputSource::Stdin, None, None);
+            let response = handle(
+                &client,
+                cache.clone(),
+                request,
+                false,
+                Some(MailCase::Domain),
+                None,
+            )
+            .await;
+            // Reported with the original casing
+            assert_eq!(response.1.uri, uri);
+            assert_eq!(response.status(), &Status::Cached(CacheStatus::Ok(200)));
+        }
+
+        // The local part is only compared case-insensitively on request
+        let other = Uri::try_from("Foo@example.com").unwrap();
+        assert_ne!(cache_key(&other, Some(MailCase::Domain)), checked);
+        assert_eq!(cache_key(&other, Some(MailCase::All)), checked);
+        assert_eq!(cache_key(&other, None), other);
+    }
 }
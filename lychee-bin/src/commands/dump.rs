@@ -16,7 +16,15 @@ where
     tokio::pin!(requests);
 
     while let Some(request) = requests.next().await {
-        let mut request = request?;
+        let mut request = match request {
+            Ok(request) => request,
+            // Invalid links or inputs don't stop the dump of the other links
+            Err(e) if e.input_source().is_some() => {
+                eprintln!("{e}");
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
 
         // Apply URI remappings (if any)
         request.uri = params.client.remap(request.uri)?;
@@ -27,7 +35,7 @@ where
         // to another program like `grep`.
 
         let excluded = params.client.is_excluded(&request.uri);
-        let verbose = params.cfg.verbose;
+        let verbose = params.cfg.verbose > 0;
 
         if excluded && !verbose {
             continue;
@@ -9,7 +9,7 @@ use crate::{
     options::{self, Format},
 };
 
-use self::response::ResponseFormatter;
+use self::{response::ResponseFormatter, stats::StatsFormatter};
 
 /// Detects whether a terminal supports color, and gives details about that
 /// support. It takes into account the `NO_COLOR` environment variable.
@@ -24,11 +24,14 @@ pub(crate) fn color_response(body: &ResponseBody) -> String {
             Status::Ok(_) | Status::Cached(CacheStatus::Ok(_)) => GREEN.apply_to(body),
             Status::Excluded
             | Status::Unsupported(_)
+            | Status::Skipped(_)
             | Status::Cached(CacheStatus::Excluded | CacheStatus::Unsupported) => {
                 DIM.apply_to(body)
             }
             Status::Redirected(_) => NORMAL.apply_to(body),
-            Status::UnknownStatusCode(_) | Status::Timeout(_) => YELLOW.apply_to(body),
+            Status::UnknownStatusCode(_) | Status::Timeout(_) | Status::Warning(_) => {
+                YELLOW.apply_to(body)
+            }
             Status::Error(_) | Status::Cached(CacheStatus::Error(_)) => PINK.apply_to(body),
         };
         out.to_string()
@@ -44,3 +47,18 @@ pub(crate) fn get_formatter(format: &options::Format) -> Box<dyn ResponseFormatt
     }
     Box::new(response::Color::new())
 }
+
+/// Create a stats formatter based on the given format option
+pub(crate) fn get_stats_formatter(format: &options::Format) -> Box<dyn StatsFormatter> {
+    match format {
+        Format::Compact => Box::new(stats::Compact::new()),
+        Format::Csv => Box::new(stats::Csv::new()),
+        Format::Detailed => Box::new(stats::Detailed::new()),
+        Format::Html => Box::new(stats::Html::new()),
+        Format::Json => Box::new(stats::Json::new()),
+        Format::Markdown => Box::new(stats::Markdown::new()),
+        Format::Raw => Box::new(stats::Raw::new()),
+        Format::Redirects => Box::new(stats::Redirects::new()),
+        Format::Unique => Box::new(stats::Unique::new()),
+    }
+}
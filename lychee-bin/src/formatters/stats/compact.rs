@@ -70,9 +70,20 @@ impl Display for CompactResponseStats {
             write!(f, " ")?;
             color!(f, PINK, "({})", print_errors(stats))?;
         }
+        if stats.warnings > 0 {
+            let warn_str = if stats.warnings == 1 {
+                "Warning"
+            } else {
+                "Warnings"
+            };
+            color!(f, BOLD_YELLOW, " \u{26a0} {} {}", stats.warnings, warn_str)?;
+        }
         if stats.excludes > 0 {
             color!(f, BOLD_YELLOW, " \u{1F4A4} {} Excluded", stats.excludes)?;
         }
+        if stats.skipped > 0 {
+            color!(f, BOLD_PINK, " \u{23ed} {} Skipped", stats.skipped)?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use lychee_lib::{InputSource, ResponseBody};
+use serde::Serialize;
+
+use super::StatsFormatter;
+use crate::stats::ResponseStats;
+
+/// A row of the CSV report
+#[derive(Serialize)]
+struct Row<'a> {
+    source: &'a InputSource,
+    uri: String,
+    /// Category of the status, e.g. `Failed` or `Timeout`
+    status: String,
+    code: String,
+    message: String,
+}
+
+impl<'a> Row<'a> {
+    fn new(source: &'a InputSource, response: &ResponseBody) -> Self {
+        let status = &response.status;
+        let message = match status.error() {
+            Some(e) => match e.details() {
+                Some(details) => format!("{e}: {details}"),
+                None => e.to_string(),
+            },
+            None => status.to_string(),
+        };
+        Self {
+            source,
+            uri: response.uri.to_string(),
+            status: format!("{status:#}"),
+            code: status.code(),
+            message,
+        }
+    }
+}
+
+/// Print the failing links as CSV with one row per link and input, e.g. to
+/// open the report in a spreadsheet
+pub(crate) struct Csv;
+
+impl Csv {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+}
+
+impl StatsFormatter for Csv {
+    fn format_stats(&self, stats: ResponseStats) -> Result<Option<String>> {
+        let mut rows: Vec<Row> = stats
+            .fail_map
+            .iter()
+            .flat_map(|(source, responses)| responses.iter().map(|r| Row::new(source, r)))
+            .collect();
+        rows.sort_by(|a, b| (a.source.to_string(), &a.uri).cmp(&(b.source.to_string(), &b.uri)));
+
+        // The header is written with the first row, so write it explicitly
+        // in case there are no failures
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(vec![]);
+        writer.write_record(["source", "uri", "status", "code", "message"])?;
+        for row in rows {
+            writer.serialize(row)?;
+        }
+        let csv = writer.into_inner().context("Cannot format stats as CSV")?;
+        Ok(Some(String::from_utf8(csv)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lychee_lib::{ErrorKind, Response, Status, Uri};
+
+    use super::*;
+
+    #[test]
+    fn test_csv_escaping() {
+        let mut stats = ResponseStats::new();
+        stats.add(
+            Response::new(
+                Uri::try_from("https://example.com/").unwrap(),
+                Status::Error(ErrorKind::InvalidBase(
+                    "docs, old".to_string(),
+                    "Say \"hello\"".to_string(),
+                )),
+                InputSource::Stdin,
+            )
+            .with_attempts(1),
+        );
+
+        let csv = Csv::new().format_stats(stats).unwrap().unwrap();
+        assert_eq!(
+            csv,
+            "source,uri,status,code,message\n\
+             stdin,https://example.com/,Failed,ERR,\"Error with base dir `docs, old` : Say \"\"hello\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_no_failures() {
+        let csv = Csv::new()
+            .format_stats(ResponseStats::new())
+            .unwrap()
+            .unwrap();
+        assert_eq!(csv, "source,uri,status,code,message\n");
+    }
+}
@@ -36,6 +36,8 @@ impl Display for DetailedResponseStats {
         write_stat(f, "\u{23f3} Timeouts", stats.timeouts, true)?; // ⏳
         write_stat(f, "\u{1f500} Redirected", stats.redirects, true)?; // 🔀
         write_stat(f, "\u{1f47b} Excluded", stats.excludes, true)?; // 👻
+        write_stat(f, "\u{26a0} Warnings", stats.warnings, true)?; // ⚠
+        write_stat(f, "\u{23ed} Skipped", stats.skipped, true)?; // ⏭
         write_stat(f, "\u{2753} Unknown", stats.unknown, true)?; //❓
         write_stat(f, "\u{1f6ab} Errors", stats.errors + stats.failures, false)?; // 🚫
 
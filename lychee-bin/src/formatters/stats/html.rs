@@ -0,0 +1,135 @@
+use std::fmt::{self, Display, Write};
+
+use super::StatsFormatter;
+use anyhow::Result;
+
+use crate::stats::ResponseStats;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em auto; max-width: 60em; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }
+li { margin: 0.2em 0; }";
+
+/// Escape text for HTML element content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn stats_table(stats: &ResponseStats) -> String {
+    let rows = [
+        ("\u{1f50d} Total", stats.total),
+        ("\u{2705} Successful", stats.successful),
+        ("\u{23f3} Timeouts", stats.timeouts),
+        ("\u{1f500} Redirected", stats.redirects),
+        ("\u{1f47b} Excluded", stats.excludes),
+        ("\u{26a0} Warnings", stats.warnings),
+        ("\u{23ed} Skipped", stats.skipped),
+        ("\u{2753} Unknown", stats.unknown),
+        ("\u{1f6ab} Errors", stats.errors + stats.failures),
+    ];
+    let mut table = String::from("<table>\n<tr><th>Status</th><th>Count</th></tr>\n");
+    for (status, count) in rows {
+        // Writing to a `String` can't fail
+        let _ = writeln!(table, "<tr><td>{status}</td><td>{count}</td></tr>");
+    }
+    table.push_str("</table>");
+    table
+}
+
+/// Self-contained HTML page of the stats, e.g. to browse the results of a
+/// CI job or to serve them with `--serve`
+pub(crate) struct HtmlResponseStats<'a>(pub(crate) &'a ResponseStats);
+
+impl Display for HtmlResponseStats<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = self.0;
+
+        writeln!(f, "<!DOCTYPE html>")?;
+        writeln!(f, "<html lang=\"en\">")?;
+        writeln!(f, "<head>")?;
+        writeln!(f, "<meta charset=\"utf-8\">")?;
+        writeln!(f, "<title>lychee report</title>")?;
+        writeln!(f, "<style>\n{STYLE}\n</style>")?;
+        writeln!(f, "</head>")?;
+        writeln!(f, "<body>")?;
+        writeln!(f, "<h1>Summary</h1>")?;
+        writeln!(f, "{}", stats_table(stats))?;
+
+        if !stats.fail_map.is_empty() {
+            writeln!(f, "<h2>Errors per input</h2>")?;
+            let mut sources: Vec<_> = stats.fail_map.iter().collect();
+            sources.sort_by_cached_key(|(source, _)| source.to_string());
+            for (source, responses) in sources {
+                writeln!(f, "<h3>Errors in {}</h3>", escape(&source.to_string()))?;
+                writeln!(f, "<ul>")?;
+                let mut responses: Vec<_> = responses.iter().collect();
+                responses.sort_by(|a, b| a.uri.cmp(&b.uri));
+                for response in responses {
+                    let uri = escape(response.uri.as_str());
+                    writeln!(
+                        f,
+                        "<li><a href=\"{uri}\">{uri}</a>: {} (status code: {})</li>",
+                        escape(&response.status.to_string()),
+                        response.status.code()
+                    )?;
+                }
+                writeln!(f, "</ul>")?;
+            }
+        }
+
+        writeln!(f, "</body>")?;
+        write!(f, "</html>")
+    }
+}
+
+pub(crate) struct Html;
+
+impl Html {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+}
+
+impl StatsFormatter for Html {
+    fn format_stats(&self, stats: ResponseStats) -> Result<Option<String>> {
+        Ok(Some(HtmlResponseStats(&stats).to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lychee_lib::{ErrorKind, InputSource, Response, Status, Uri};
+
+    use super::*;
+
+    #[test]
+    fn test_render_html() {
+        let mut stats = ResponseStats::new();
+        stats.add(Response::new(
+            Uri::try_from("https://example.com/?a=1&b=<2>").unwrap(),
+            Status::Error(ErrorKind::InvalidUrlHost),
+            InputSource::String("<a>".to_string()),
+        ));
+        let html = HtmlResponseStats(&stats).to_string();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.ends_with("</html>"));
+        assert!(html.contains("<tr><td>\u{1f50d} Total</td><td>1</td></tr>"));
+        assert!(html.contains("<tr><td>\u{1f6ab} Errors</td><td>1</td></tr>"));
+        assert!(html.contains(
+            "<a href=\"https://example.com/?a=1&amp;b=%3C2%3E\">https://example.com/?a=1&amp;b=%3C2%3E</a>"
+        ));
+        assert!(!html.contains("<a>"));
+    }
+}
@@ -36,6 +36,14 @@ fn stats_table(stats: &ResponseStats) -> String {
             status: "\u{1f47b} Excluded",
             count: stats.excludes,
         },
+        StatsTableEntry {
+            status: "\u{26a0} Warnings",
+            count: stats.warnings,
+        },
+        StatsTableEntry {
+            status: "\u{23ed} Skipped",
+            count: stats.skipped,
+        },
         StatsTableEntry {
             status: "\u{2753} Unknown",
             count: stats.unknown,
@@ -105,7 +113,7 @@ impl StatsFormatter for Markdown {
 
 #[cfg(test)]
 mod tests {
-    use lychee_lib::{CacheStatus, InputSource, Response, ResponseBody, Status, Uri};
+    use lychee_lib::{CacheStatus, InputSource, Response, Status, Uri};
 
     use super::*;
 
@@ -120,6 +128,8 @@ mod tests {
 | ⏳ Timeouts   | 0     |
 | 🔀 Redirected | 0     |
 | 👻 Excluded   | 0     |
+| ⚠ Warnings    | 0     |
+| ⏭ Skipped     | 0     |
 | ❓ Unknown    | 0     |
 | 🚫 Errors     | 0     |
 "#;
@@ -129,13 +139,12 @@ mod tests {
     #[test]
     fn test_render_summary() {
         let mut stats = ResponseStats::new();
-        let response = Response(
+        let response = Response::new(
+            Uri::try_from("http://127.0.0.1").unwrap(),
+            Status::Cached(CacheStatus::Error(Some(404))),
             InputSource::Stdin,
-            ResponseBody {
-                uri: Uri::try_from("http://127.0.0.1").unwrap(),
-                status: Status::Cached(CacheStatus::Error(Some(404))),
-            },
-        );
+        )
+        .with_attempts(1);
         stats.add(response);
         let summary = MarkdownResponseStats(stats);
         let expected = r#"## Summary
@@ -147,6 +156,8 @@ mod tests {
 | ⏳ Timeouts   | 0     |
 | 🔀 Redirected | 0     |
 | 👻 Excluded   | 0     |
+| ⚠ Warnings    | 0     |
+| ⏭ Skipped     | 0     |
 | ❓ Unknown    | 0     |
 | 🚫 Errors     | 1     |
 
@@ -1,19 +1,98 @@
 mod compact;
+mod csv;
 mod detailed;
+mod html;
 mod json;
 mod markdown;
 mod raw;
+mod redirects;
+mod unique;
 
 pub(crate) use compact::Compact;
+pub(crate) use csv::Csv;
 pub(crate) use detailed::Detailed;
+pub(crate) use html::{Html, HtmlResponseStats};
 pub(crate) use json::Json;
 pub(crate) use markdown::Markdown;
 pub(crate) use raw::Raw;
+pub(crate) use redirects::Redirects;
+pub(crate) use unique::Unique;
 
 use crate::stats::ResponseStats;
 use anyhow::Result;
 
+/// Output format for the final report of a link check
+///
+/// The stats include the results of all checked links, so a formatter has
+/// access to everything needed for a complete report. To add a new format,
+/// implement this trait and register it in [`crate::options::Format`] and
+/// [`crate::formatters::get_stats_formatter`], which makes it selectable by
+/// name with `--format`.
 pub(crate) trait StatsFormatter {
     /// Format the stats of all responses and write them to stdout
     fn format_stats(&self, stats: ResponseStats) -> Result<Option<String>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use lychee_lib::{InputSource, Response, Status, Uri};
+
+    use super::*;
+    use crate::{formatters::get_stats_formatter, options::Format};
+
+    /// Minimal custom formatter, which only prints the number of links
+    struct Total;
+
+    impl StatsFormatter for Total {
+        fn format_stats(&self, stats: ResponseStats) -> Result<Option<String>> {
+            Ok(Some(format!("{} links", stats.total)))
+        }
+    }
+
+    fn stats() -> ResponseStats {
+        let mut stats = ResponseStats::new();
+        stats.add(Response::new(
+            Uri::try_from("https://example.com").unwrap(),
+            Status::Excluded,
+            InputSource::Stdin,
+        ));
+        stats
+    }
+
+    #[test]
+    fn test_custom_formatter() {
+        let formatter: Box<dyn StatsFormatter> = Box::new(Total);
+        assert_eq!(
+            formatter.format_stats(stats()).unwrap(),
+            Some("1 links".to_string())
+        );
+    }
+
+    #[test]
+    fn test_select_formatter_by_name() {
+        let formatter = get_stats_formatter(&Format::from_str("json").unwrap());
+        let json = formatter.format_stats(stats()).unwrap().unwrap();
+        assert_eq!(
+            json,
+            r#"{
+  "total": 1,
+  "successful": 0,
+  "failures": 0,
+  "unknown": 0,
+  "timeouts": 0,
+  "redirects": 0,
+  "excludes": 1,
+  "warnings": 0,
+  "skipped": 0,
+  "errors": 0,
+  "cached": 0,
+  "fail_map": {}
+}"#
+        );
+
+        let formatter = get_stats_formatter(&Format::from_str("raw").unwrap());
+        assert_eq!(formatter.format_stats(stats()).unwrap(), None);
+    }
+}
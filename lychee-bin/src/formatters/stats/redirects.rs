@@ -0,0 +1,110 @@
+use std::fmt::{self, Display};
+
+use super::StatsFormatter;
+use crate::{
+    color::{color, BOLD_YELLOW},
+    stats::ResponseStats,
+};
+
+use anyhow::Result;
+
+/// Wrap as newtype because multiple `Display` implementations are not allowed
+/// for `ResponseStats`
+struct RedirectResponseStats(ResponseStats);
+
+impl Display for RedirectResponseStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redirects = &self.0.redirect_map;
+        if redirects.is_empty() {
+            return write!(f, "No redirects found");
+        }
+
+        let uri = if redirects.len() == 1 { "URI" } else { "URIs" };
+        color!(f, BOLD_YELLOW, "{} redirected {uri}", redirects.len())?;
+        for (original, target) in redirects {
+            write!(f, "\n{original} \u{2192} {target}")?; // →
+        }
+        Ok(())
+    }
+}
+
+/// Only print links which got redirected, along with the URL they ended up at,
+/// e.g. to update outdated links
+pub(crate) struct Redirects;
+
+impl Redirects {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+}
+
+impl StatsFormatter for Redirects {
+    fn format_stats(&self, stats: ResponseStats) -> Result<Option<String>> {
+        let redirects = RedirectResponseStats(stats);
+        Ok(Some(redirects.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use lychee_lib::{ErrorKind, InputSource, Response, Status, Uri};
+
+    use super::*;
+
+    fn response(uri: &str, status: Status) -> Response {
+        Response::new(Uri::try_from(uri).unwrap(), status, InputSource::Stdin).with_attempts(1)
+    }
+
+    fn redirect(target: &str) -> Status {
+        Status::Warning(Box::new(ErrorKind::Redirect(
+            Uri::try_from(target).unwrap(),
+        )))
+    }
+
+    #[test]
+    fn test_only_redirects() {
+        let mut stats = ResponseStats::new();
+        stats.add(response(
+            "https://example.com/ok",
+            Status::Ok(StatusCode::OK),
+        ));
+        stats.add(response(
+            "https://example.com/missing",
+            Status::Error(ErrorKind::InvalidUrlHost),
+        ));
+        stats.add(response(
+            "https://example.com/old",
+            redirect("https://example.com/new"),
+        ));
+        stats.add(response("https://example.com/excluded", Status::Excluded));
+        stats.add(response(
+            "http://example.org/",
+            Status::Error(ErrorKind::CrossHostRedirect(
+                Uri::try_from("https://parked.example.net/").unwrap(),
+            )),
+        ));
+
+        let report = Redirects::new().format_stats(stats).unwrap().unwrap();
+        let lines: Vec<_> = report.lines().skip(1).collect();
+        assert!(report.contains("2 redirected URIs"));
+        assert_eq!(
+            lines,
+            vec![
+                "http://example.org/ \u{2192} https://parked.example.net/",
+                "https://example.com/old \u{2192} https://example.com/new",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_redirects() {
+        let mut stats = ResponseStats::new();
+        stats.add(response(
+            "https://example.com/ok",
+            Status::Ok(StatusCode::OK),
+        ));
+        let report = Redirects::new().format_stats(stats).unwrap();
+        assert_eq!(report, Some("No redirects found".to_string()));
+    }
+}
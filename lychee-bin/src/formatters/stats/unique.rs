@@ -0,0 +1,51 @@
+use std::fmt::{self, Display};
+
+use super::StatsFormatter;
+use crate::{
+    color::{color, BOLD_PINK},
+    formatters::color_response,
+    stats::ResponseStats,
+};
+
+use anyhow::Result;
+
+/// Wrap as newtype because multiple `Display` implementations are not allowed
+/// for `ResponseStats`
+struct UniqueResponseStats(ResponseStats);
+
+impl Display for UniqueResponseStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failures = self.0.unique_failures();
+        if failures.is_empty() {
+            return write!(f, "No failing URIs found");
+        }
+
+        let uri = if failures.len() == 1 { "URI" } else { "URIs" };
+        color!(f, BOLD_PINK, "{} unique failing {uri}", failures.len())?;
+        for (response, count) in failures {
+            let references = if count == 1 {
+                "reference"
+            } else {
+                "references"
+            };
+            write!(f, "\n{} ({count} {references})", color_response(response))?;
+        }
+        Ok(())
+    }
+}
+
+/// Print each failing URI only once, no matter how many inputs reference it
+pub(crate) struct Unique;
+
+impl Unique {
+    pub(crate) const fn new() -> Self {
+        Self {}
+    }
+}
+
+impl StatsFormatter for Unique {
+    fn format_stats(&self, stats: ResponseStats) -> Result<Option<String>> {
+        let unique = UniqueResponseStats(stats);
+        Ok(Some(unique.to_string()))
+    }
+}
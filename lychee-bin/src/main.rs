@@ -59,7 +59,8 @@
 #![deny(missing_docs)]
 
 use std::fs::{self, File};
-use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::{Context, Error, Result};
@@ -71,7 +72,8 @@ use openssl_sys as _; // required for vendored-openssl feature
 use ring as _; // required for apple silicon
 use structopt::StructOpt;
 
-use lychee_lib::Collector;
+use futures::StreamExt;
+use lychee_lib::{Collector, InputContent};
 
 mod cache;
 mod client;
@@ -80,14 +82,16 @@ mod commands;
 mod formatters;
 mod options;
 mod parse;
+mod serve;
 mod stats;
+mod suggestions;
 mod time;
 
 use crate::{
     cache::{Cache, StoreExt},
     color::color,
-    formatters::stats::StatsFormatter,
-    options::{Config, Format, LycheeOptions, LYCHEE_CACHE_FILE, LYCHEE_IGNORE_FILE},
+    options::{Config, LycheeOptions, LYCHEE_CACHE_DIR, LYCHEE_CACHE_FILE, LYCHEE_IGNORE_FILE},
+    stats::ResponseStats,
 };
 
 /// A C-like enum that can be cast to `i32` and used as process exit code.
@@ -116,14 +120,15 @@ fn main() -> Result<()> {
     std::process::exit(exit_code);
 }
 
-/// Read lines from file; ignore empty lines
+/// Read lines from file; ignore blank lines and comments. Surrounding
+/// whitespace gets removed, as it's easy to miss in a pattern.
 fn read_lines(file: &File) -> Result<Vec<String>> {
     let lines: Vec<_> = BufReader::new(file).lines().collect::<Result<_, _>>()?;
     Ok(lines
-        .into_iter()
-        .filter(|line| {
-            !line.is_empty() && !line.trim_start().starts_with(LYCHEEINGORE_COMMENT_MARKER)
-        })
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with(LYCHEEINGORE_COMMENT_MARKER))
+        .map(ToString::to_string)
         .collect())
 }
 
@@ -131,6 +136,10 @@ fn read_lines(file: &File) -> Result<Vec<String>> {
 /// file, command-line- and environment variables
 fn load_config() -> Result<LycheeOptions> {
     let mut opts = LycheeOptions::from_args();
+    // Like other CLI options, tokens from the environment take precedence
+    // over the config file
+    opts.config
+        .resolve_github_token(|name| std::env::var(name).ok());
 
     // Load a potentially existing config file and merge it into the config from
     // the CLI
@@ -165,9 +174,26 @@ fn load_cache(cfg: &Config) -> Option<Cache> {
         return None;
     }
 
-    // Discard entire cache if it hasn't been updated since `max_cache_age`.
+    let max_redirect_age = cfg.cache_redirects.unwrap_or_default();
+    if cfg.cache_shards.is_some() {
+        // Shards get rewritten in place, so the directory doesn't tell the
+        // age of the cache. Old entries are discarded while loading instead.
+        if !Path::new(LYCHEE_CACHE_DIR).is_dir() {
+            return None;
+        }
+        let cache = Cache::load_sharded(
+            LYCHEE_CACHE_DIR,
+            cfg.max_cache_age.as_secs(),
+            max_redirect_age.as_secs(),
+        );
+        return cache_or_warn(cache);
+    }
+
+    // Discard entire cache if it hasn't been updated since `max_cache_age`
+    // (or the maximum age of redirects, if longer).
     // This is an optimization, which avoids iterating over the file and
     // checking the age of each entry.
+    let max_age = cfg.max_cache_age.max(max_redirect_age);
     match fs::metadata(LYCHEE_CACHE_FILE) {
         Err(_e) => {
             // No cache found; silently start with empty cache
@@ -176,18 +202,27 @@ fn load_cache(cfg: &Config) -> Option<Cache> {
         Ok(metadata) => {
             let modified = metadata.modified().ok()?;
             let elapsed = modified.elapsed().ok()?;
-            if elapsed > cfg.max_cache_age {
+            if elapsed > max_age {
                 eprintln!(
                     "Cache is too old (age: {}, max age: {}). Discarding",
                     humantime::format_duration(elapsed),
-                    humantime::format_duration(cfg.max_cache_age)
+                    humantime::format_duration(max_age)
                 );
                 return None;
             }
         }
     }
 
-    let cache = Cache::load(LYCHEE_CACHE_FILE, cfg.max_cache_age.as_secs());
+    let cache = Cache::load(
+        LYCHEE_CACHE_FILE,
+        cfg.max_cache_age.as_secs(),
+        max_redirect_age.as_secs(),
+    );
+    cache_or_warn(cache)
+}
+
+/// Continue without a cache if it can't be loaded
+fn cache_or_warn(cache: Result<Cache>) -> Option<Cache> {
     match cache {
         Ok(cache) => Some(cache),
         Err(e) => {
@@ -234,17 +269,88 @@ fn underlying_io_error_kind(error: &Error) -> Option<io::ErrorKind> {
     None
 }
 
+/// Read a unified diff from a file or from stdin (`-`)
+fn read_diff(path: &Path) -> Result<String> {
+    if path == Path::new("-") {
+        let mut diff = String::new();
+        io::stdin()
+            .read_to_string(&mut diff)
+            .context("Cannot read diff from stdin")?;
+        Ok(diff)
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Cannot read diff {}", path.display()))
+    }
+}
+
+/// Format the stats of a run and write them to stdout or the output file
+fn write_stats(cfg: &Config, stats: ResponseStats) -> Result<()> {
+    let writer = formatters::get_stats_formatter(&cfg.format);
+    let is_empty = stats.is_empty();
+    let formatted = writer.format_stats(stats)?;
+
+    if let Some(formatted) = formatted {
+        if let Some(output) = &cfg.output {
+            fs::write(output, formatted).context("Cannot write status output to file")?;
+        } else {
+            if cfg.verbose > 0 && !is_empty {
+                // separate summary from the verbose list of links above
+                // with a newline
+                writeln!(io::stdout())?;
+            }
+            // we assume that the formatted stats don't have a final newline
+            writeln!(io::stdout(), "{formatted}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Merge the JSON reports of previous runs instead of checking links
+fn merge_reports(opts: &LycheeOptions) -> Result<ExitCode> {
+    let stats = stats::merge_reports(&opts.merge_reports)?;
+    let exit_code = if stats.is_success(opts.config.timeout_as_success) {
+        ExitCode::Success
+    } else {
+        ExitCode::LinkCheckFailure
+    };
+    write_stats(&opts.config, stats)?;
+    Ok(exit_code)
+}
+
 /// Run lychee on the given inputs
 async fn run(opts: &LycheeOptions) -> Result<i32> {
-    let inputs = opts.inputs()?;
-    let requests = Collector::new(opts.config.base.clone())
+    client::validate(&opts.config)?;
+    if opts.validate {
+        println!("Configuration is valid");
+        return Ok(ExitCode::Success as i32);
+    }
+    if !opts.merge_reports.is_empty() {
+        return Ok(merge_reports(opts)? as i32);
+    }
+
+    let client = client::create(&opts.config)?;
+    let collector = Collector::new(opts.config.base.clone())
         .skip_missing_inputs(opts.config.skip_missing)
         .include_verbatim(opts.config.include_verbatim)
+        .deny_empty_urls(opts.config.deny_empty_urls)
+        .include_anchors(opts.config.exclude_self_links)
+        .strip_query_params(opts.config.strip_query_param.clone())
+        .trailing_slash(opts.config.trailing_slash.map(Into::into))
+        .variables(parse::parse_variables(&opts.config.var, parse::env_var)?)
+        .crawl(opts.config.crawl_depth, client.clone())
         // File a bug if you rely on this envvar! It's going to go away eventually.
-        .use_html5ever(std::env::var("LYCHEE_USE_HTML5EVER").map_or(false, |x| x == "1"))
-        .collect_links(inputs)
-        .await;
-    let client = client::create(&opts.config)?;
+        .use_html5ever(std::env::var("LYCHEE_USE_HTML5EVER").map_or(false, |x| x == "1"));
+    let requests = match &opts.diff {
+        Some(diff) => {
+            let contents = InputContent::from_diff(&read_diff(diff)?);
+            collector.collect_links_from_contents(contents).boxed()
+        }
+        None => collector.collect_links(opts.inputs()?).await.boxed(),
+    };
+    // Bind early, so that an unavailable address doesn't waste a whole run
+    let listener = match opts.serve {
+        Some(addr) => Some(serve::bind(addr).await?),
+        None => None,
+    };
     let cache = load_cache(&opts.config).unwrap_or_default();
     let cache = Arc::new(cache);
 
@@ -269,38 +375,49 @@ async fn run(opts: &LycheeOptions) -> Result<i32> {
             .values()
             .flatten()
             .any(|body| body.uri.domain() == Some("github.com"));
+        let auth_issues = stats
+            .fail_map
+            .values()
+            .flatten()
+            .any(|body| body.status.is_auth_required());
 
-        let writer: Box<dyn StatsFormatter> = match opts.config.format {
-            Format::Compact => Box::new(formatters::stats::Compact::new()),
-            Format::Detailed => Box::new(formatters::stats::Detailed::new()),
-            Format::Json => Box::new(formatters::stats::Json::new()),
-            Format::Markdown => Box::new(formatters::stats::Markdown::new()),
-            Format::Raw => Box::new(formatters::stats::Raw::new()),
-        };
-        let is_empty = stats.is_empty();
-        let formatted = writer.format_stats(stats)?;
-
-        if let Some(formatted) = formatted {
-            if let Some(output) = &opts.config.output {
-                fs::write(output, formatted).context("Cannot write status output to file")?;
-            } else {
-                if opts.config.verbose && !is_empty {
-                    // separate summary from the verbose list of links above
-                    // with a newline
-                    writeln!(io::stdout())?;
-                }
-                // we assume that the formatted stats don't have a final newline
-                writeln!(io::stdout(), "{formatted}")?;
-            }
+        if let Some(path) = &opts.config.suggestions {
+            let suggestions = serde_json::to_string_pretty(&stats.suggestions)?;
+            fs::write(path, suggestions).context("Cannot write suggestions to file")?;
         }
 
+        let report = listener
+            .is_some()
+            .then(|| serve::Report::new(&stats))
+            .transpose()?;
+        write_stats(&opts.config, stats)?;
+
         if github_issues && opts.config.github_token.is_none() {
             let mut f = io::stdout();
             color!(f, YELLOW, "\u{1f4a1} There were issues with Github URLs. You could try setting a Github token and running lychee again.",)?;
         }
 
+        if auth_issues && opts.config.basic_auth.is_none() {
+            let mut f = io::stdout();
+            color!(f, YELLOW, "\u{1f4a1} Some links require authentication (401/407). You could try setting credentials with `--basic-auth` or `--header` and running lychee again.",)?;
+        }
+
         if opts.config.cache {
-            cache.store(LYCHEE_CACHE_FILE)?;
+            match opts.config.cache_shards {
+                Some(shards) => cache.store_sharded(LYCHEE_CACHE_DIR, shards)?,
+                None => cache.store(LYCHEE_CACHE_FILE)?,
+            }
+        }
+
+        if let (Some(listener), Some(report)) = (listener, report) {
+            println!(
+                "Serving the report at http://{} (press Ctrl-C to stop)",
+                listener.local_addr()?
+            );
+            tokio::select! {
+                result = serve::serve(listener, report) => result?,
+                _ = tokio::signal::ctrl_c() => {}
+            }
         }
         exit_code
     };
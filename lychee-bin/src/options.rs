@@ -2,20 +2,29 @@ use crate::parse::{parse_base, parse_statuscodes};
 use anyhow::{anyhow, Context, Error, Result};
 use const_format::{concatcp, formatcp};
 use lychee_lib::{
-    Base, Input, DEFAULT_MAX_REDIRECTS, DEFAULT_MAX_RETRIES, DEFAULT_RETRY_WAIT_TIME_SECS,
-    DEFAULT_TIMEOUT_SECS, DEFAULT_USER_AGENT,
+    Base, FileType, Input, RetryJitter, TrailingSlash, DEFAULT_MAX_CONCURRENCY,
+    DEFAULT_MAX_REDIRECTS, DEFAULT_MAX_RETRIES, DEFAULT_RETRY_WAIT_TIME_SECS, DEFAULT_TIMEOUT_SECS,
+    DEFAULT_USER_AGENT,
 };
 use secrecy::{ExposeSecret, SecretString};
-use serde::Deserialize;
-use std::{collections::HashSet, fs, io::ErrorKind, path::PathBuf, str::FromStr, time::Duration};
+use serde::{Deserialize, Deserializer};
+use std::{
+    collections::HashSet,
+    fs,
+    io::{ErrorKind, Read},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 use structopt::StructOpt;
 
 pub(crate) const LYCHEE_IGNORE_FILE: &str = ".lycheeignore";
 pub(crate) const LYCHEE_CACHE_FILE: &str = ".lycheecache";
+pub(crate) const LYCHEE_CACHE_DIR: &str = ".lycheecache.d";
 
 const DEFAULT_METHOD: &str = "get";
 const DEFAULT_MAX_CACHE_AGE: &str = "1d";
-const DEFAULT_MAX_CONCURRENCY: usize = 128;
 
 // this exists because structopt requires `&str` type values for defaults
 // whereas serde expects owned `String` types
@@ -34,10 +43,14 @@ const RETRY_WAIT_TIME_STR: &str = concatcp!(DEFAULT_RETRY_WAIT_TIME_SECS);
 #[derive(Debug, Deserialize, Clone)]
 pub(crate) enum Format {
     Compact,
+    Csv,
     Detailed,
+    Html,
     Json,
     Markdown,
     Raw,
+    Redirects,
+    Unique,
 }
 
 impl FromStr for Format {
@@ -45,10 +58,14 @@ impl FromStr for Format {
     fn from_str(format: &str) -> Result<Self, Self::Err> {
         match format.to_lowercase().as_str() {
             "compact" | "string" => Ok(Format::Compact),
+            "csv" => Ok(Format::Csv),
             "detailed" => Ok(Format::Detailed),
+            "html" => Ok(Format::Html),
             "json" => Ok(Format::Json),
             "markdown" | "md" => Ok(Format::Markdown),
             "raw" => Ok(Format::Raw),
+            "redirects" => Ok(Format::Redirects),
+            "unique" => Ok(Format::Unique),
             _ => Err(anyhow!("Unknown format {}", format)),
         }
     }
@@ -60,6 +77,160 @@ impl Default for Format {
     }
 }
 
+/// Which parts of mail addresses to compare case-insensitively
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum MailCase {
+    /// Only the domain (after the `@`)
+    Domain,
+    /// The whole address, including the local part (before the `@`)
+    All,
+}
+
+impl FromStr for MailCase {
+    type Err = Error;
+    fn from_str(mail_case: &str) -> Result<Self, Self::Err> {
+        match mail_case.to_lowercase().as_str() {
+            "domain" => Ok(MailCase::Domain),
+            "all" => Ok(MailCase::All),
+            _ => Err(anyhow!("Unknown mail case {}", mail_case)),
+        }
+    }
+}
+
+/// Which form of website links with or without a trailing slash is
+/// canonical
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SlashPolicy {
+    /// With a trailing slash
+    Add,
+    /// Without a trailing slash
+    Remove,
+}
+
+impl FromStr for SlashPolicy {
+    type Err = Error;
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy.to_lowercase().as_str() {
+            "add" => Ok(SlashPolicy::Add),
+            "remove" => Ok(SlashPolicy::Remove),
+            _ => Err(anyhow!("Unknown trailing slash policy {}", policy)),
+        }
+    }
+}
+
+impl From<SlashPolicy> for TrailingSlash {
+    fn from(policy: SlashPolicy) -> Self {
+        match policy {
+            SlashPolicy::Add => TrailingSlash::Add,
+            SlashPolicy::Remove => TrailingSlash::Remove,
+        }
+    }
+}
+
+/// Input format which overrides the file types detected from the extensions
+/// of the inputs
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub(crate) enum InputFormat {
+    Html,
+    Markdown,
+    Notebook,
+    Email,
+    AsciiDoc,
+    Xml,
+    Feed,
+    Plaintext,
+}
+
+impl FromStr for InputFormat {
+    type Err = Error;
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "html" => Ok(InputFormat::Html),
+            "markdown" | "md" => Ok(InputFormat::Markdown),
+            "notebook" | "ipynb" => Ok(InputFormat::Notebook),
+            "email" | "eml" => Ok(InputFormat::Email),
+            "asciidoc" | "adoc" => Ok(InputFormat::AsciiDoc),
+            "xml" => Ok(InputFormat::Xml),
+            "feed" | "rss" | "atom" | "opml" => Ok(InputFormat::Feed),
+            "plaintext" | "text" | "txt" => Ok(InputFormat::Plaintext),
+            _ => Err(anyhow!("Unknown input format {}", format)),
+        }
+    }
+}
+
+impl TryFrom<String> for InputFormat {
+    type Error = Error;
+    fn try_from(format: String) -> Result<Self, Self::Error> {
+        format.parse()
+    }
+}
+
+impl From<InputFormat> for FileType {
+    fn from(format: InputFormat) -> Self {
+        match format {
+            InputFormat::Html => FileType::Html,
+            InputFormat::Markdown => FileType::Markdown,
+            InputFormat::Notebook => FileType::Notebook,
+            InputFormat::Email => FileType::Email,
+            InputFormat::AsciiDoc => FileType::AsciiDoc,
+            InputFormat::Xml => FileType::Xml,
+            InputFormat::Feed => FileType::Feed,
+            InputFormat::Plaintext => FileType::Plaintext,
+        }
+    }
+}
+
+/// How to randomize the wait time between retries
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Jitter {
+    /// Up to the backoff time
+    Full,
+    /// At least half of the backoff time
+    Equal,
+}
+
+impl FromStr for Jitter {
+    type Err = Error;
+    fn from_str(jitter: &str) -> Result<Self, Self::Err> {
+        match jitter.to_lowercase().as_str() {
+            "full" => Ok(Jitter::Full),
+            "equal" => Ok(Jitter::Equal),
+            _ => Err(anyhow!("Unknown retry jitter {}", jitter)),
+        }
+    }
+}
+
+impl From<Jitter> for RetryJitter {
+    fn from(jitter: Jitter) -> Self {
+        match jitter {
+            Jitter::Full => RetryJitter::Full,
+            Jitter::Equal => RetryJitter::Equal,
+        }
+    }
+}
+
+/// Verbosity level of the output, which also accepts a boolean in the config
+/// file (`verbose = true` is the same as `-v`)
+fn deserialize_verbosity<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Verbosity {
+        Flag(bool),
+        Level(u8),
+    }
+    Ok(match Verbosity::deserialize(deserializer)? {
+        Verbosity::Flag(verbose) => u8::from(verbose),
+        Verbosity::Level(level) => level,
+    })
+}
+
 // Macro for generating default functions to be used by serde
 macro_rules! default_function {
     ( $( $name:ident : $T:ty = $e:expr; )* ) => {
@@ -72,6 +243,9 @@ macro_rules! default_function {
     };
 }
 
+/// Environment variables with a GitHub token, in order of precedence
+const GITHUB_TOKEN_ENV_VARS: [&str; 2] = ["GITHUB_TOKEN", "GH_TOKEN"];
+
 // Generate the functions for serde defaults
 default_function! {
     max_redirects: usize = DEFAULT_MAX_REDIRECTS;
@@ -105,9 +279,43 @@ pub(crate) struct LycheeOptions {
     /// These can be: files (e.g. `README.md`), file globs (e.g. `"~/git/*/README.md"`),
     /// remote URLs (e.g. `https://example.com/README.md`) or standard input (`-`).
     /// NOTE: Use `--` to separate inputs from options that allow multiple arguments.
-    #[structopt(name = "inputs", required = true)]
+    #[structopt(
+        name = "inputs",
+        required_unless_one = &["diff", "inputs-from", "validate", "merge-reports"]
+    )]
     raw_inputs: Vec<String>,
 
+    /// Read additional inputs from a file with one input (file, glob or URL)
+    /// per line, e.g. a list of changed files computed in CI.
+    /// Blank lines and lines starting with `#` are ignored.
+    /// Use `-` to read the list from stdin
+    #[structopt(long, parse(from_os_str))]
+    pub(crate) inputs_from: Option<PathBuf>,
+
+    /// Only check links on lines added in the given unified diff
+    /// (e.g. the output of `git diff`) instead of checking inputs.
+    /// Use `-` to read the diff from stdin
+    #[structopt(long, parse(from_os_str))]
+    pub(crate) diff: Option<PathBuf>,
+
+    /// Only validate the configuration (e.g. regular expressions, status
+    /// codes and headers) and exit without checking any links
+    #[structopt(long)]
+    pub(crate) validate: bool,
+
+    /// Merge the JSON reports of previous runs (e.g. of a sharded CI job,
+    /// written with `--format json`) into one report in the selected format,
+    /// instead of checking links. Failing links reported by several runs for
+    /// the same input are only counted once
+    #[structopt(long, parse(from_os_str))]
+    pub(crate) merge_reports: Vec<PathBuf>,
+
+    /// After the check, serve an HTML report of the results at this address
+    /// (e.g. `127.0.0.1:8080`) until lychee gets stopped with Ctrl-C. The
+    /// JSON stats are available at `/report.json`
+    #[structopt(long)]
+    pub(crate) serve: Option<SocketAddr>,
+
     /// Configuration file to use
     #[structopt(short, long = "config", default_value = "./lychee.toml")]
     pub(crate) config_file: String,
@@ -127,21 +335,53 @@ impl LycheeOptions {
         } else {
             Some(self.config.exclude_path.clone())
         };
-        self.raw_inputs
+        let file_type = self.config.input_format.map(FileType::from);
+        let mut raw_inputs = self.raw_inputs.clone();
+        if let Some(manifest) = &self.inputs_from {
+            raw_inputs.extend(read_manifest(manifest)?);
+        }
+        raw_inputs
             .iter()
-            .map(|s| Input::new(s, None, self.config.glob_ignore_case, excluded.clone()))
+            .map(|s| Input::new(s, file_type, self.config.glob_ignore_case, excluded.clone()))
             .collect::<Result<_, _>>()
             .context("Cannot parse inputs from arguments")
     }
 }
 
+/// Read the inputs listed in a manifest file, or in stdin for `-`
+fn read_manifest(path: &Path) -> Result<Vec<String>> {
+    let contents = if path == Path::new("-") {
+        let mut contents = String::new();
+        std::io::stdin()
+            .read_to_string(&mut contents)
+            .context("Cannot read inputs from stdin")?;
+        contents
+    } else {
+        fs::read_to_string(path)
+            .with_context(|| format!("Cannot read inputs from {}", path.display()))?
+    };
+    Ok(manifest_inputs(&contents))
+}
+
+/// Get the inputs of a manifest, skipping blank lines and comments
+fn manifest_inputs(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect()
+}
+
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Deserialize, StructOpt, Clone)]
 pub(crate) struct Config {
-    /// Verbose program output
-    #[structopt(short, long)]
-    #[serde(default)]
-    pub(crate) verbose: bool,
+    /// Verbose program output.
+    /// Use `-vv` to print each link as it gets checked, with the time the
+    /// check took
+    #[structopt(short, long, parse(from_occurrences))]
+    #[serde(default, deserialize_with = "deserialize_verbosity")]
+    pub(crate) verbose: u8,
 
     /// Do not show progress bar.
     /// This is recommended for non-interactive shells (e.g. for continuous integration)
@@ -164,13 +404,44 @@ pub(crate) struct Config {
     #[serde(with = "humantime_serde")]
     pub(crate) max_cache_age: Duration,
 
+    /// Keep the final URL of redirected links in the cache for this duration
+    /// (e.g. `30d`), even after their status expired. Such links get checked
+    /// by requesting the final URL directly, without following the redirects
+    /// again, until it fails. Requires `--cache`
+    #[structopt(long, parse(try_from_str = humantime::parse_duration))]
+    #[serde(default)]
+    #[serde(with = "humantime_serde")]
+    pub(crate) cache_redirects: Option<Duration>,
+
+    /// Remove cache entries for links which are not found in the inputs
+    /// anymore, to keep the cache small. Runs stopped by `--fail-fast` don't
+    /// prune the cache
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) prune_cache: bool,
+
+    /// Split the cache into this number of files, which get stored in the
+    /// `.lycheecache.d` directory and are loaded in parallel. Speeds up
+    /// loading and storing large caches. Requires `--cache`
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) cache_shards: Option<usize>,
+
+    /// Only recheck links which failed in a previous run (including
+    /// timeouts) and reuse all other cached results. Requires `--cache`
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) recheck_failures: bool,
+
     /// Don't perform any link checking.
     /// Instead, dump all the links extracted from inputs that would be checked
     #[structopt(long)]
     #[serde(default)]
     pub(crate) dump: bool,
 
-    /// Maximum number of allowed redirects
+    /// Maximum number of allowed redirects. With 0, redirects are reported
+    /// without following them, as permanent (301, 308) or temporary (302,
+    /// 303, 307) redirects
     #[structopt(short, long, default_value = &MAX_REDIRECTS_STR)]
     #[serde(default = "max_redirects")]
     pub(crate) max_redirects: usize,
@@ -185,12 +456,40 @@ pub(crate) struct Config {
     #[serde(default = "max_concurrency")]
     pub(crate) max_concurrency: usize,
 
+    /// Maximum number of concurrent requests to hosts matching a regular
+    /// expression, e.g. `example\.com$=2`. GitHub hosts are limited to
+    /// four concurrent requests by default
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) host_concurrency: Vec<String>,
+
     /// Number of threads to utilize.
     /// Defaults to number of cores available to the system
     #[structopt(short = "T", long)]
     #[serde(default)]
     pub(crate) threads: Option<usize>,
 
+    /// Stop at the first failed link check, cancelling all outstanding
+    /// checks. Useful for quick local checks
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) fail_fast: bool,
+
+    /// Stop checking after this duration (e.g. `10m`) and report the results
+    /// so far. Links which were not checked in time are reported as skipped,
+    /// which doesn't fail the run
+    #[structopt(long, parse(try_from_str = humantime::parse_duration))]
+    #[serde(default)]
+    #[serde(with = "humantime_serde")]
+    pub(crate) time_budget: Option<Duration>,
+
+    /// Stop checking after this number of checks and report the remaining
+    /// links as skipped, which doesn't fail the run. Results from the cache
+    /// don't count as checks
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) max_checks: Option<usize>,
+
     /// User agent
     #[structopt(short, long, default_value = DEFAULT_USER_AGENT)]
     #[serde(default = "user_agent")]
@@ -201,6 +500,13 @@ pub(crate) struct Config {
     #[serde(default)]
     pub(crate) insecure: bool,
 
+    /// Prefer HTTP/2 for HTTPS links, so that requests to the same host share
+    /// one connection. Servers without HTTP/2 support are checked over
+    /// HTTP/1.1
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) http2: bool,
+
     /// Only test links with the given schemes (e.g. http and https)
     #[structopt(short, long)]
     #[serde(default)]
@@ -227,6 +533,7 @@ pub(crate) struct Config {
     pub(crate) exclude_file: Vec<String>,
 
     /// Exclude file path from getting checked.
+    /// Supports glob patterns, e.g. `**/CHANGELOG.md`
     #[structopt(long)]
     #[serde(default)]
     pub(crate) exclude_path: Vec<PathBuf>,
@@ -257,12 +564,58 @@ pub(crate) struct Config {
     #[serde(default)]
     pub(crate) exclude_mail: bool,
 
+    /// Exclude a host and its subdomains from checking, e.g. `example.com`
+    /// also excludes `www.example.com`
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) exclude_host: Vec<String>,
+
+    /// Only check links to this host and its subdomains, e.g. `example.com`
+    /// also includes `docs.example.com`. All other links are excluded. Can be
+    /// given multiple times
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) include_host: Vec<String>,
+
+    /// Check mail addresses which only differ in case just once, and cache
+    /// them as one address (domain or all). `all` also ignores the case of
+    /// the local part before the `@`, which most mail servers don't
+    /// distinguish. Per input, the first of these addresses is reported
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) ignore_mail_case: Option<MailCase>,
+
     /// Remap URI matching pattern to different URI
     #[serde(default)]
     #[structopt(long)]
     pub(crate) remap: Vec<String>,
 
-    /// Custom request headers
+    /// Remove query parameters from links before checking and caching them.
+    /// A trailing `*` matches by prefix, e.g. `utm_*`
+    #[serde(default)]
+    #[structopt(long)]
+    pub(crate) strip_query_param: Vec<String>,
+
+    /// Substitute `${NAME}` placeholders in links, given as `NAME=VALUE`.
+    /// Environment variables in the value (e.g. `$DOCS_HOST`) get expanded;
+    /// write `$$` for a literal `$`. Links with unknown variables are an error. Can be given multiple times
+    #[serde(default)]
+    #[structopt(long)]
+    pub(crate) var: Vec<String>,
+
+    /// Check website links which only differ by a trailing slash just once
+    /// per input. With `add`, the form with a trailing slash is canonical,
+    /// with `remove` the one without. Links are checked and reported as
+    /// written; those which get redirected to their other form are reported
+    /// as warnings
+    #[serde(default)]
+    #[structopt(long)]
+    pub(crate) trailing_slash: Option<SlashPolicy>,
+
+    /// Custom request headers.
+    /// Environment variables in values (e.g. `${TOKEN}`) get expanded; write
+    /// `$$` for a literal `$`. A custom `accept` header replaces the default, which prefers HTML
+    /// for links with a fragment and accepts anything otherwise
     #[structopt(short, long)]
     #[serde(default)]
     pub(crate) headers: Vec<String>,
@@ -272,16 +625,64 @@ pub(crate) struct Config {
     #[serde(default)]
     pub(crate) accept: Option<HashSet<u16>>,
 
+    /// Expected status code for URIs matching a regular expression, e.g.
+    /// `example\.com/admin=401` for a link which requires a login. Matching
+    /// links are only valid with exactly this status code
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) expect_status: Vec<String>,
+
     /// Website timeout in seconds from connect to response finished
     #[structopt(short, long, default_value = &TIMEOUT_STR)]
     #[serde(default = "timeout")]
     pub(crate) timeout: usize,
 
+    /// Timeout in seconds for a specific URI scheme, overriding the global timeout.
+    /// E.g. `mailto=60` gives mail address checks more time
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) scheme_timeout: Vec<String>,
+
+    /// Timeout in seconds for URIs matching a regular expression, overriding
+    /// the scheme and global timeouts. E.g. `example\.com/slow=60` gives a
+    /// single slow link more time
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) uri_timeout: Vec<String>,
+
     /// Minimum wait time in seconds between retries of failed requests
     #[structopt(short, long, default_value = &RETRY_WAIT_TIME_STR)]
     #[serde(default = "retry_wait_time")]
     pub(crate) retry_wait_time: usize,
 
+    /// Randomize the wait time between retries, so that links which failed
+    /// together don't get retried together: `full` waits up to the backoff
+    /// time, `equal` waits at least half of it
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) retry_jitter: Option<Jitter>,
+
+    /// Comma-separated list of status codes which get retried, e.g.
+    /// `408,429,503`. By default, server errors (5xx) get retried.
+    /// Timeouts and network errors without a response, like DNS failures,
+    /// are always retried
+    #[structopt(long, parse(try_from_str = parse_statuscodes))]
+    #[serde(default)]
+    pub(crate) retry_on: Option<HashSet<u16>>,
+
+    /// Minimum delay in milliseconds between two requests to the same host.
+    /// Requests to different hosts are not delayed
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) host_delay: Option<u64>,
+
+    /// Maximum number of bytes to read from a response body.
+    /// Reading stops once the limit is reached, which avoids downloading huge assets in full.
+    /// By default, response bodies are not read
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) max_response_size: Option<usize>,
+
     /// Request method
     // Using `-X` as a short param similar to curl
     #[structopt(short = "X", long, default_value = DEFAULT_METHOD)]
@@ -290,17 +691,19 @@ pub(crate) struct Config {
 
     /// Base URL or website root directory to check relative URLs
     /// e.g. https://example.com or `/path/to/public`
-    #[structopt(short, long, parse(try_from_str = parse_base))]
-    #[serde(default)]
+    #[structopt(short, long, alias = "base-url", parse(try_from_str = parse_base))]
+    #[serde(default, alias = "base_url")]
     pub(crate) base: Option<Base>,
 
-    /// Basic authentication support. E.g. `username:password`
+    /// Basic authentication support. E.g. `username:password`. Environment
+    /// variables (e.g. `$PASSWORD`) get expanded; write `$$` for a literal `$`
     #[structopt(long)]
     #[serde(default)]
     pub(crate) basic_auth: Option<String>,
 
-    /// GitHub API token to use when checking github.com links, to avoid rate limiting
-    #[structopt(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+    /// GitHub API token to use when checking github.com links, to avoid rate
+    /// limiting. Falls back to the `GITHUB_TOKEN` and `GH_TOKEN` env vars
+    #[structopt(long)]
     #[serde(default)]
     pub(crate) github_token: Option<SecretString>,
 
@@ -314,25 +717,187 @@ pub(crate) struct Config {
     #[serde(default)]
     pub(crate) include_verbatim: bool,
 
+    /// Fail on links with an empty target (e.g. `href=""` or `href="#"`)
+    /// instead of skipping them
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) deny_empty_urls: bool,
+
+    /// Exclude links to the document they were found in (self-links), like `#top` or a link
+    /// from `README.md` to `README.md`, instead of checking them
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) exclude_self_links: bool,
+
+    /// Still verify that the fragments of self-links match an anchor of the document (only
+    /// for local HTML files). Only applies with `--exclude-self-links`
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) check_self_link_anchors: bool,
+
     /// Ignore case when expanding filesystem path glob inputs
     #[structopt(long)]
     #[serde(default)]
     pub(crate) glob_ignore_case: bool,
 
+    /// Crawl websites given as inputs: follow links to HTML pages on the same
+    /// host up to this many links away from the input, and check their links
+    /// as well. Every page is only visited once. Pages are requested like the
+    /// checked links (e.g. with the same headers), and excluded pages are not
+    /// crawled. 0 disables crawling
+    #[structopt(long, default_value = "0")]
+    #[serde(default)]
+    pub(crate) crawl_depth: usize,
+
+    /// Input format (html, markdown, notebook, email, asciidoc, xml, feed or plaintext) to use for all
+    /// inputs, regardless of their file extensions. Useful for stdin or files
+    /// with uncommon extensions
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) input_format: Option<InputFormat>,
+
     /// Output file of status report
     #[structopt(short, long, parse(from_os_str))]
     #[serde(default)]
     pub(crate) output: Option<PathBuf>,
 
-    /// Output format of final status report (compact, detailed, json, markdown)
+    /// Output format of final status report (compact, csv, detailed, html, json, markdown,
+    /// redirects, unique). The redirects report only lists links which got redirected, along with the URL
+    /// they ended up at. The CSV report lists the failing links, e.g. for spreadsheets
     #[structopt(short, long, default_value = "compact")]
     #[serde(default)]
     pub(crate) format: Format,
 
+    /// Write suggested fixes for broken or outdated links to this file as JSON, e.g. the HTTPS
+    /// version of insecure links, the targets of redirects and similarly named files for
+    /// missing local files. Each entry has the `original` link, the `suggestion` and a `reason`
+    #[structopt(long, parse(from_os_str))]
+    #[serde(default)]
+    pub(crate) suggestions: Option<PathBuf>,
+
     /// When HTTPS is available, treat HTTP links as errors
     #[structopt(long)]
     #[serde(default)]
     pub(crate) require_https: bool,
+
+    /// Remember hosts which send a `Strict-Transport-Security` (HSTS) header over HTTPS, and
+    /// check later HTTP links to them via HTTPS instead, like browsers do. Upgraded links are
+    /// reported as warnings
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) hsts: bool,
+
+    /// Treat insecure (HTTP) subresources like images or scripts on HTTPS pages
+    /// as errors (mixed content)
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) deny_mixed_content: bool,
+
+    /// Treat redirects to a different host (e.g. domain parking pages) as errors
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) deny_cross_host_redirects: bool,
+
+    /// Don't ignore a `www.` prefix when checking for cross-host redirects,
+    /// e.g. to flag redirects from `example.com` to `www.example.com`
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) exact_redirect_hosts: bool,
+
+    /// Treat links as errors if they don't end on HTTPS after following all
+    /// redirects, even if the request succeeded
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) require_final_https: bool,
+
+    /// Follow redirects to the same URL (e.g. with an added trailing slash)
+    /// once and accept them as successful if they loop
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) accept_self_redirects: bool,
+
+    /// Report links which get redirected to a different URL as warnings, so
+    /// that they can be updated. Warnings don't fail the run
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) warn_on_redirects: bool,
+
+    /// Report mail addresses of disposable (temporary) mail providers like
+    /// `mailinator.com` as warnings, based on a built-in list of providers
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) warn_on_disposable_mail: bool,
+
+    /// Add a domain (including its subdomains) to the list of disposable mail
+    /// providers for `--warn-on-disposable-mail`. Can be given multiple times
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) disposable_domain: Vec<String>,
+
+    /// Look up dead links (404 and 410) in the Wayback Machine of the
+    /// Internet Archive and suggest their archived snapshot instead.
+    /// Headers set with `--header` are not sent to the Internet Archive
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) wayback: bool,
+
+    /// Report dead links with a snapshot in the Wayback Machine as warnings
+    /// instead of errors (implies `--wayback`)
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) wayback_as_warning: bool,
+
+    /// Check that links to images and other binary files serve content of the
+    /// type expected from their file extension, by inspecting the first bytes
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) sniff_content: bool,
+
+    /// Check that successful responses deliver as many bytes as announced in
+    /// their `Content-Length` header, to catch truncated downloads
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) verify_content_length: bool,
+
+    /// Expected `Content-Type` prefix for links with a file extension, e.g.
+    /// `pdf=application/pdf`. Links with a mismatching content type are errors
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) content_type: Vec<String>,
+
+    /// Count timeouts as successful checks, so that they don't make lychee
+    /// exit with an error code (e.g. on flaky networks)
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) timeout_as_success: bool,
+
+    /// Check that websites allow cross-origin requests (CORS) from the given
+    /// origin, e.g. `https://app.example.com`. Sends an additional `OPTIONS`
+    /// preflight request for every website
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) cors_origin: Option<String>,
+
+    /// Send a single warmup request to each host before checking its links. If the host can't
+    /// be resolved or reached, all of its links fail right away, without retries
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) warmup: bool,
+
+    /// Send the URL of the page a link was found on as `Referer` header, to pass the hotlink
+    /// protection of some CDNs. Links from local files use the base URL (`--base`) instead.
+    /// Like in browsers, links to other sites only get the origin of the page, and links from
+    /// HTTPS to HTTP pages get none
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) referer: bool,
+
+    /// Response header to include in the output of website checks for
+    /// debugging, e.g. `server` or `cf-ray`. Values of credential headers
+    /// like `set-cookie` are redacted
+    #[structopt(long)]
+    #[serde(default)]
+    pub(crate) capture_header: Vec<String>,
 }
 
 impl Config {
@@ -355,6 +920,24 @@ impl Config {
         Ok(Some(toml::from_slice(&contents)?))
     }
 
+    /// Fall back to a GitHub token from the environment if none was given with
+    /// `--github-token`. `GITHUB_TOKEN` takes precedence over `GH_TOKEN`, and
+    /// empty values are ignored.
+    pub(crate) fn resolve_github_token<F>(&mut self, var: F)
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let is_set = |token: &Option<SecretString>| matches!(token, Some(token) if !token.expose_secret().is_empty());
+        if is_set(&self.github_token) {
+            return;
+        }
+        self.github_token = GITHUB_TOKEN_ENV_VARS
+            .iter()
+            .filter_map(|name| var(name))
+            .find(|token| !token.is_empty())
+            .map(SecretString::new);
+    }
+
     /// Merge the configuration from TOML into the CLI configuration
     pub(crate) fn merge(&mut self, toml: Config) {
         fold_in! {
@@ -362,16 +945,25 @@ impl Config {
             self, toml;
 
             // Keys with defaults to assign
-            verbose: false;
+            verbose: 0;
             cache: false;
             no_progress: false;
             max_redirects: DEFAULT_MAX_REDIRECTS;
             max_retries: DEFAULT_MAX_RETRIES;
             max_concurrency: DEFAULT_MAX_CONCURRENCY;
+            host_concurrency: Vec::<String>::new();
+            fail_fast: false;
+            time_budget: None;
+            max_checks: None;
             max_cache_age: humantime::parse_duration(DEFAULT_MAX_CACHE_AGE).unwrap();
+            cache_redirects: None;
+            prune_cache: false;
+            cache_shards: None;
+            recheck_failures: false;
             threads: None;
             user_agent: DEFAULT_USER_AGENT;
             insecure: false;
+            http2: false;
             scheme: Vec::<String>::new();
             include: Vec::<String>::new();
             exclude: Vec::<String>::new();
@@ -382,19 +974,57 @@ impl Config {
             exclude_link_local: false;
             exclude_loopback: false;
             exclude_mail: false;
+            exclude_host: Vec::<String>::new();
+            include_host: Vec::<String>::new();
+            ignore_mail_case: None;
             remap: Vec::<String>::new();
+            strip_query_param: Vec::<String>::new();
+            var: Vec::<String>::new();
+            trailing_slash: None;
             headers: Vec::<String>::new();
             accept: None;
+            expect_status: Vec::<String>::new();
             timeout: DEFAULT_TIMEOUT_SECS;
+            scheme_timeout: Vec::<String>::new();
+            uri_timeout: Vec::<String>::new();
             retry_wait_time: DEFAULT_RETRY_WAIT_TIME_SECS;
+            retry_jitter: None;
+            retry_on: None;
+            host_delay: None;
+            max_response_size: None;
             method: DEFAULT_METHOD;
             base: None;
             basic_auth: None;
             skip_missing: false;
             include_verbatim: false;
+            deny_empty_urls: false;
+            exclude_self_links: false;
+            check_self_link_anchors: false;
             glob_ignore_case: false;
+            crawl_depth: 0;
+            input_format: None;
             output: None;
+            suggestions: None;
             require_https: false;
+            hsts: false;
+            deny_mixed_content: false;
+            deny_cross_host_redirects: false;
+            exact_redirect_hosts: false;
+            require_final_https: false;
+            accept_self_redirects: false;
+            warn_on_redirects: false;
+            warn_on_disposable_mail: false;
+            disposable_domain: Vec::<String>::new();
+            wayback: false;
+            wayback_as_warning: false;
+            sniff_content: false;
+            verify_content_length: false;
+            content_type: Vec::<String>::new();
+            timeout_as_success: false;
+            cors_origin: None;
+            warmup: false;
+            referer: false;
+            capture_header: Vec::<String>::new();
         }
 
         if self
@@ -412,3 +1042,69 @@ impl Config {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(flag: Option<&str>, vars: &[(&str, &str)]) -> Option<String> {
+        let mut config = Config::from_iter(["lychee"]);
+        config.github_token = flag.map(|token| SecretString::new(token.to_string()));
+        config.resolve_github_token(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| String::from(*value))
+        });
+        config
+            .github_token
+            .map(|token| token.expose_secret().clone())
+    }
+
+    #[test]
+    fn test_input_format() {
+        assert_eq!("html".parse::<InputFormat>().unwrap(), InputFormat::Html);
+        assert_eq!(
+            "Markdown".parse::<InputFormat>().unwrap(),
+            InputFormat::Markdown
+        );
+        assert_eq!(
+            "ipynb".parse::<InputFormat>().unwrap(),
+            InputFormat::Notebook
+        );
+        assert_eq!("OPML".parse::<InputFormat>().unwrap(), InputFormat::Feed);
+        assert_eq!(
+            "txt".parse::<InputFormat>().unwrap(),
+            InputFormat::Plaintext
+        );
+        assert!("rst".parse::<InputFormat>().is_err());
+
+        let config: Config = toml::from_str(r#"input_format = "AsciiDoc""#).unwrap();
+        assert_eq!(config.input_format, Some(InputFormat::AsciiDoc));
+    }
+
+    #[test]
+    fn test_github_token_precedence() {
+        let vars = [("GITHUB_TOKEN", "github"), ("GH_TOKEN", "gh")];
+        assert_eq!(resolve(Some("flag"), &vars), Some("flag".to_string()));
+        assert_eq!(resolve(None, &vars), Some("github".to_string()));
+        assert_eq!(resolve(None, &vars[1..]), Some("gh".to_string()));
+        assert_eq!(resolve(None, &[]), None);
+    }
+
+    #[test]
+    fn test_github_token_ignores_empty_values() {
+        let vars = [("GITHUB_TOKEN", ""), ("GH_TOKEN", "gh")];
+        assert_eq!(resolve(None, &vars), Some("gh".to_string()));
+        assert_eq!(resolve(Some(""), &vars), Some("gh".to_string()));
+        assert_eq!(resolve(None, &vars[..1]), None);
+    }
+
+    #[test]
+    fn test_manifest_inputs() {
+        let manifest = "# Changed files\nREADME.md\n\n  docs/*.md  \r\nhttps://example.com\n";
+        assert_eq!(
+            manifest_inputs(manifest),
+            vec!["README.md", "docs/*.md", "https://example.com"]
+        );
+    }
+}
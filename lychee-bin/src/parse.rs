@@ -1,7 +1,13 @@
 use anyhow::{anyhow, Context, Result};
 use headers::{authorization::Basic, Authorization, HeaderMap, HeaderName};
+use http::StatusCode;
 use lychee_lib::{remap::Remaps, Base};
-use std::{collections::HashSet, time::Duration};
+use regex::Regex;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 /// Split a single HTTP header into a (key, value) tuple
 fn read_header(input: &str) -> Result<(String, String)> {
@@ -15,29 +21,219 @@ fn read_header(input: &str) -> Result<(String, String)> {
     Ok((elements[0].into(), elements[1].into()))
 }
 
+/// Look up an environment variable, for expanding references to it in
+/// arguments like headers
+pub(crate) fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Expand `$VAR` and `${VAR}` references with the values returned by `var`.
+/// This allows keeping secrets like tokens out of configuration files.
+/// A literal `$` is written as `$$`.
+fn expand_env_vars<F>(input: &str, var: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    shellexpand::env_with_context(input, |name| {
+        var(name).map(Some).ok_or(std::env::VarError::NotPresent)
+    })
+    .map(Cow::into_owned)
+    .map_err(|e| {
+        anyhow!(
+            "Cannot expand environment variable `{}`: {}",
+            e.var_name,
+            e.cause
+        )
+    })
+}
+
 /// Parse seconds into a `Duration`
 pub(crate) const fn parse_duration_secs(secs: usize) -> Duration {
     Duration::from_secs(secs as u64)
 }
 
-/// Parse HTTP headers into a `HeaderMap`
-pub(crate) fn parse_headers<T: AsRef<str>>(headers: &[T]) -> Result<HeaderMap> {
+/// Parse HTTP headers into a `HeaderMap`. Environment variables in values
+/// get expanded using `var`.
+pub(crate) fn parse_headers<T, F>(headers: &[T], var: F) -> Result<HeaderMap>
+where
+    T: AsRef<str>,
+    F: Fn(&str) -> Option<String>,
+{
     let mut out = HeaderMap::new();
     for header in headers {
         let (key, val) = read_header(header.as_ref())?;
+        let val = expand_env_vars(&val, &var)?;
         out.insert(HeaderName::from_bytes(key.as_bytes())?, val.parse()?);
     }
     Ok(out)
 }
 
+/// Parse variables of the form `NAME=VALUE` into a map. Environment variables
+/// in values (like `$DOCS_HOST`) get expanded using `var`.
+pub(crate) fn parse_variables<T, F>(variables: &[T], var: F) -> Result<HashMap<String, String>>
+where
+    T: AsRef<str>,
+    F: Fn(&str) -> Option<String>,
+{
+    let mut out = HashMap::new();
+    for variable in variables {
+        let variable = variable.as_ref();
+        let (name, value) = variable
+            .split_once('=')
+            .filter(|(name, _)| !name.is_empty())
+            .ok_or_else(|| anyhow!("Variable must be of the form NAME=VALUE, got {}", variable))?;
+        out.insert(name.to_string(), expand_env_vars(value, &var)?);
+    }
+    Ok(out)
+}
+
+/// Parse timeout overrides of the form `scheme=seconds` into a map
+pub(crate) fn parse_scheme_timeouts<T: AsRef<str>>(
+    timeouts: &[T],
+) -> Result<HashMap<String, Duration>> {
+    let mut out = HashMap::new();
+    for timeout in timeouts {
+        let timeout = timeout.as_ref();
+        let (scheme, secs) = timeout.split_once('=').ok_or_else(|| {
+            anyhow!(
+                "Scheme timeout must be of the form scheme=seconds, got {}",
+                timeout
+            )
+        })?;
+        let secs: usize = secs
+            .parse()
+            .with_context(|| format!("Invalid timeout for scheme `{scheme}`: {secs}"))?;
+        out.insert(scheme.to_lowercase(), parse_duration_secs(secs));
+    }
+    Ok(out)
+}
+
+/// Parse timeout overrides of the form `pattern=seconds`, where `pattern` is
+/// a regular expression matched against URIs. The pattern may contain `=`
+/// itself, since the value is split at the last one.
+pub(crate) fn parse_uri_timeouts<T: AsRef<str>>(timeouts: &[T]) -> Result<Vec<(Regex, Duration)>> {
+    let mut out = vec![];
+    for timeout in timeouts {
+        let timeout = timeout.as_ref();
+        let (pattern, secs) = timeout.rsplit_once('=').ok_or_else(|| {
+            anyhow!(
+                "URI timeout must be of the form pattern=seconds, got {}",
+                timeout
+            )
+        })?;
+        let pattern = Regex::new(pattern)
+            .with_context(|| format!("Invalid URI timeout pattern `{pattern}`"))?;
+        let secs: usize = secs
+            .parse()
+            .with_context(|| format!("Invalid timeout for URI pattern `{pattern}`: {secs}"))?;
+        out.push((pattern, parse_duration_secs(secs)));
+    }
+    Ok(out)
+}
+
+/// Parse expected status codes of the form `pattern=code`, where `pattern` is
+/// a regular expression matched against URIs
+pub(crate) fn parse_expected_status<T: AsRef<str>>(
+    expected: &[T],
+) -> Result<Vec<(Regex, StatusCode)>> {
+    let mut out = vec![];
+    for rule in expected {
+        let rule = rule.as_ref();
+        let (pattern, code) = rule.rsplit_once('=').ok_or_else(|| {
+            anyhow!(
+                "Expected status must be of the form pattern=code, got {}",
+                rule
+            )
+        })?;
+        let pattern = Regex::new(pattern)
+            .with_context(|| format!("Invalid expected status pattern `{pattern}`"))?;
+        let code = code
+            .parse::<u16>()
+            .map_err(anyhow::Error::from)
+            .and_then(|code| Ok(StatusCode::from_u16(code)?))
+            .with_context(|| format!("Invalid status code for URI pattern `{pattern}`: {code}"))?;
+        out.push((pattern, code));
+    }
+    Ok(out)
+}
+
+/// Parse concurrency limits of the form `pattern=limit`, where `pattern` is a
+/// regular expression matched against host names
+pub(crate) fn parse_host_concurrency<T: AsRef<str>>(limits: &[T]) -> Result<Vec<(Regex, usize)>> {
+    let mut out = vec![];
+    for limit in limits {
+        let limit = limit.as_ref();
+        let (pattern, max) = limit.rsplit_once('=').ok_or_else(|| {
+            anyhow!(
+                "Host concurrency must be of the form pattern=limit, got {}",
+                limit
+            )
+        })?;
+        let pattern = Regex::new(pattern)
+            .with_context(|| format!("Invalid host concurrency pattern `{pattern}`"))?;
+        let max: usize = max
+            .parse()
+            .with_context(|| format!("Invalid concurrency for host pattern `{pattern}`: {max}"))?;
+        if max == 0 {
+            return Err(anyhow!(
+                "Concurrency for host pattern `{}` must be at least 1",
+                pattern
+            ));
+        }
+        out.push((pattern, max));
+    }
+    Ok(out)
+}
+
+/// Parse expected content types of the form `extension=content-type` into a
+/// map from lowercase extensions (without a leading dot) to content types
+pub(crate) fn parse_content_types<T: AsRef<str>>(
+    content_types: &[T],
+) -> Result<HashMap<String, String>> {
+    let mut out = HashMap::new();
+    for content_type in content_types {
+        let content_type = content_type.as_ref();
+        let (extension, mime) = content_type
+            .split_once('=')
+            .filter(|(extension, mime)| !extension.is_empty() && !mime.is_empty())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Content type must be of the form extension=content-type, got {}",
+                    content_type
+                )
+            })?;
+        out.insert(
+            extension.trim_start_matches('.').to_lowercase(),
+            mime.to_string(),
+        );
+    }
+    Ok(out)
+}
+
+/// Parse names of HTTP headers
+pub(crate) fn parse_header_names<T: AsRef<str>>(names: &[T]) -> Result<Vec<HeaderName>> {
+    names
+        .iter()
+        .map(|name| {
+            let name = name.as_ref();
+            HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid header name `{name}`"))
+        })
+        .collect()
+}
+
 /// Parse URI remaps
 pub(crate) fn parse_remaps(remaps: &[String]) -> Result<Remaps> {
     Remaps::try_from(remaps)
         .context("Remaps must be of the form '<pattern> <uri>' (separated by whitespace)")
 }
 
-/// Parse a HTTP basic auth header into username and password
-pub(crate) fn parse_basic_auth(auth: &str) -> Result<Authorization<Basic>> {
+/// Parse a HTTP basic auth header into username and password. Environment
+/// variables in both get expanded using `var`.
+pub(crate) fn parse_basic_auth<F>(auth: &str, var: F) -> Result<Authorization<Basic>>
+where
+    F: Fn(&str) -> Option<String>,
+{
     let params: Vec<_> = auth.split(':').collect();
     if params.len() != 2 {
         return Err(anyhow!(
@@ -45,7 +241,9 @@ pub(crate) fn parse_basic_auth(auth: &str) -> Result<Authorization<Basic>> {
             auth
         ));
     }
-    Ok(Authorization::basic(params[0], params[1]))
+    let username = expand_env_vars(params[0], &var)?;
+    let password = expand_env_vars(params[1], &var)?;
+    Ok(Authorization::basic(&username, &password))
 }
 
 pub(crate) fn parse_base(src: &str) -> Result<Base, lychee_lib::ErrorKind> {
@@ -62,6 +260,20 @@ pub(crate) fn parse_statuscodes<T: AsRef<str>>(accept: T) -> Result<HashSet<u16>
     Ok(statuscodes)
 }
 
+/// Convert optional status codes, e.g. from `--accept`, into `StatusCode`s
+pub(crate) fn parse_optional_statuscodes(
+    codes: Option<&HashSet<u16>>,
+) -> Result<Option<HashSet<StatusCode>>> {
+    codes
+        .map(|codes| {
+            codes
+                .iter()
+                .map(|code| Ok(StatusCode::from_u16(*code)?))
+                .collect()
+        })
+        .transpose()
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -72,11 +284,141 @@ mod tests {
 
     use super::*;
 
+    /// Look up variables in `vars` instead of the environment
+    fn vars<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| String::from(*value))
+        }
+    }
+
     #[test]
     fn test_parse_custom_headers() {
         let mut custom = HeaderMap::new();
         custom.insert(header::ACCEPT, "text/html".parse().unwrap());
-        assert_eq!(parse_headers(&["accept=text/html"]).unwrap(), custom);
+        assert_eq!(
+            parse_headers(&["accept=text/html"], vars(&[])).unwrap(),
+            custom
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_headers_with_env_var() {
+        let env = vars(&[("TOKEN", "secret")]);
+        let mut custom = HeaderMap::new();
+        custom.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert_eq!(
+            parse_headers(&["authorization=Bearer ${TOKEN}"], &env).unwrap(),
+            custom
+        );
+        assert_eq!(
+            parse_headers(&["authorization=Bearer $TOKEN"], &env).unwrap(),
+            custom
+        );
+
+        // `$$` is a literal `$`
+        let mut custom = HeaderMap::new();
+        custom.insert(header::AUTHORIZATION, "Bearer pa$word".parse().unwrap());
+        assert_eq!(
+            parse_headers(&["authorization=Bearer pa$$word"], &env).unwrap(),
+            custom
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_headers_with_unset_env_var() {
+        let err = parse_headers(&["authorization=Bearer $TOKEN"], vars(&[])).unwrap_err();
+        assert!(err.to_string().contains("TOKEN"));
+    }
+
+    #[test]
+    fn test_parse_content_types() {
+        let content_types = parse_content_types(&["pdf=application/pdf", ".PNG=image/"]).unwrap();
+        assert_eq!(
+            content_types.get("pdf").map(String::as_str),
+            Some("application/pdf")
+        );
+        assert_eq!(content_types.get("png").map(String::as_str), Some("image/"));
+
+        assert!(parse_content_types(&["pdf"]).is_err());
+        assert!(parse_content_types(&["=application/pdf"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_header_names() {
+        let names = parse_header_names(&["Server", "cf-ray"]).unwrap();
+        assert_eq!(
+            names,
+            vec![header::SERVER, HeaderName::from_static("cf-ray")]
+        );
+
+        assert!(parse_header_names(&["not a header"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_variables() {
+        let env = vars(&[("DOCS_HOST", "docs.example.com")]);
+        let variables = parse_variables(&["HOST=$DOCS_HOST", "QUERY=a=b"], &env).unwrap();
+        assert_eq!(
+            variables.get("HOST").map(String::as_str),
+            Some("docs.example.com")
+        );
+        assert_eq!(variables.get("QUERY").map(String::as_str), Some("a=b"));
+
+        assert!(parse_variables(&["HOST"], &env).is_err());
+        assert!(parse_variables(&["=example.com"], &env).is_err());
+    }
+
+    #[test]
+    fn test_parse_scheme_timeouts() {
+        let timeouts = parse_scheme_timeouts(&["mailto=30", "HTTPS=5"]).unwrap();
+        assert_eq!(timeouts.get("mailto"), Some(&Duration::from_secs(30)));
+        assert_eq!(timeouts.get("https"), Some(&Duration::from_secs(5)));
+
+        assert!(parse_scheme_timeouts(&["mailto"]).is_err());
+        assert!(parse_scheme_timeouts(&["mailto=soon"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_host_concurrency() {
+        let limits = parse_host_concurrency(&[r"github\.com$=2", "^example\\.org$=8"]).unwrap();
+        assert_eq!(limits[0].0.as_str(), r"github\.com$");
+        assert_eq!(limits[0].1, 2);
+        assert_eq!(limits[1].0.as_str(), "^example\\.org$");
+        assert_eq!(limits[1].1, 8);
+
+        assert!(parse_host_concurrency(&["github.com"]).is_err());
+        assert!(parse_host_concurrency(&["github.com=0"]).is_err());
+        assert!(parse_host_concurrency(&["github.com=many"]).is_err());
+        assert!(parse_host_concurrency(&["(=2"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_uri_timeouts() {
+        let timeouts = parse_uri_timeouts(&["example\\.com/slow=30", "page\\?a=b=45"]).unwrap();
+        assert_eq!(timeouts[0].0.as_str(), "example\\.com/slow");
+        assert_eq!(timeouts[0].1, Duration::from_secs(30));
+        assert_eq!(timeouts[1].0.as_str(), "page\\?a=b");
+        assert_eq!(timeouts[1].1, Duration::from_secs(45));
+
+        assert!(parse_uri_timeouts(&["example\\.com/slow"]).is_err());
+        assert!(parse_uri_timeouts(&["[=30"]).is_err());
+        assert!(parse_uri_timeouts(&["example=soon"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_expected_status() {
+        let expected = parse_expected_status(&["example\\.com/login=401", "a=b=403"]).unwrap();
+        assert_eq!(expected[0].0.as_str(), "example\\.com/login");
+        assert_eq!(expected[0].1, StatusCode::UNAUTHORIZED);
+        assert_eq!(expected[1].0.as_str(), "a=b");
+        assert_eq!(expected[1].1, StatusCode::FORBIDDEN);
+
+        assert!(parse_expected_status(&["example\\.com/login"]).is_err());
+        assert!(parse_expected_status(&["[=401"]).is_err());
+        assert!(parse_expected_status(&["example=ok"]).is_err());
+        assert!(parse_expected_status(&["example=1000"]).is_err());
     }
 
     #[test]
@@ -95,12 +437,29 @@ mod tests {
         );
 
         let mut actual = HeaderMap::new();
-        let auth_header = parse_basic_auth("aladin:abretesesamo").unwrap();
+        let auth_header = parse_basic_auth("aladin:abretesesamo", vars(&[])).unwrap();
         actual.typed_insert(auth_header);
 
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_parse_basic_auth_with_env_var() {
+        let env = vars(&[("PASSWORD", "abretesesamo")]);
+        let mut expected = HeaderMap::new();
+        expected.typed_insert(Authorization::basic("aladin", "abretesesamo"));
+
+        let mut actual = HeaderMap::new();
+        actual.typed_insert(parse_basic_auth("aladin:${PASSWORD}", &env).unwrap());
+
+        assert_eq!(expected, actual);
+        assert!(parse_basic_auth("aladin:$UNSET_PASSWORD", &env).is_err());
+
+        // `$$` is a literal `$`
+        let auth = parse_basic_auth("user:pa$$word", &env).unwrap();
+        assert_eq!(auth.password(), "pa$word");
+    }
+
     #[test]
     fn test_parse_remap() {
         let remaps =
@@ -0,0 +1,142 @@
+//! Minimal HTTP server for `--serve`, which serves the report of a run as an
+//! HTML page (and the JSON stats it is based on at `/report.json`), e.g. to
+//! review large results on a CI machine.
+//!
+//! Only `GET` and `HEAD` requests are supported and every connection gets
+//! closed after the response.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::{Context, Result};
+use log::debug;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{formatters::stats::HtmlResponseStats, stats::ResponseStats};
+
+/// Rendered report, which gets served until lychee gets stopped
+pub(crate) struct Report {
+    html: String,
+    json: String,
+}
+
+impl Report {
+    pub(crate) fn new(stats: &ResponseStats) -> Result<Self> {
+        Ok(Self {
+            html: HtmlResponseStats(stats).to_string(),
+            json: serde_json::to_string_pretty(stats).context("Cannot format stats as JSON")?,
+        })
+    }
+}
+
+/// Bind the server to `addr`, so that binding errors get reported before
+/// starting the link check
+pub(crate) async fn bind(addr: SocketAddr) -> Result<TcpListener> {
+    TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Cannot serve the report at {addr}"))
+}
+
+/// Serve the report to all connections of `listener`
+pub(crate) async fn serve(listener: TcpListener, report: Report) -> Result<()> {
+    let report = Arc::new(report);
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let report = report.clone();
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream, &report).await {
+                debug!("Cannot serve the report to {peer}: {e}");
+            }
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream, report: &Report) -> std::io::Result<()> {
+    let (read, mut write) = stream.split();
+    let mut lines = BufReader::new(read).lines();
+    let request_line = lines.next_line().await?.unwrap_or_default();
+    // Skip the headers, which don't matter for a static report
+    while let Some(line) = lines.next_line().await? {
+        if line.is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    let path = path.split_once('?').map_or(path, |(path, _)| path);
+    let (status, content_type, body) = match (method, path) {
+        ("GET" | "HEAD", "/" | "/index.html") => {
+            ("200 OK", "text/html; charset=utf-8", report.html.as_str())
+        }
+        ("GET" | "HEAD", "/report.json") => ("200 OK", "application/json", report.json.as_str()),
+        ("GET" | "HEAD", _) => ("404 Not Found", "text/plain; charset=utf-8", "Not found"),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "Method not allowed",
+        ),
+    };
+
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    write.write_all(head.as_bytes()).await?;
+    if method != "HEAD" {
+        write.write_all(body.as_bytes()).await?;
+    }
+    write.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use lychee_lib::{ErrorKind, InputSource, Response, Status, Uri};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_serve_report() {
+        let mut stats = ResponseStats::new();
+        for (uri, status) in [
+            ("https://example.com/", Status::Ok(StatusCode::OK)),
+            ("https://example.org/", Status::Ok(StatusCode::OK)),
+            (
+                "https://example.net/",
+                Status::Error(ErrorKind::InvalidUrlHost),
+            ),
+        ] {
+            stats.add(Response::new(
+                Uri::try_from(uri).unwrap(),
+                status,
+                InputSource::Stdin,
+            ));
+        }
+        let listener = bind("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, Report::new(&stats).unwrap()));
+
+        let response = reqwest::get(format!("http://{addr}/")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let html = Box::pin(response.text()).await.unwrap();
+        assert!(html.contains("<tr><td>\u{1f50d} Total</td><td>3</td></tr>"));
+        assert!(html.contains("<tr><td>\u{2705} Successful</td><td>2</td></tr>"));
+        assert!(html.contains("https://example.net/"));
+
+        let response = reqwest::get(format!("http://{addr}/report.json"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = Box::pin(response.text()).await.unwrap();
+        assert!(json.contains("\"total\": 3"));
+
+        let response = reqwest::get(format!("http://{addr}/missing"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
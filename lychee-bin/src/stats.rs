@@ -1,9 +1,19 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
 
-use lychee_lib::{CacheStatus, InputSource, Response, ResponseBody, Status};
-use serde::Serialize;
+use anyhow::{Context, Result};
+use lychee_lib::{CacheStatus, ErrorKind, InputSource, Response, ResponseBody, Status, Uri};
+use serde::{Deserialize, Serialize};
 
-#[derive(Default, Serialize)]
+use crate::suggestions::{suggest, Fix};
+
+/// Statistics of a run, which can be read back from a JSON report (e.g. to
+/// compare runs). Errors of failed links only keep their kind and message
+/// when deserialized.
+#[derive(Default, Serialize, Deserialize)]
 pub(crate) struct ResponseStats {
     pub(crate) total: usize,
     pub(crate) successful: usize,
@@ -12,9 +22,24 @@ pub(crate) struct ResponseStats {
     pub(crate) timeouts: usize,
     pub(crate) redirects: usize,
     pub(crate) excludes: usize,
+    #[serde(default)]
+    pub(crate) warnings: usize,
+    /// Links which were not checked, because the time budget or the maximum
+    /// number of checks was exceeded. Like excluded links, these don't fail
+    /// the run.
+    #[serde(default)]
+    pub(crate) skipped: usize,
     pub(crate) errors: usize,
     pub(crate) cached: usize,
     pub(crate) fail_map: HashMap<InputSource, HashSet<ResponseBody>>,
+    /// Links which got redirected, mapped to the URL they ended up at. Only
+    /// used for the redirects report, so it's not part of the JSON output.
+    #[serde(skip)]
+    pub(crate) redirect_map: BTreeMap<Uri, Uri>,
+    /// Suggested fixes for broken or outdated links, written with
+    /// `--suggestions`
+    #[serde(skip)]
+    pub(crate) suggestions: BTreeSet<Fix>,
 }
 
 impl ResponseStats {
@@ -31,25 +56,14 @@ impl ResponseStats {
             return;
         }
 
-        self.total += 1;
+        self.count(status, true);
 
-        match status {
-            Status::Ok(_) => self.successful += 1,
-            Status::Error(_) => self.failures += 1,
-            Status::UnknownStatusCode(_) => self.unknown += 1,
-            Status::Timeout(_) => self.timeouts += 1,
-            Status::Redirected(_) => self.redirects += 1,
-            Status::Excluded => self.excludes += 1,
-            Status::Unsupported(_) => (), // Just skip unsupported URI
-            Status::Cached(_) => self.cached += 1,
-        }
+        self.suggestions.extend(suggest(&response.1));
 
-        if let Status::Cached(cache_status) = status {
-            match cache_status {
-                CacheStatus::Ok(_) => self.successful += 1,
-                CacheStatus::Error(_) => self.failures += 1,
-                CacheStatus::Excluded | CacheStatus::Unsupported => self.excludes += 1,
-            }
+        match status {
+            Status::Warning(e) => self.add_redirect(&response.1.uri, e),
+            Status::Error(e) => self.add_redirect(&response.1.uri, e),
+            _ => (),
         }
 
         if matches!(
@@ -64,9 +78,121 @@ impl ResponseStats {
         };
     }
 
+    /// Count a link with the given status, or uncount it if `add` is false.
+    /// Cached links count as cached and as their cached result.
+    fn count(&mut self, status: &Status, add: bool) {
+        let step = |n: &mut usize| *n = if add { *n + 1 } else { n.saturating_sub(1) };
+
+        step(&mut self.total);
+        match status {
+            Status::Ok(_) => step(&mut self.successful),
+            Status::Error(_) => step(&mut self.failures),
+            Status::UnknownStatusCode(_) => step(&mut self.unknown),
+            Status::Timeout(_) => step(&mut self.timeouts),
+            Status::Redirected(_) => step(&mut self.redirects),
+            Status::Excluded => step(&mut self.excludes),
+            Status::Skipped(_) => step(&mut self.skipped),
+            Status::Unsupported(_) => (), // Just skip unsupported URI
+            Status::Cached(_) => step(&mut self.cached),
+            Status::Warning(_) => step(&mut self.warnings),
+        }
+
+        if let Status::Cached(cache_status) = status {
+            match cache_status {
+                CacheStatus::Ok(_) => step(&mut self.successful),
+                CacheStatus::Error(_) => step(&mut self.failures),
+                CacheStatus::Excluded | CacheStatus::Unsupported => step(&mut self.excludes),
+            }
+        }
+    }
+
+    /// Merge the stats of another run, e.g. of another shard of a CI job.
+    ///
+    /// Failing links which both runs report for the same input are only
+    /// counted once, with their statuses merged by [`Status::merge`]. Other
+    /// links can't be told apart, as the stats only keep their counts.
+    pub(crate) fn merge(&mut self, other: ResponseStats) {
+        self.total += other.total;
+        self.successful += other.successful;
+        self.failures += other.failures;
+        self.unknown += other.unknown;
+        self.timeouts += other.timeouts;
+        self.redirects += other.redirects;
+        self.excludes += other.excludes;
+        self.warnings += other.warnings;
+        self.skipped += other.skipped;
+        self.errors += other.errors;
+        self.cached += other.cached;
+        self.redirect_map.extend(other.redirect_map);
+        self.suggestions.extend(other.suggestions);
+
+        for (source, responses) in other.fail_map {
+            let mut merged: HashMap<Uri, ResponseBody> = self
+                .fail_map
+                .remove(&source)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|response| (response.uri.clone(), response))
+                .collect();
+            for response in responses {
+                let response = match merged.remove(&response.uri) {
+                    Some(mut existing) => {
+                        self.count(&existing.status, false);
+                        self.count(&response.status, false);
+                        existing.status = existing.status.merge(response.status);
+                        self.count(&existing.status, true);
+                        existing
+                    }
+                    None => response,
+                };
+                merged.insert(response.uri.clone(), response);
+            }
+            self.fail_map.insert(source, merged.into_values().collect());
+        }
+    }
+
+    /// Remember the final URL of a redirected link, if the error (or warning)
+    /// is about a redirect
+    fn add_redirect(&mut self, uri: &Uri, e: &ErrorKind) {
+        if let ErrorKind::Redirect(target)
+        | ErrorKind::TrailingSlashRedirect(target)
+        | ErrorKind::CrossHostRedirect(target)
+        | ErrorKind::InsecureFinalUrl(target) = e
+        {
+            self.redirect_map.insert(uri.clone(), target.clone());
+        }
+    }
+
+    /// Aggregate failures across all inputs by URI
+    ///
+    /// Every failing URI is returned once, together with a representative
+    /// response and the number of inputs referencing it. The result is sorted
+    /// by URI.
+    ///
+    /// The same URI found in several inputs is usually answered from the
+    /// cache after the first check, so a response of an actual check is
+    /// preferred as the representative, as it has the more useful error.
+    pub(crate) fn unique_failures(&self) -> Vec<(&ResponseBody, usize)> {
+        let mut failures: BTreeMap<&Uri, (&ResponseBody, usize)> = BTreeMap::new();
+        for response in self.fail_map.values().flatten() {
+            let (representative, count) = failures.entry(&response.uri).or_insert((response, 0));
+            if matches!(representative.status, Status::Cached(_))
+                && !matches!(response.status, Status::Cached(_))
+            {
+                *representative = response;
+            }
+            *count += 1;
+        }
+        failures.into_values().collect()
+    }
+
+    /// Check if all links were successful, which determines the exit code.
+    /// Warnings and skipped links don't fail a run. Timeouts only count as
+    /// successful if `timeout_as_success` is set.
     #[inline]
-    pub(crate) const fn is_success(&self) -> bool {
-        self.total == self.successful + self.excludes
+    pub(crate) const fn is_success(&self, timeout_as_success: bool) -> bool {
+        let timeouts = if timeout_as_success { self.timeouts } else { 0 };
+        self.total == self.successful + self.excludes + self.warnings + self.skipped + timeouts
     }
 
     #[inline]
@@ -75,16 +201,32 @@ impl ResponseStats {
     }
 }
 
+/// Read the JSON reports of several runs (e.g. of a sharded CI job) and
+/// merge them into one
+pub(crate) fn merge_reports(paths: &[PathBuf]) -> Result<ResponseStats> {
+    let mut stats = ResponseStats::new();
+    for path in paths {
+        let report = fs::read_to_string(path)
+            .with_context(|| format!("Cannot read report {}", path.display()))?;
+        let report: ResponseStats = serde_json::from_str(&report)
+            .with_context(|| format!("Cannot parse JSON report {}", path.display()))?;
+        stats.merge(report);
+    }
+    Ok(stats)
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::{HashMap, HashSet};
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
     use http::StatusCode;
-    use lychee_lib::{ClientBuilder, InputSource, Response, ResponseBody, Status, Uri};
+    use lychee_lib::{
+        CacheStatus, ClientBuilder, ErrorKind, InputSource, Response, ResponseBody, Status, Uri,
+    };
     use reqwest::Url;
     use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
 
-    use super::ResponseStats;
+    use super::{merge_reports, ResponseStats};
 
     fn website(url: &str) -> Uri {
         Uri::from(Url::parse(url).expect("Expected valid Website URI"))
@@ -115,13 +257,14 @@ mod tests {
         let mut stats = ResponseStats::new();
         assert!(stats.is_empty());
 
-        stats.add(Response(
-            InputSource::Stdin,
-            ResponseBody {
-                uri: website("https://example.com/ok"),
-                status: Status::Ok(StatusCode::OK),
-            },
-        ));
+        stats.add(
+            Response::new(
+                website("https://example.com/ok"),
+                Status::Ok(StatusCode::OK),
+                InputSource::Stdin,
+            )
+            .with_attempts(1),
+        );
 
         assert!(!stats.is_empty());
     }
@@ -150,4 +293,273 @@ mod tests {
 
         assert_eq!(stats.fail_map, expected_map);
     }
+
+    #[test]
+    fn test_timeout_as_success() {
+        let mut stats = ResponseStats::new();
+        stats.add(
+            Response::new(
+                website("https://example.com/ok"),
+                Status::Ok(StatusCode::OK),
+                InputSource::Stdin,
+            )
+            .with_attempts(1),
+        );
+        stats.add(
+            Response::new(
+                website("https://example.com/slow"),
+                Status::Timeout(None),
+                InputSource::Stdin,
+            )
+            .with_attempts(1),
+        );
+        assert!(!stats.is_success(false));
+        assert!(stats.is_success(true));
+
+        stats.add(
+            Response::new(
+                website("https://example.com/404"),
+                Status::Cached(CacheStatus::Error(Some(404))),
+                InputSource::Stdin,
+            )
+            .with_attempts(1),
+        );
+        assert!(!stats.is_success(true));
+    }
+
+    #[test]
+    fn test_warnings() {
+        let mut stats = ResponseStats::new();
+        stats.add(
+            Response::new(
+                website("https://example.com/moved"),
+                Status::Warning(Box::new(ErrorKind::Redirect(website(
+                    "https://example.com/new",
+                )))),
+                InputSource::Stdin,
+            )
+            .with_attempts(1),
+        );
+        assert_eq!(stats.warnings, 1);
+        assert!(stats.fail_map.is_empty());
+        assert!(stats.is_success(false));
+    }
+
+    #[test]
+    fn test_skipped() {
+        let mut stats = ResponseStats::new();
+        stats.add(Response::new(
+            website("https://example.com/"),
+            Status::Skipped(Box::new(ErrorKind::CheckLimitReached(1))),
+            InputSource::Stdin,
+        ));
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(stats.excludes, 0);
+        assert!(stats.is_success(false));
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut stats = ResponseStats::new();
+        let source = InputSource::FsPath("docs/README.md".into());
+        let statuses = [
+            Status::Ok(StatusCode::OK),
+            Status::Excluded,
+            Status::Redirected(StatusCode::MOVED_PERMANENTLY),
+            Status::Timeout(Some(StatusCode::REQUEST_TIMEOUT)),
+            Status::Timeout(None),
+            Status::Error(ErrorKind::InvalidUrlHost),
+            Status::Cached(CacheStatus::Error(None)),
+        ];
+        for (i, status) in statuses.into_iter().enumerate() {
+            stats.add(
+                Response::new(
+                    website(&format!("https://example.com/{i}")),
+                    status,
+                    source.clone(),
+                )
+                .with_attempts(2)
+                .with_headers(vec![("server".to_string(), "nginx".to_string())]),
+            );
+        }
+        stats.add(
+            Response::new(
+                website("https://example.com/teapot"),
+                Status::Error(ErrorKind::InvalidUrlHost),
+                InputSource::RemoteUrl(Box::new(Url::parse("https://example.com/docs").unwrap())),
+            )
+            .with_attempts(1),
+        );
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let deserialized: ResponseStats = serde_json::from_str(&json).unwrap();
+
+        // The order of failures per input is not stable
+        let failures = |stats: &ResponseStats| -> BTreeMap<String, BTreeSet<String>> {
+            stats
+                .fail_map
+                .iter()
+                .map(|(source, responses)| {
+                    let responses = responses
+                        .iter()
+                        .map(|response| serde_json::to_string(response).unwrap())
+                        .collect();
+                    (source.to_string(), responses)
+                })
+                .collect()
+        };
+        assert_eq!(failures(&deserialized), failures(&stats));
+        assert_eq!(deserialized.total, 8);
+        assert_eq!(deserialized.successful, 1);
+        assert_eq!(deserialized.excludes, 1);
+        assert_eq!(deserialized.timeouts, 2);
+        assert_eq!(deserialized.cached, 1);
+        assert_eq!(deserialized.fail_map.len(), 2);
+        let failures = &deserialized.fail_map[&source];
+        assert_eq!(failures.len(), 5);
+        let timeout = failures
+            .iter()
+            .find(|response| response.uri == website("https://example.com/3"))
+            .unwrap();
+        assert_eq!(
+            timeout.status,
+            Status::Timeout(Some(StatusCode::REQUEST_TIMEOUT))
+        );
+        assert_eq!(timeout.attempts, 2);
+        assert_eq!(
+            timeout.headers,
+            vec![("server".to_string(), "nginx".to_string())]
+        );
+        let error = failures
+            .iter()
+            .find(|response| response.uri == website("https://example.com/5"))
+            .unwrap();
+        assert_eq!(
+            error.status.error().map(ErrorKind::kind),
+            Some("InvalidUrlHost")
+        );
+    }
+
+    #[test]
+    fn test_deserialize_old_report() {
+        let mut stats = ResponseStats::new();
+        let source = InputSource::RemoteUrl(Box::new(Url::parse("https://example.com").unwrap()));
+        stats.add(
+            Response::new(
+                website("https://example.com/teapot"),
+                Status::Error(ErrorKind::InvalidUrlHost),
+                source.clone(),
+            )
+            .with_attempts(1),
+        );
+
+        // Older releases didn't write warnings, attempts and errors
+        let mut json = serde_json::to_value(&stats).unwrap();
+        json.as_object_mut().unwrap().remove("warnings").unwrap();
+        for failures in json["fail_map"].as_object_mut().unwrap().values_mut() {
+            for failure in failures.as_array_mut().unwrap() {
+                let failure = failure.as_object_mut().unwrap();
+                failure.remove("attempts").unwrap();
+                failure.remove("error").unwrap();
+            }
+        }
+
+        let deserialized: ResponseStats = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.failures, 1);
+        let failure = deserialized.fail_map[&source].iter().next().unwrap();
+        assert_eq!(failure.status.to_string(), "Failed: URL is missing a host");
+        assert_eq!(failure.attempts, 0);
+    }
+
+    #[test]
+    fn test_unique_failures() {
+        let broken = website("https://example.com/404");
+        let mut stats = ResponseStats::new();
+        for file in ["a.md", "b.md", "c.md"] {
+            stats.add(
+                Response::new(
+                    broken.clone(),
+                    Status::Cached(CacheStatus::Error(Some(404))),
+                    InputSource::FsPath(file.into()),
+                )
+                .with_attempts(1),
+            );
+        }
+        stats.add(
+            Response::new(
+                broken.clone(),
+                Status::Error(ErrorKind::InvalidUrlHost),
+                InputSource::FsPath("d.md".into()),
+            )
+            .with_attempts(1),
+        );
+        stats.add(
+            Response::new(
+                website("https://example.com/ok"),
+                Status::Ok(StatusCode::OK),
+                InputSource::Stdin,
+            )
+            .with_attempts(1),
+        );
+
+        // The response of the actual check is preferred over cached ones
+        let failures = stats.unique_failures();
+        assert_eq!(failures.len(), 1);
+        let (response, count) = failures[0];
+        assert_eq!(response.uri, broken);
+        assert_eq!(response.status, Status::Error(ErrorKind::InvalidUrlHost));
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn test_merge_reports() {
+        let response = |uri: &str, status| {
+            Response::new(
+                website(uri),
+                status,
+                InputSource::FsPath("docs/README.md".into()),
+            )
+            .with_attempts(1)
+        };
+        let mut first = ResponseStats::new();
+        first.add(response(
+            "https://example.com/ok",
+            Status::Ok(StatusCode::OK),
+        ));
+        first.add(response("https://example.com/flaky", Status::Timeout(None)));
+        let mut second = ResponseStats::new();
+        second.add(response(
+            "https://example.com/flaky",
+            Status::Error(ErrorKind::InvalidUrlHost),
+        ));
+        second.add(response(
+            "https://example.com/moved",
+            Status::Redirected(StatusCode::MOVED_PERMANENTLY),
+        ));
+
+        let dir = tempfile::tempdir().unwrap();
+        let paths = [
+            dir.path().join("first.json"),
+            dir.path().join("second.json"),
+        ];
+        for (path, stats) in paths.iter().zip([first, second]) {
+            std::fs::write(path, serde_json::to_string(&stats).unwrap()).unwrap();
+        }
+        let merged = merge_reports(&paths).unwrap();
+
+        // The flaky link is only counted once, with the error of the second run
+        assert_eq!(merged.total, 3);
+        assert_eq!(merged.successful, 1);
+        assert_eq!(merged.timeouts, 0);
+        assert_eq!(merged.failures, 1);
+        assert_eq!(merged.redirects, 1);
+        let failures = &merged.fail_map[&InputSource::FsPath("docs/README.md".into())];
+        assert_eq!(failures.len(), 2);
+        let flaky = failures
+            .iter()
+            .find(|r| r.uri == website("https://example.com/flaky"))
+            .unwrap();
+        assert!(matches!(flaky.status, Status::Error(_)));
+        assert!(!merged.is_success(false));
+    }
 }
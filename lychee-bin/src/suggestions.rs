@@ -0,0 +1,159 @@
+//! Suggested fixes for broken or outdated links, which can be written as JSON
+//! with `--suggestions` to be processed by other tools (e.g. to rewrite the
+//! links automatically).
+
+use lychee_lib::{ErrorKind, ResponseBody, Status, Uri};
+use reqwest::Url;
+use serde::Serialize;
+
+/// Reason for suggesting the HTTPS version of a link
+const REASON_HTTPS: &str = "HTTPS is available";
+/// Reason for suggesting the target of a redirect
+const REASON_REDIRECT: &str = "Redirected";
+/// Reason for suggesting a similarly named file
+const REASON_SIMILAR_FILE: &str = "File not found, but a similar file exists";
+/// Reason for suggesting the snapshot of a dead link
+const REASON_ARCHIVED: &str = "Dead link, but archived by the Wayback Machine";
+
+/// A suggested fix for a link
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct Fix {
+    /// The link as found in the input
+    pub(crate) original: String,
+    /// The link to use instead
+    pub(crate) suggestion: String,
+    /// Why the link should be replaced
+    pub(crate) reason: &'static str,
+}
+
+impl Fix {
+    fn new(original: &Uri, suggestion: &impl ToString, reason: &'static str) -> Self {
+        Self {
+            original: original.to_string(),
+            suggestion: suggestion.to_string(),
+            reason,
+        }
+    }
+}
+
+/// Suggest a fix for the link of a response, if it's broken or outdated and
+/// a likely replacement is known:
+/// - the HTTPS version of an insecure link (with `--require-https` or `--hsts`)
+/// - the target of a redirect (with `--warn-on-redirects`)
+/// - a file with a similar name in the same directory for missing files
+/// - the snapshot of a dead link in the Wayback Machine (with `--wayback`)
+pub(crate) fn suggest(response: &ResponseBody) -> Option<Fix> {
+    let error = match &response.status {
+        Status::Error(e) => e,
+        Status::Warning(e) => &**e,
+        _ => return None,
+    };
+    let uri = &response.uri;
+    match error {
+        ErrorKind::InsecureURL(https) | ErrorKind::HstsUpgrade(https) => {
+            Some(Fix::new(uri, https, REASON_HTTPS))
+        }
+        ErrorKind::Redirect(target) | ErrorKind::TrailingSlashRedirect(target) => {
+            Some(Fix::new(uri, target, REASON_REDIRECT))
+        }
+        ErrorKind::Archived(_, snapshot) => Some(Fix::new(uri, snapshot, REASON_ARCHIVED)),
+        ErrorKind::InvalidFilePath(..) => {
+            let url = Url::from_file_path(error.suggestion()?).ok()?;
+            Some(Fix::new(uri, &url, REASON_SIMILAR_FILE))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+
+    fn response(uri: &str, status: Status) -> ResponseBody {
+        ResponseBody::new(Uri::try_from(uri).unwrap(), status)
+    }
+
+    fn uri(uri: &str) -> Uri {
+        Uri::try_from(uri).unwrap()
+    }
+
+    #[test]
+    fn test_https_suggestion() {
+        let insecure = response(
+            "http://example.com/page",
+            Status::Error(ErrorKind::InsecureURL(uri("https://example.com/page"))),
+        );
+        assert_eq!(
+            suggest(&insecure),
+            Some(Fix {
+                original: "http://example.com/page".to_string(),
+                suggestion: "https://example.com/page".to_string(),
+                reason: REASON_HTTPS,
+            })
+        );
+        let upgraded = response(
+            "http://example.com/page",
+            Status::Warning(Box::new(ErrorKind::HstsUpgrade(uri(
+                "https://example.com/page",
+            )))),
+        );
+        assert_eq!(suggest(&upgraded), suggest(&insecure));
+    }
+
+    #[test]
+    fn test_redirect_suggestion() {
+        let redirected = response(
+            "https://example.com/old",
+            Status::Warning(Box::new(ErrorKind::Redirect(uri(
+                "https://example.com/new",
+            )))),
+        );
+        assert_eq!(
+            suggest(&redirected),
+            Some(Fix {
+                original: "https://example.com/old".to_string(),
+                suggestion: "https://example.com/new".to_string(),
+                reason: REASON_REDIRECT,
+            })
+        );
+
+        // Redirects to a different host (e.g. a parking page) are no fix
+        let parked = response(
+            "https://example.com/",
+            Status::Error(ErrorKind::CrossHostRedirect(uri(
+                "https://parked.example.net/",
+            ))),
+        );
+        assert_eq!(suggest(&parked), None);
+    }
+
+    #[test]
+    fn test_archived_suggestion() {
+        let snapshot = "https://web.archive.org/web/2020/https://example.com/gone";
+        let dead = response(
+            "https://example.com/gone",
+            Status::Error(ErrorKind::Archived(StatusCode::NOT_FOUND, uri(snapshot))),
+        );
+        assert_eq!(
+            suggest(&dead),
+            Some(Fix {
+                original: "https://example.com/gone".to_string(),
+                suggestion: snapshot.to_string(),
+                reason: REASON_ARCHIVED,
+            })
+        );
+    }
+
+    #[test]
+    fn test_no_suggestion() {
+        let ok = response("https://example.com/", Status::Ok(StatusCode::OK));
+        assert_eq!(suggest(&ok), None);
+        let not_found = response(
+            "https://example.com/missing",
+            Status::Error(ErrorKind::InvalidUrlHost),
+        );
+        assert_eq!(suggest(&not_found), None);
+    }
+}
@@ -9,7 +9,10 @@ mod cli {
 
     use assert_cmd::Command;
     use http::StatusCode;
-    use predicates::str::{contains, is_empty};
+    use predicates::{
+        boolean::PredicateBooleanExt,
+        str::{contains, is_empty},
+    };
     use uuid::Uuid;
 
     type Result<T> = std::result::Result<T, Box<dyn Error>>;
@@ -50,6 +53,8 @@ mod cli {
         timeouts: usize,
         redirects: usize,
         excludes: usize,
+        warnings: usize,
+        skipped: usize,
         errors: usize,
         cached: usize,
     }
@@ -65,6 +70,8 @@ mod cli {
   "timeouts": {},
   "redirects": {},
   "excludes": {},
+  "warnings": {},
+  "skipped": {},
   "errors": {},
   "cached": {},
   "fail_map": {{}}
@@ -76,6 +83,8 @@ mod cli {
                 self.timeouts,
                 self.redirects,
                 self.excludes,
+                self.warnings,
+                self.skipped,
                 self.errors,
                 self.cached
             )
@@ -288,6 +297,37 @@ mod cli {
             ));
     }
 
+    #[tokio::test]
+    async fn test_auth_required_hint() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::UNAUTHORIZED);
+        let hint = "Some links require authentication (401/407)";
+
+        main_command()
+            .arg("-")
+            .arg("--retry-wait-time")
+            .arg("0")
+            .write_stdin(mock_server.uri())
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains(hint));
+
+        // No hint once credentials are configured
+        main_command()
+            .arg("-")
+            .arg("--basic-auth")
+            .arg("user:password")
+            .arg("--retry-wait-time")
+            .arg("0")
+            .write_stdin(mock_server.uri())
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains(hint).not());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_stdin_input() {
         let mut cmd = main_command();
@@ -327,6 +367,29 @@ mod cli {
             .success();
     }
 
+    #[tokio::test]
+    async fn test_verbose_timings() {
+        let mut cmd = main_command();
+        let mock_server = mock_server!(
+            StatusCode::OK,
+            set_delay(std::time::Duration::from_millis(100))
+        );
+
+        cmd.arg("-")
+            .arg("-vv")
+            .arg("--no-progress")
+            .write_stdin(mock_server.uri())
+            .assert()
+            .success()
+            .stdout(
+                predicates::str::is_match(format!(
+                    r"(?m)^\x{{2714}} \[ *[1-9]\d{{2,}}ms\] {}/ \(TTFB [1-9]\d{{2,}}ms\)$",
+                    regex::escape(&mock_server.uri())
+                ))
+                .unwrap(),
+            );
+    }
+
     #[test]
     fn test_missing_file_ok_if_skip_missing() {
         let mut cmd = main_command();
@@ -427,6 +490,158 @@ mod cli {
         Ok(())
     }
 
+    /// Test that failing URIs are reported once with their reference count
+    #[tokio::test]
+    async fn test_unique_failures_format() -> Result<()> {
+        let mut cmd = main_command();
+
+        let dir = tempfile::tempdir()?;
+        let mock_server = mock_server!(StatusCode::NOT_FOUND);
+        let mut file_a = File::create(dir.path().join("a.md"))?;
+        let mut file_b = File::create(dir.path().join("b.md"))?;
+
+        writeln!(file_a, "{}", mock_server.uri().as_str())?;
+        writeln!(file_b, "{}", mock_server.uri().as_str())?;
+
+        cmd.arg(dir.path().join("*.md"))
+            .arg("--format")
+            .arg("unique")
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains("1 unique failing URI"))
+            .stdout(contains(format!(
+                "[404] {}/ | Network error: Not Found (2 references)",
+                mock_server.uri()
+            )));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_csv_format() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::NOT_FOUND);
+
+        main_command()
+            .write_stdin(mock_server.uri())
+            .arg("--format")
+            .arg("csv")
+            .arg("--retry-wait-time")
+            .arg("0")
+            .arg("-")
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains("source,uri,status,code,message\n"))
+            .stdout(contains(format!(
+                "stdin,{}/,Failed,404,Network error: Not Found\n",
+                mock_server.uri()
+            )));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_reports() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mock_ok = mock_server!(StatusCode::OK);
+        let mock_broken = mock_server!(StatusCode::NOT_FOUND);
+        let input = dir.path().join("README.md");
+
+        // Two runs with an overlapping broken link
+        let first = dir.path().join("first.json");
+        let second = dir.path().join("second.json");
+        for (report, links) in [
+            (&first, format!("{}\n{}", mock_ok.uri(), mock_broken.uri())),
+            (&second, mock_broken.uri()),
+        ] {
+            fs::write(&input, links)?;
+            main_command()
+                .arg(&input)
+                .arg("--format")
+                .arg("json")
+                .arg("--output")
+                .arg(report)
+                .arg("--retry-wait-time")
+                .arg("0")
+                .assert()
+                .failure()
+                .code(2);
+        }
+
+        main_command()
+            .arg("--merge-reports")
+            .arg(&first)
+            .arg(&second)
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains("2 Total"))
+            .stdout(contains("1 OK"))
+            .stdout(contains("1 Error"));
+
+        Ok(())
+    }
+
+    /// Test that only links on added lines of a diff get checked
+    #[tokio::test]
+    async fn test_diff_input() -> Result<()> {
+        let mut cmd = main_command();
+        let mock_server_added = mock_server!(StatusCode::OK);
+        let mock_server_removed = mock_server!(StatusCode::NOT_FOUND);
+
+        let diff = format!(
+            "--- a/README.md\n+++ b/README.md\n@@ -1,2 +1,2 @@\n Title\n-{}\n+{}\n",
+            mock_server_removed.uri(),
+            mock_server_added.uri()
+        );
+
+        cmd.arg("--diff")
+            .arg("-")
+            .arg("--verbose")
+            .write_stdin(diff)
+            .assert()
+            .success()
+            .stdout(contains("1 Total"))
+            .stdout(contains("1 OK"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_inputs_from_manifest() -> Result<()> {
+        let mut cmd = main_command();
+
+        let dir = tempfile::tempdir()?;
+        let mock_server_file = mock_server!(StatusCode::OK);
+        let mock_server_link = mock_server!(StatusCode::OK);
+        let mock_server_page = mock_server!(
+            StatusCode::OK,
+            set_body_string(format!(r#"<a href="{}">link</a>"#, mock_server_link.uri()))
+        );
+        let file = dir.path().join("a.md");
+        fs::write(&file, mock_server_file.uri())?;
+        let manifest = dir.path().join("inputs.txt");
+        fs::write(
+            &manifest,
+            format!(
+                "# Inputs computed in CI\n{}\n\n{}\n",
+                file.display(),
+                mock_server_page.uri()
+            ),
+        )?;
+
+        cmd.arg("--inputs-from")
+            .arg(&manifest)
+            .arg("--verbose")
+            .assert()
+            .success()
+            .stdout(contains("2 Total"))
+            .stdout(contains("2 OK"));
+
+        Ok(())
+    }
+
     /// Test excludes
     #[test]
     fn test_exclude_wildcard() -> Result<()> {
@@ -493,6 +708,46 @@ mod cli {
         Ok(())
     }
 
+    #[test]
+    fn test_validate_config() {
+        main_command()
+            .arg("--validate")
+            .arg("--exclude")
+            .arg("[invalid")
+            .arg("--headers")
+            .arg("no-value")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(contains("Invalid configuration"))
+            .stderr(contains("Invalid exclude pattern `[invalid`"))
+            .stderr(contains("Invalid header `no-value`"));
+
+        main_command()
+            .arg("--validate")
+            .arg("--input-format")
+            .arg("rst")
+            .assert()
+            .failure()
+            .stderr(contains("Unknown input format rst"));
+
+        main_command()
+            .arg("--validate")
+            .arg("--retry-jitter")
+            .arg("none")
+            .assert()
+            .failure()
+            .stderr(contains("Unknown retry jitter none"));
+
+        main_command()
+            .arg("--validate")
+            .arg("--exclude")
+            .arg("valid")
+            .assert()
+            .success()
+            .stdout(contains("Configuration is valid"));
+    }
+
     #[tokio::test]
     async fn test_example_config() -> Result<()> {
         let mock_server = mock_server!(StatusCode::OK);
@@ -593,6 +848,45 @@ mod cli {
         Ok(())
     }
 
+    #[test]
+    fn test_lycheeignore_file_whitespace() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join(".lycheeignore"),
+            "  # Indented comment\n\n   \nexample\\.com/excluded  \n\texample\\.org\n",
+        )?;
+        fs::write(
+            dir.path().join("TEST.md"),
+            "https://example.com/excluded https://example.com/included https://example.org",
+        )?;
+
+        main_command()
+            .current_dir(dir.path())
+            .arg("--dump")
+            .arg("TEST.md")
+            .assert()
+            .success()
+            .stdout("https://example.com/included\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_lycheeignore_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("TEST.md"), "https://example.com")?;
+
+        main_command()
+            .current_dir(dir.path())
+            .arg("--dump")
+            .arg("TEST.md")
+            .assert()
+            .success()
+            .stdout("https://example.com/\n");
+
+        Ok(())
+    }
+
     #[test]
     fn test_lycheeignore_and_exclude_file() -> Result<()> {
         let mut cmd = main_command();
@@ -685,6 +979,620 @@ mod cli {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_strip_query_param_cache() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::OK);
+        let dir = tempfile::tempdir()?;
+        let mut file = File::create(dir.path().join("c.md"))?;
+
+        writeln!(file, "{}/?utm_source=a", mock_server.uri())?;
+        writeln!(file, "{}/?utm_source=b", mock_server.uri())?;
+
+        main_command()
+            .current_dir(dir.path())
+            .arg("c.md")
+            .arg("--cache")
+            .arg("--strip-query-param")
+            .arg("utm_*")
+            .assert()
+            .success()
+            .stdout(contains("1 Total"));
+
+        // Both links end up as a single cache entry
+        let data = fs::read_to_string(dir.path().join(LYCHEE_CACHE_FILE))?;
+        assert_eq!(data.lines().count(), 1);
+        assert!(data.starts_with(&format!("{}/,200", mock_server.uri())));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_referer() -> Result<()> {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::header(
+            "referer",
+            "http://docs.example.com/",
+        ))
+        .respond_with(wiremock::ResponseTemplate::new(StatusCode::OK))
+        .mount(&mock_server)
+        .await;
+        wiremock::Mock::given(wiremock::matchers::any())
+            .respond_with(wiremock::ResponseTemplate::new(StatusCode::FORBIDDEN))
+            .mount(&mock_server)
+            .await;
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        fs::write(&input, format!("{}/image.png", mock_server.uri()))?;
+
+        main_command()
+            .arg(&input)
+            .arg("--base")
+            .arg("http://docs.example.com")
+            .arg("--retry-wait-time")
+            .arg("0")
+            .assert()
+            .failure();
+
+        main_command()
+            .arg(&input)
+            .arg("--base")
+            .arg("http://docs.example.com")
+            .arg("--referer")
+            .assert()
+            .success()
+            .stdout(contains("1 OK"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_suggestions() -> Result<()> {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::path("/old"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(StatusCode::MOVED_PERMANENTLY)
+                    .insert_header("Location", "/new"),
+            )
+            .mount(&mock_server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::path("/new"))
+            .respond_with(wiremock::ResponseTemplate::new(StatusCode::OK))
+            .mount(&mock_server)
+            .await;
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("guide.md"), "")?;
+        let input = dir.path().join("links.md");
+        fs::write(
+            &input,
+            format!("[old]({}/old)\n[guide](giude.md)\n", mock_server.uri()),
+        )?;
+        let suggestions = dir.path().join("suggestions.json");
+
+        main_command()
+            .arg(&input)
+            .arg("--suggestions")
+            .arg(&suggestions)
+            .assert()
+            .failure();
+
+        let suggestions: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(suggestions)?)?;
+        let suggestions = suggestions.as_array().unwrap();
+        assert_eq!(suggestions.len(), 2);
+        assert!(suggestions.contains(&serde_json::json!({
+            "original": format!("{}/old", mock_server.uri()),
+            "suggestion": format!("{}/new", mock_server.uri()),
+            "reason": "Redirected",
+        })));
+        let guide = suggestions
+            .iter()
+            .find(|s| s["original"].as_str().unwrap().ends_with("/giude.md"))
+            .unwrap();
+        assert!(guide["suggestion"].as_str().unwrap().ends_with("/guide.md"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_trailing_slash() -> Result<()> {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::path("/page"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(StatusCode::MOVED_PERMANENTLY)
+                    .insert_header("Location", "/page/"),
+            )
+            .mount(&mock_server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::path("/page/"))
+            .respond_with(wiremock::ResponseTemplate::new(StatusCode::OK))
+            .mount(&mock_server)
+            .await;
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        fs::write(&input, format!("{0}/page\n{0}/page/\n", mock_server.uri()))?;
+
+        main_command()
+            .arg(&input)
+            .arg("--trailing-slash")
+            .arg("add")
+            .assert()
+            .success()
+            .stdout(contains("1 Total"))
+            .stdout(contains("1 OK"));
+
+        // The canonical form of the link has a trailing slash
+        main_command()
+            .arg(&input)
+            .arg("--trailing-slash")
+            .arg("remove")
+            .arg("--verbose")
+            .assert()
+            .success()
+            .stdout(contains("1 Total"))
+            .stdout(contains("1 Warning"))
+            .stderr(contains(format!(
+                "Redirected to {}/page/, which only differs by a trailing slash",
+                mock_server.uri()
+            )));
+
+        // Both variants get checked otherwise
+        main_command()
+            .arg(&input)
+            .assert()
+            .success()
+            .stdout(contains("2 Total"));
+
+        // Links are checked and reported as written
+        fs::write(&input, format!("{}/page\n", mock_server.uri()))?;
+        main_command()
+            .arg(&input)
+            .arg("--trailing-slash")
+            .arg("add")
+            .arg("--verbose")
+            .assert()
+            .success()
+            .stdout(contains("1 Warning"))
+            .stderr(contains(format!("{}/page ", mock_server.uri())));
+
+        main_command()
+            .arg(&input)
+            .arg("--trailing-slash")
+            .arg("keep")
+            .assert()
+            .failure()
+            .stderr(contains("Unknown trailing slash policy keep"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prune_cache() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        let current = mock_server!(StatusCode::OK);
+        let stale = mock_server!(StatusCode::OK);
+
+        fs::write(&input, format!("{}\n{}\n", current.uri(), stale.uri()))?;
+        main_command()
+            .current_dir(dir.path())
+            .arg(&input)
+            .arg("--cache")
+            .assert()
+            .success();
+        let data = fs::read_to_string(dir.path().join(LYCHEE_CACHE_FILE))?;
+        assert_eq!(data.lines().count(), 2);
+
+        // The stale link is no longer referenced
+        fs::write(&input, current.uri())?;
+        main_command()
+            .current_dir(dir.path())
+            .arg(&input)
+            .arg("--cache")
+            .arg("--prune-cache")
+            .arg("--verbose")
+            .assert()
+            .success()
+            .stderr(contains("Pruned 1 stale cache entries"));
+        let data = fs::read_to_string(dir.path().join(LYCHEE_CACHE_FILE))?;
+        assert_eq!(data.lines().count(), 1);
+        assert!(data.starts_with(&format!("{}/,", current.uri())));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recheck_failures() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        // Both servers changed their status since the cache was written
+        let previously_ok = mock_server!(StatusCode::NOT_FOUND);
+        let previously_broken = mock_server!(StatusCode::OK);
+        let previously_timeout = mock_server!(StatusCode::OK);
+
+        fs::write(
+            &input,
+            format!(
+                "{}\n{}\n{}\n",
+                previously_ok.uri(),
+                previously_broken.uri(),
+                previously_timeout.uri()
+            ),
+        )?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        fs::write(
+            dir.path().join(LYCHEE_CACHE_FILE),
+            format!(
+                "{}/,200,{timestamp}\n{}/,404,{timestamp}\n{}/,Error,{timestamp}\n",
+                previously_ok.uri(),
+                previously_broken.uri(),
+                previously_timeout.uri()
+            ),
+        )?;
+
+        main_command()
+            .current_dir(dir.path())
+            .arg(&input)
+            .arg("--cache")
+            .arg("--recheck-failures")
+            .arg("--verbose")
+            .assert()
+            .success()
+            .stderr(contains(format!(
+                "[200] {}/ | OK (cached)\n",
+                previously_ok.uri()
+            )))
+            .stderr(contains(format!("[200] {}/\n", previously_broken.uri())))
+            .stderr(contains(format!("[200] {}/\n", previously_timeout.uri())));
+
+        // The cache gets updated with the results of the rechecked links
+        let data = fs::read_to_string(dir.path().join(LYCHEE_CACHE_FILE))?;
+        assert!(data.contains(&format!("{}/,200", previously_broken.uri())));
+        assert!(data.contains(&format!("{}/,200", previously_timeout.uri())));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        let broken = mock_server!(StatusCode::NOT_FOUND);
+        let slow = mock_server!(
+            StatusCode::OK,
+            set_delay(std::time::Duration::from_secs(30))
+        );
+
+        // All links get checked at once (the order of links within a file is
+        // not guaranteed), so the slow ones get cancelled once the broken
+        // link failed
+        fs::write(
+            &input,
+            format!("{}\n{}/a\n{}/b\n", broken.uri(), slow.uri(), slow.uri()),
+        )?;
+        let start = std::time::Instant::now();
+        main_command()
+            .arg(&input)
+            .arg("--fail-fast")
+            .arg("--timeout")
+            .arg("60")
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains("1 Total"))
+            .stdout(contains("1 Error"));
+        assert!(start.elapsed() < std::time::Duration::from_secs(30));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast_keeps_cache() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        let broken = mock_server!(StatusCode::NOT_FOUND);
+        let cached = mock_server!(StatusCode::OK);
+        // The second input only gets read after the broken link failed
+        let slow_input = mock_server!(
+            StatusCode::OK,
+            set_delay(std::time::Duration::from_secs(2)),
+            set_body_string(format!("<a href=\"{}\">Cached</a>", cached.uri()))
+        );
+
+        fs::write(&input, broken.uri())?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        fs::write(
+            dir.path().join(LYCHEE_CACHE_FILE),
+            format!("{}/,200,{timestamp}\n", cached.uri()),
+        )?;
+
+        // Links which were not collected yet are not pruned from the cache
+        main_command()
+            .current_dir(dir.path())
+            .arg(&input)
+            .arg(slow_input.uri())
+            .arg("--cache")
+            .arg("--prune-cache")
+            .arg("--fail-fast")
+            .arg("--verbose")
+            .assert()
+            .failure()
+            .code(2)
+            .stderr(contains("Pruned").not());
+        let data = fs::read_to_string(dir.path().join(LYCHEE_CACHE_FILE))?;
+        assert!(data.contains(&format!("{}/,200,", cached.uri())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deny_empty_urls() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("index.html");
+        fs::write(
+            &input,
+            r##"<a href="">Empty</a><a href="#">Top</a><a href="other.html">Other</a>"##,
+        )?;
+        fs::write(dir.path().join("other.html"), "")?;
+
+        // Each empty link gets reported, and the other links still get checked
+        main_command()
+            .arg(&input)
+            .arg("--deny-empty-urls")
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains("3 Total"))
+            .stdout(contains("1 OK"))
+            .stdout(contains("2 Errors"))
+            .stdout(contains("index.html | Empty URL found"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unresolved_variable() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("index.html");
+        fs::write(
+            &input,
+            r#"<a href="${DOCS}/other.html">Docs</a><a href="other.html">Other</a>"#,
+        )?;
+        fs::write(dir.path().join("other.html"), "")?;
+
+        // Only the link with the unknown variable fails
+        main_command()
+            .arg(&input)
+            .arg("--var")
+            .arg("HOST=example.com")
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains("2 Total"))
+            .stdout(contains("1 OK"))
+            .stdout(contains("Unresolved variable `${DOCS}`"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_feed() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let feed = dir.path().join("feed.xml");
+        fs::write(&feed, "<rss><link>other.html</rss>")?;
+        let input = dir.path().join("index.html");
+        fs::write(&input, r#"<a href="other.html">Other</a>"#)?;
+        fs::write(dir.path().join("other.html"), "")?;
+
+        // The malformed feed gets reported, and the other inputs still get checked
+        main_command()
+            .arg(&feed)
+            .arg(&input)
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains("2 Total"))
+            .stdout(contains("1 OK"))
+            .stdout(contains("1 Error"))
+            .stdout(contains("expected `</link>`, found `</rss>` on line 1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_mail_case() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("contact.md");
+        fs::write(
+            &input,
+            "mailto:foo@example.com mailto:foo@Example.COM mailto:Foo@example.com",
+        )?;
+
+        main_command()
+            .arg(&input)
+            .arg("--exclude-mail")
+            .assert()
+            .success()
+            .stdout(contains("3 Total"));
+
+        // Only the address with a different local part gets checked as well
+        main_command()
+            .arg(&input)
+            .arg("--exclude-mail")
+            .arg("--ignore-mail-case")
+            .arg("domain")
+            .assert()
+            .success()
+            .stdout(contains("2 Total"));
+
+        main_command()
+            .arg(&input)
+            .arg("--exclude-mail")
+            .arg("--ignore-mail-case")
+            .arg("all")
+            .assert()
+            .success()
+            .stdout(contains("1 Total"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_time_budget() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        let ok = mock_server!(StatusCode::OK);
+        let slow = mock_server!(
+            StatusCode::OK,
+            set_delay(std::time::Duration::from_secs(30))
+        );
+
+        fs::write(
+            &input,
+            format!("{}\n{}/a\n{}/b\n", ok.uri(), slow.uri(), slow.uri()),
+        )?;
+        let start = std::time::Instant::now();
+        main_command()
+            .arg(&input)
+            .arg("--time-budget")
+            .arg("2s")
+            .arg("--no-progress")
+            .arg("--timeout")
+            .arg("60")
+            .assert()
+            .success()
+            .stdout(contains("3 Total"))
+            .stdout(contains("1 OK"))
+            .stdout(contains("2 Skipped"))
+            .stdout(contains(format!(
+                "[SKIPPED] {}/a | Not checked, time budget of 2s exceeded",
+                slow.uri()
+            )));
+        assert!(start.elapsed() < std::time::Duration::from_secs(30));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_checks() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        let mock_server = mock_server!(StatusCode::OK);
+
+        let links: Vec<_> = (0..5)
+            .map(|i| format!("{}/{i}", mock_server.uri()))
+            .collect();
+        fs::write(&input, links.join("\n"))?;
+        main_command()
+            .arg(&input)
+            .arg("--max-checks")
+            .arg("2")
+            .arg("--no-progress")
+            .arg("--verbose")
+            .assert()
+            .success()
+            .stdout(contains("5 Total"))
+            .stdout(contains("2 OK"))
+            .stdout(contains("3 Skipped"))
+            .stdout(contains("Not checked, limit of 2 checks reached"));
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_exclude_self_links() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("README.md");
+        fs::write(&input, "[Top](#top)\n[Self](README.md)\n[Other](other.md)")?;
+        fs::write(dir.path().join("other.md"), "")?;
+
+        // Fragment-only links are skipped by default
+        main_command()
+            .arg(&input)
+            .assert()
+            .success()
+            .stdout(contains("2 Total"))
+            .stdout(contains("2 OK"));
+
+        main_command()
+            .arg(&input)
+            .arg("--exclude-self-links")
+            .arg("--verbose")
+            .assert()
+            .success()
+            .stdout(contains("3 Total"))
+            .stdout(contains("1 OK"))
+            .stdout(contains("2 Excluded"))
+            .stderr(contains("README.md#top | Excluded (self-link)\n"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_include_host() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        let mock_server = mock_server!(StatusCode::OK);
+        let included = mock_server.uri().replace("127.0.0.1", "localhost");
+        fs::write(
+            &input,
+            format!(
+                "{included}/a\n{}/b\nhttps://example.org/",
+                mock_server.uri()
+            ),
+        )?;
+
+        main_command()
+            .arg(&input)
+            .arg("--include-host")
+            .arg("localhost")
+            .arg("--no-progress")
+            .assert()
+            .success()
+            .stdout(contains("3 Total"))
+            .stdout(contains("1 OK"))
+            .stdout(contains("2 Excluded"));
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url.path(), "/a");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_shards() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("links.md");
+        let mock_server = mock_server!(StatusCode::OK);
+        fs::write(&input, format!("{}/", mock_server.uri()))?;
+
+        let run = || {
+            main_command()
+                .current_dir(dir.path())
+                .arg(&input)
+                .arg("--cache")
+                .arg("--cache-shards")
+                .arg("4")
+                .arg("--verbose")
+                .assert()
+                .success()
+        };
+        run();
+        assert!(!dir.path().join(LYCHEE_CACHE_FILE).exists());
+        assert_eq!(fs::read_dir(dir.path().join(".lycheecache.d"))?.count(), 4);
+
+        run().stderr(contains(format!(
+            "[200] {}/ | OK (cached)\n",
+            mock_server.uri()
+        )));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_skip_cache_unsupported() -> Result<()> {
         let base_path = fixtures_path().join("cache");
@@ -693,7 +1601,7 @@ mod cli {
         // Unconditionally remove cache file if it exists
         let _ = fs::remove_file(&cache_file);
 
-        let unsupported_url = "slack://user".to_string();
+        let unsupported_url = "irc://irc.libera.chat/lychee".to_string();
         let excluded_url = "https://example.com/";
 
         // run first without cache to generate the cache file
@@ -708,7 +1616,7 @@ mod cli {
             .arg("-")
             .assert()
             .stderr(contains(format!(
-                "[IGNORED] {unsupported_url} | Unsupported Error creating request client\n"
+                "[IGNORED] {unsupported_url} | Unsupported URI scheme `irc` is not supported\n"
             )))
             .stderr(contains(format!("[EXCLUDED] {excluded_url} | Excluded\n")));
 
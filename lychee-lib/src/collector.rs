@@ -1,20 +1,46 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
 use crate::{
-    extract::Extractor, helpers::request, types::uri::raw::RawUri, Base, Input, Request, Result,
+    extract::{self, Extractor},
+    helpers::{request, url},
+    types::uri::raw::RawUri,
+    Base, Client, ErrorKind, FileType, Input, InputContent, InputSource, Request, Result,
+    TrailingSlash,
 };
+use async_stream::stream;
 use futures::{
     stream::{self, Stream},
     StreamExt, TryStreamExt,
 };
+use log::debug;
 use par_stream::ParStreamExt;
+use reqwest::Url;
+use tokio::sync::mpsc;
+
+/// Maximum number of inputs which get read at the same time
+const MAX_CONCURRENT_INPUTS: usize = 16;
+
+/// Maximum number of input contents which were read, but not yet extracted
+const CONTENTS_BUFFER: usize = 32;
 
 /// Collector keeps the state of link collection
 /// It drives the link extraction from inputs
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone)]
 pub struct Collector {
     base: Option<Base>,
     skip_missing_inputs: bool,
     include_verbatim: bool,
     use_html5ever: bool,
+    strip_query_params: Vec<String>,
+    trailing_slash: Option<TrailingSlash>,
+    variables: Option<HashMap<String, String>>,
+    deny_empty_urls: bool,
+    include_anchors: bool,
+    crawl: Option<(usize, Arc<Client>)>,
 }
 
 impl Collector {
@@ -26,6 +52,12 @@ impl Collector {
             skip_missing_inputs: false,
             use_html5ever: false,
             include_verbatim: false,
+            strip_query_params: Vec::new(),
+            trailing_slash: None,
+            variables: None,
+            deny_empty_urls: false,
+            include_anchors: false,
+            crawl: None,
         }
     }
 
@@ -50,41 +82,369 @@ impl Collector {
         self
     }
 
+    /// Remove the given query parameters (e.g. tracking parameters like
+    /// `utm_*` or `fbclid`) from all links, so that otherwise equal links
+    /// only get checked once. A trailing `*` matches parameters by prefix.
+    #[must_use]
+    pub fn strip_query_params(mut self, params: Vec<String>) -> Self {
+        self.strip_query_params = params;
+        self
+    }
+
+    /// Unify website links which only differ by a trailing slash, so that
+    /// they only get checked once per input. `policy` decides which form of
+    /// a link is canonical; the links still get checked and reported as
+    /// they were written.
+    #[must_use]
+    pub const fn trailing_slash(mut self, policy: Option<TrailingSlash>) -> Self {
+        self.trailing_slash = policy;
+        self
+    }
+
+    /// Substitute `${NAME}` placeholders in links (e.g. `https://${HOST}/`)
+    /// with the values of `variables` before they get normalized. Links with
+    /// a placeholder of an unknown variable cause an
+    /// [`ErrorKind::UnresolvedVariable`], while the other links of the input
+    /// still get checked. Without variables (the default), links are left as
+    /// they are.
+    #[must_use]
+    pub fn variables(mut self, variables: HashMap<String, String>) -> Self {
+        self.variables = (!variables.is_empty()).then_some(variables);
+        self
+    }
+
+    /// Return an [`ErrorKind::EmptyUrl`] for each link with an empty target
+    /// (e.g. `href=""` or `href="#"`) instead of skipping them. The other
+    /// links still get collected.
+    #[must_use]
+    pub const fn deny_empty_urls(mut self, yes: bool) -> Self {
+        self.deny_empty_urls = yes;
+        self
+    }
+
+    /// Collect fragment-only links (e.g. `#top`) of local files and remote
+    /// pages as links into the document they were found in, instead of
+    /// skipping them or resolving them against the base URL. This is needed
+    /// to recognize them as self-links (see
+    /// [`ClientBuilder::exclude_self_links`](crate::ClientBuilder)).
+    #[must_use]
+    pub const fn include_anchors(mut self, yes: bool) -> Self {
+        self.include_anchors = yes;
+        self
+    }
+
+    /// Crawl websites given as inputs: follow links to HTML pages on the
+    /// same host up to `depth` links away from the input, and collect their
+    /// links as well. Every page gets visited only once. A depth of 0 (the
+    /// default) disables crawling.
+    ///
+    /// Pages get fetched with `client`, so that they are requested like the
+    /// checked links (e.g. with the same headers and host concurrency).
+    /// Pages excluded by the client are not crawled.
+    #[must_use]
+    pub fn crawl(mut self, depth: usize, client: Client) -> Self {
+        self.crawl = (depth > 0).then(|| (depth, Arc::new(client)));
+        self
+    }
+
     /// Fetch all unique links from inputs
     /// All relative URLs get prefixed with `base` (if given).
     /// (This can be a directory or a base URL)
     ///
     /// # Errors
     ///
-    /// Will return `Err` if links cannot be extracted from an input
+    /// The stream contains an `Err` for each input which can't be read and
+    /// for each link which can't be extracted, along with the links of the
+    /// other inputs
     pub async fn collect_links(self, inputs: Vec<Input>) -> impl Stream<Item = Result<Request>> {
-        let skip_missing_inputs = self.skip_missing_inputs;
-        let contents = stream::iter(inputs)
-            .par_then_unordered(None, move |input| async move {
-                input.get_contents(skip_missing_inputs).await
-            })
-            .flatten();
+        let contents = read_inputs(inputs, self.skip_missing_inputs);
 
-        let base = self.base;
+        match self.crawl.clone() {
+            Some((depth, client)) => self
+                .crawl_links(contents, depth, client)
+                .boxed()
+                .right_stream(),
+            None => self.extract_requests(contents).left_stream(),
+        }
+    }
+
+    /// Fetch all unique links from already loaded input contents,
+    /// e.g. from [`InputContent::from_diff`].
+    /// All relative URLs get prefixed with `base` (if given).
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if links cannot be extracted from an input
+    pub fn collect_links_from_contents(
+        self,
+        contents: Vec<InputContent>,
+    ) -> impl Stream<Item = Result<Request>> {
+        self.extract_requests(stream::iter(contents.into_iter().map(Ok)))
+    }
+
+    /// Extract requests from a stream of input contents
+    fn extract_requests<S>(self, contents: S) -> impl Stream<Item = Result<Request>>
+    where
+        S: Stream<Item = Result<InputContent>> + Send + 'static,
+    {
         contents
             .par_then_unordered(None, move |content| {
                 // send to parallel worker
-                let base = base.clone();
-                async move {
-                    let content = content?;
-                    let extractor = Extractor::new(self.use_html5ever, self.include_verbatim);
-                    let uris: Vec<RawUri> = extractor.extract(&content);
-                    let requests = request::create(uris, &content, &base)?;
-                    Result::Ok(stream::iter(requests.into_iter().map(Ok)))
-                }
+                let collector = self.clone();
+                async move { Result::Ok(stream::iter(collector.requests(&content?))) }
             })
             .try_flatten()
     }
+
+    /// Extract requests from input contents and from the pages they lead to
+    /// on the same host, up to the crawl depth
+    ///
+    /// Pages are fetched level by level. Pages which can't be fetched are
+    /// skipped; their links get reported by the link check itself.
+    fn crawl_links<S>(
+        self,
+        contents: S,
+        max_depth: usize,
+        client: Arc<Client>,
+    ) -> impl Stream<Item = Result<Request>>
+    where
+        S: Stream<Item = Result<InputContent>> + Send + 'static,
+    {
+        stream! {
+            let mut visited = HashSet::new();
+            // Pages to crawl at the next level
+            let mut pages = vec![];
+            for await content in contents {
+                let content = match content {
+                    Ok(content) => content,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+                for request in self.requests(&content) {
+                    if let Ok(request) = &request {
+                        if let Some(page) = crawl_target(&content.source, request, &client, &mut visited) {
+                            pages.push(page);
+                        }
+                    }
+                    yield request;
+                }
+            }
+
+            for depth in 1..=max_depth {
+                let fetcher = client.clone();
+                let contents: Vec<_> = stream::iter(pages)
+                    .par_then_unordered(None, move |page| {
+                        let client = fetcher.clone();
+                        async move { client.page_contents(&page).await }
+                    })
+                    .collect()
+                    .await;
+                pages = vec![];
+                for content in contents.into_iter().flatten() {
+                    for request in self.requests(&content) {
+                        if let Ok(request) = &request {
+                            if depth < max_depth {
+                                if let Some(page) = crawl_target(&content.source, request, &client, &mut visited) {
+                                    pages.push(page);
+                                }
+                            }
+                        }
+                        yield request;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Extract the requests for all links of an input
+    ///
+    /// Invalid links are returned as errors along with the valid ones, see
+    /// [`ErrorKind::input_source`].
+    fn requests(&self, content: &InputContent) -> Vec<Result<Request>> {
+        let is_feed = match content.file_type {
+            FileType::Feed => true,
+            FileType::Xml => extract::is_feed(&content.content),
+            _ => false,
+        };
+        if is_feed {
+            if let Err(reason) = extract::validate_feed(&content.content) {
+                return vec![Err(ErrorKind::InvalidFeed(content.source.clone(), reason))];
+            }
+        }
+        let extractor = Extractor::new(self.use_html5ever, self.include_verbatim);
+        let (empty, uris): (Vec<RawUri>, Vec<RawUri>) = extractor
+            .extract(content)
+            .into_iter()
+            .partition(RawUri::is_empty);
+        let mut results = vec![];
+        if self.deny_empty_urls {
+            results.extend(
+                empty
+                    .iter()
+                    .map(|_| Err(ErrorKind::EmptyUrl(content.source.clone()))),
+            );
+        }
+        let uris = match &self.variables {
+            Some(variables) => {
+                let (uris, unresolved) = substitute_variables(uris, variables, &content.source);
+                results.extend(unresolved.into_iter().map(Err));
+                uris
+            }
+            None => uris,
+        };
+        let mut requests = match request::create(uris, content, &self.base, self.include_anchors) {
+            Ok(requests) => requests,
+            Err(e) => return vec![Err(e)],
+        };
+        if !self.strip_query_params.is_empty() {
+            requests = request::unique(requests.into_iter().map(|mut request| {
+                url::strip_query_params(&mut request.uri.url, &self.strip_query_params);
+                request
+            }));
+        }
+        if let Some(policy) = self.trailing_slash {
+            requests = unify_trailing_slashes(requests, policy);
+        }
+        results.extend(requests.into_iter().map(Ok));
+        results
+    }
+}
+
+/// Keep only one of the requests of an input whose links only differ by a
+/// trailing slash, see [`Collector::trailing_slash`]
+///
+/// The canonical form of a link is only used to tell the variants apart:
+/// the links are checked and reported as they were written. If one of the
+/// variants already is in canonical form, that one is kept.
+fn unify_trailing_slashes(requests: Vec<Request>, policy: TrailingSlash) -> Vec<Request> {
+    let mut unified: Vec<Request> = vec![];
+    // Position of the request kept for each canonical form
+    let mut canonical: HashMap<Url, usize> = HashMap::new();
+    for request in requests {
+        let mut key = request.uri.url.clone();
+        policy.apply(&mut key);
+        match canonical.get(&key) {
+            Some(&i) if unified[i].uri.url == key || request.uri.url != key => {
+                debug!(
+                    "Skipping {}, which only differs from {} by a trailing slash",
+                    request.uri, unified[i].uri
+                );
+            }
+            Some(&i) => {
+                debug!(
+                    "Skipping {}, which is not in canonical form",
+                    unified[i].uri
+                );
+                unified[i] = request;
+            }
+            None => {
+                canonical.insert(key, unified.len());
+                unified.push(request);
+            }
+        }
+    }
+    unified
+}
+
+/// Substitute the `${NAME}` placeholders in the links of an input, see
+/// [`Collector::variables`]
+///
+/// Returns the substituted links, and an error for each link with a
+/// placeholder of an unknown variable.
+fn substitute_variables(
+    uris: Vec<RawUri>,
+    variables: &HashMap<String, String>,
+    source: &InputSource,
+) -> (Vec<RawUri>, Vec<ErrorKind>) {
+    let mut substituted = vec![];
+    let mut unresolved = vec![];
+    for mut uri in uris {
+        match url::substitute_variables(&uri.text, variables) {
+            Ok(text) => {
+                uri.text = text.into_owned();
+                substituted.push(uri);
+            }
+            Err(name) => unresolved.push(ErrorKind::UnresolvedVariable(
+                name,
+                uri.text.clone(),
+                source.clone(),
+            )),
+        }
+    }
+    (substituted, unresolved)
+}
+
+/// Read the contents of the inputs concurrently on a separate task
+///
+/// The contents get sent through a bounded channel, so that reading goes on
+/// while links are extracted and checked, without loading all inputs into
+/// memory at once. An input which can't be read is returned as an error
+/// along with the contents of the other inputs.
+fn read_inputs(inputs: Vec<Input>, skip_missing: bool) -> impl Stream<Item = Result<InputContent>> {
+    let (send, mut recv) = mpsc::channel(CONTENTS_BUFFER);
+    let reader = tokio::spawn(stream::iter(inputs).for_each_concurrent(
+        MAX_CONCURRENT_INPUTS,
+        move |input| {
+            let send = send.clone();
+            async move {
+                let contents = input.get_contents(skip_missing).await;
+                futures::pin_mut!(contents);
+                while let Some(content) = contents.next().await {
+                    // Stop reading once the contents are no longer needed
+                    if send.send(content).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        },
+    ));
+    stream! {
+        while let Some(content) = recv.recv().await {
+            yield content;
+        }
+        if let Err(e) = reader.await {
+            yield Err(e.into());
+        }
+    }
+}
+
+/// Get the page to crawl for a link found on a remote input, if the link
+/// points to an HTML page on the same host which wasn't visited yet and isn't
+/// excluded by `client`
+fn crawl_target(
+    source: &InputSource,
+    request: &Request,
+    client: &Client,
+    visited: &mut HashSet<Url>,
+) -> Option<Url> {
+    let InputSource::RemoteUrl(source) = source else {
+        return None;
+    };
+    let mut page = request.uri.url.clone();
+    page.set_fragment(None);
+    if !matches!(page.scheme(), "http" | "https")
+        || (page.host_str(), page.port()) != (source.host_str(), source.port())
+        || Input::url_file_type(&page) != FileType::Html
+        || client.is_excluded(&request.uri)
+    {
+        return None;
+    }
+    // The input itself counts as visited
+    visited.insert(source.as_ref().clone());
+    visited.insert(page.clone()).then_some(page)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashSet, convert::TryFrom, fs::File, io::Write};
+    use std::{
+        collections::HashSet,
+        convert::TryFrom,
+        fs::File,
+        io::Write,
+        path::{Path, PathBuf},
+    };
 
     use http::StatusCode;
     use reqwest::Url;
@@ -94,7 +454,7 @@ mod tests {
         mock_server,
         test_utils::{load_fixture, mail, website},
         types::{FileType, Input, InputSource},
-        Result, Uri,
+        ClientBuilder, Result, Uri,
     };
 
     // Helper function to run the collector on the given inputs
@@ -125,7 +485,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_url_without_extension_is_html() -> Result<()> {
-        let input = Input::new("https://example.com/", None, true, None)?;
+        let mock_server = mock_server!(
+            StatusCode::OK,
+            set_body_string(r#"<a href="https://example.com">Link</a>"#)
+        );
+        let url = format!("{}/docs/page", mock_server.uri());
+        let input = Input::new(&url, None, true, None)?;
         let contents: Vec<_> = input.get_contents(true).await.collect::<Vec<_>>().await;
 
         assert_eq!(contents.len(), 1);
@@ -368,4 +733,568 @@ mod tests {
 
         assert_eq!(links, expected_links);
     }
+
+    #[tokio::test]
+    async fn test_collect_links_from_diff() {
+        let diff = "\
+--- a/README.md
++++ b/README.md
+@@ -1,2 +1,2 @@
+ Unchanged https://unchanged.com
+-Removed https://removed.com
++Added https://added.com
+";
+        let contents = InputContent::from_diff(diff);
+        let requests: Vec<Request> = Collector::new(None)
+            .collect_links_from_contents(contents)
+            .map(|r| r.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].uri, website("https://added.com"));
+        assert_eq!(requests[0].source, InputSource::FsPath("README.md".into()));
+    }
+
+    #[tokio::test]
+    async fn test_strip_query_params() {
+        let input = Input {
+            source: InputSource::String(
+                "https://example.com/?utm_source=a https://example.com/?utm_source=b".to_string(),
+            ),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        let links: HashSet<Uri> = Collector::new(None)
+            .strip_query_params(vec!["utm_source".to_string()])
+            .collect_links(vec![input])
+            .await
+            .map(|r| r.unwrap().uri)
+            .collect()
+            .await;
+
+        assert_eq!(links, HashSet::from([website("https://example.com/")]));
+    }
+
+    #[tokio::test]
+    async fn test_trailing_slash() {
+        let input = || Input {
+            source: InputSource::String(
+                "https://example.com/page https://example.com/page/ https://example.com/a.html \
+                https://example.com/docs/ https://example.com/about"
+                    .to_string(),
+            ),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        let collect = |policy| async move {
+            Collector::new(None)
+                .trailing_slash(policy)
+                .collect_links(vec![input()])
+                .await
+                .map(|r| r.unwrap().uri)
+                .collect::<HashSet<Uri>>()
+                .await
+        };
+
+        assert_eq!(
+            collect(Some(TrailingSlash::Add)).await,
+            HashSet::from([
+                website("https://example.com/page/"),
+                website("https://example.com/a.html"),
+                website("https://example.com/docs/"),
+                website("https://example.com/about")
+            ])
+        );
+        assert_eq!(
+            collect(Some(TrailingSlash::Remove)).await,
+            HashSet::from([
+                website("https://example.com/page"),
+                website("https://example.com/a.html"),
+                website("https://example.com/docs/"),
+                website("https://example.com/about")
+            ])
+        );
+        assert_eq!(collect(None).await.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_variables() {
+        let input = || Input {
+            source: InputSource::String(
+                r#"<a href="https://${HOST}/docs">docs</a><a href="https://example.org">b</a>"#
+                    .to_string(),
+            ),
+            file_type_hint: Some(FileType::Html),
+            excluded_paths: None,
+        };
+        let collect = |variables: &[(&str, &str)]| {
+            let variables = variables
+                .iter()
+                .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+                .collect();
+            async move {
+                Collector::new(None)
+                    .variables(variables)
+                    .collect_links(vec![input()])
+                    .await
+                    .collect::<Vec<Result<Request>>>()
+                    .await
+            }
+        };
+
+        let links: HashSet<Uri> = collect(&[("HOST", "example.com")])
+            .await
+            .into_iter()
+            .map(|r| r.unwrap().uri)
+            .collect();
+        assert_eq!(
+            links,
+            HashSet::from([
+                website("https://example.com/docs"),
+                website("https://example.org")
+            ])
+        );
+
+        // Only the link with the unknown variable fails
+        let responses = collect(&[("PORT", "8080")]).await;
+        assert_eq!(responses.len(), 2);
+        assert!(responses.iter().any(|r| matches!(
+            r,
+            Err(ErrorKind::UnresolvedVariable(name, link, InputSource::String(_)))
+                if name == "HOST" && link == "https://${HOST}/docs"
+        )));
+        assert!(responses
+            .iter()
+            .any(|r| matches!(r, Ok(request) if request.uri == website("https://example.org"))));
+    }
+
+    #[tokio::test]
+    async fn test_empty_urls() {
+        let contents = [
+            r#"<a href="">a</a>"#,
+            r##"<a href="#">b</a>"##,
+            r#"<a href="   ">c</a>"#,
+        ];
+        for content in contents {
+            let html = format!(r#"{content}<a href="https://example.com">d</a>"#);
+            let input = || Input {
+                source: InputSource::String(html.clone()),
+                file_type_hint: Some(FileType::Html),
+                excluded_paths: None,
+            };
+
+            // Skipped by default
+            let links = collect(vec![input()], None).await;
+            assert_eq!(links, HashSet::from([website("https://example.com")]));
+
+            // Reported along with the other links
+            let responses: Vec<Result<Request>> = Collector::new(None)
+                .deny_empty_urls(true)
+                .collect_links(vec![input()])
+                .await
+                .collect()
+                .await;
+            assert_eq!(responses.len(), 2);
+            assert!(responses
+                .iter()
+                .any(|r| matches!(r, Err(ErrorKind::EmptyUrl(InputSource::String(_))))));
+            assert!(responses.iter().any(
+                |r| matches!(r, Ok(request) if request.uri == website("https://example.com"))
+            ));
+        }
+
+        // One error per empty link
+        let input = Input {
+            source: InputSource::String(r##"<a href="">a</a><a href="#">b</a>"##.to_string()),
+            file_type_hint: Some(FileType::Html),
+            excluded_paths: None,
+        };
+        let responses: Vec<Result<Request>> = Collector::new(None)
+            .deny_empty_urls(true)
+            .collect_links(vec![input])
+            .await
+            .collect()
+            .await;
+        assert_eq!(responses.len(), 2);
+        assert!(responses
+            .iter()
+            .all(|r| matches!(r, Err(ErrorKind::EmptyUrl(InputSource::String(_))))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_feed() {
+        let input = |content: &str| Input {
+            source: InputSource::String(content.to_string()),
+            file_type_hint: Some(FileType::Feed),
+            excluded_paths: None,
+        };
+
+        let links = collect(
+            vec![input("<rss><link>https://example.com</link></rss>")],
+            None,
+        )
+        .await;
+        assert_eq!(links, HashSet::from([website("https://example.com")]));
+
+        let responses: Vec<Result<Request>> = Collector::new(None)
+            .collect_links(vec![input("<rss><link>https://example.com</rss>")])
+            .await
+            .collect()
+            .await;
+        assert!(matches!(
+            responses.as_slice(),
+            [Err(ErrorKind::InvalidFeed(InputSource::String(_), reason))]
+                if reason == "expected `</link>`, found `</rss>` on line 1"
+        ));
+
+        // Feeds in XML files are validated, too, without affecting other inputs
+        let responses: Vec<Result<Request>> = Collector::new(None)
+            .collect_links(vec![
+                Input {
+                    file_type_hint: Some(FileType::Xml),
+                    ..input("<feed><link href=\"https://example.com\"></feed>")
+                },
+                Input {
+                    file_type_hint: Some(FileType::Xml),
+                    ..input("<doc><link href=\"https://example.org\"></doc>")
+                },
+            ])
+            .await
+            .collect()
+            .await;
+        assert_eq!(responses.len(), 2);
+        assert!(responses.iter().any(|r| matches!(
+            r,
+            Err(ErrorKind::InvalidFeed(InputSource::String(_), reason))
+                if reason == "expected `</link>`, found `</feed>` on line 1"
+        )));
+        assert!(responses
+            .iter()
+            .any(|r| matches!(r, Ok(request) if request.uri == website("https://example.org"))));
+    }
+
+    #[tokio::test]
+    async fn test_include_anchors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("index.html");
+        std::fs::write(&file_path, r##"<a href="#top">Top</a>"##).unwrap();
+        let input = || Input {
+            source: InputSource::FsPath(file_path.clone()),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+
+        // Skipped by default
+        assert!(collect(vec![input()], None).await.is_empty());
+
+        let links: HashSet<Uri> = Collector::new(None)
+            .include_anchors(true)
+            .collect_links(vec![input()])
+            .await
+            .map(|r| r.unwrap().uri)
+            .collect()
+            .await;
+        let mut expected = Url::from_file_path(&file_path).unwrap();
+        expected.set_fragment(Some("top"));
+        assert_eq!(links, HashSet::from([Uri { url: expected }]));
+    }
+
+    #[tokio::test]
+    async fn test_gitignore() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("vendor/lib")).unwrap();
+        std::fs::write(root.join(".gitignore"), "vendor/\n").unwrap();
+        std::fs::write(root.join("README.md"), "https://included.com").unwrap();
+        std::fs::write(root.join("vendor/lib/README.md"), "https://vendored.com").unwrap();
+
+        let expected_links = HashSet::from_iter([website("https://included.com")]);
+
+        let input = Input {
+            source: InputSource::FsPath(root.to_path_buf()),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        assert_eq!(collect(vec![input], None).await, expected_links);
+
+        let input = Input {
+            source: InputSource::FsGlob {
+                pattern: root.join("**/*.md").to_str().unwrap().to_owned(),
+                ignore_case: false,
+            },
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        assert_eq!(collect(vec![input], None).await, expected_links);
+
+        // An ignored directory can still be checked explicitly
+        let input = Input {
+            source: InputSource::FsPath(root.join("vendor")),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        assert_eq!(
+            collect(vec![input], None).await,
+            HashSet::from_iter([website("https://vendored.com")])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_excluded_path_glob() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("README.md"), "https://included.com").unwrap();
+        std::fs::write(root.join("CHANGELOG.md"), "https://changelog.com").unwrap();
+        std::fs::write(root.join("docs/CHANGELOG.md"), "https://changelog.com/docs").unwrap();
+
+        let excluded_paths = Some(vec![PathBuf::from("**/CHANGELOG.md")]);
+        let expected_links = HashSet::from_iter([website("https://included.com")]);
+
+        let input = Input {
+            source: InputSource::FsPath(root.to_path_buf()),
+            file_type_hint: None,
+            excluded_paths: excluded_paths.clone(),
+        };
+        assert_eq!(collect(vec![input], None).await, expected_links);
+
+        let input = Input {
+            source: InputSource::FsGlob {
+                pattern: root.join("**/*.md").to_str().unwrap().to_owned(),
+                ignore_case: false,
+            },
+            file_type_hint: None,
+            excluded_paths,
+        };
+        assert_eq!(collect(vec![input], None).await, expected_links);
+    }
+
+    #[tokio::test]
+    async fn test_file_type_hint_overrides_extension() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("links.txt");
+        std::fs::write(&file, r#"<a href="docs/page.html">Docs</a>"#).unwrap();
+        let base = Some(Base::try_from("https://example.com/").unwrap());
+
+        // Relative links are not detected in plaintext
+        let input = Input {
+            source: InputSource::FsPath(file.clone()),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        assert!(collect(vec![input], base.clone()).await.is_empty());
+
+        let input = Input {
+            source: InputSource::FsPath(file),
+            file_type_hint: Some(FileType::Html),
+            excluded_paths: None,
+        };
+        assert_eq!(
+            collect(vec![input], base).await,
+            HashSet::from_iter([website("https://example.com/docs/page.html")])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collect_links_from_sitemap() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("TEST_SITEMAP.xml");
+        let input = Input {
+            source: InputSource::FsPath(path),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        let links = collect(vec![input], None).await;
+
+        // The sitemap namespace is not a link to check
+        let expected_links = HashSet::from_iter([
+            website("https://example.com/"),
+            website("https://example.com/blog?page=1&sort=date"),
+        ]);
+        assert_eq!(links, expected_links);
+    }
+
+    #[tokio::test]
+    async fn test_collect_links_from_sitemap_index() {
+        let sitemap = mock_server!(
+            StatusCode::OK,
+            set_body_string(load_fixture("TEST_SITEMAP.xml"))
+        );
+        let index = format!(
+            r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>{}/sitemap.xml</loc></sitemap>
+</sitemapindex>"#,
+            sitemap.uri()
+        );
+        let mock_server = mock_server!(StatusCode::OK, set_body_string(index));
+
+        let input = Input {
+            source: InputSource::RemoteUrl(Box::new(
+                Url::parse(&format!("{}/sitemap_index.xml", mock_server.uri())).unwrap(),
+            )),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        let links = collect(vec![input], None).await;
+
+        let expected_links = HashSet::from_iter([
+            website("https://example.com/"),
+            website("https://example.com/blog?page=1&sort=date"),
+        ]);
+        assert_eq!(links, expected_links);
+    }
+
+    #[tokio::test]
+    async fn test_crawl() {
+        use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+        // A chain of pages: / -> /a -> /b -> /c -> /d
+        let mock_server = MockServer::start().await;
+        for (page, next, expected_visits) in [
+            ("/", "/a", 1),
+            ("/a", "/b", 1),
+            ("/b", "/c", 1),
+            ("/c", "/d", 0),
+            ("/d", "/", 0),
+        ] {
+            let body = format!(
+                r#"<a href="{next}">Next</a>
+                <a href="/#top">Home</a>
+                <a href="https://example.com/">External</a>"#
+            );
+            Mock::given(path(page))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .expect(expected_visits)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let input = Input {
+            source: InputSource::RemoteUrl(Box::new(Url::parse(&mock_server.uri()).unwrap())),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        let links: HashSet<Uri> = Collector::new(None)
+            .crawl(2, ClientBuilder::default().client().unwrap())
+            .collect_links(vec![input])
+            .await
+            .map(|r| r.unwrap().uri)
+            .collect()
+            .await;
+
+        let page = |path: &str| website(&format!("{}{path}", mock_server.uri()));
+        let expected_links = HashSet::from_iter([
+            page("/a"),
+            page("/b"),
+            page("/c"),
+            page("/#top"),
+            website("https://example.com/"),
+        ]);
+        assert_eq!(links, expected_links);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_with_client() {
+        use http::{header::HeaderMap, HeaderValue};
+        use regex::RegexSet;
+        use wiremock::{
+            matchers::{header, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let mock_server = MockServer::start().await;
+        Mock::given(path("/"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"<a href="/docs">Docs</a><a href="/private">Private</a>"#),
+            )
+            .mount(&mock_server)
+            .await;
+        // Pages get requested with the headers of the client
+        Mock::given(path("/docs"))
+            .and(header("x-token", "secret"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"<a href="/docs/intro">Intro</a>"#),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        // Excluded pages don't get crawled
+        Mock::given(path("/private"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"<a href="/secret">Secret</a>"#),
+            )
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-token", HeaderValue::from_static("secret"));
+        let client = ClientBuilder::builder()
+            .custom_headers(headers)
+            .excludes(RegexSet::new(["/private"]).unwrap())
+            .build()
+            .client()
+            .unwrap();
+        let input = Input {
+            source: InputSource::RemoteUrl(Box::new(Url::parse(&mock_server.uri()).unwrap())),
+            file_type_hint: None,
+            excluded_paths: None,
+        };
+        let links: HashSet<Uri> = Collector::new(None)
+            .crawl(1, client)
+            .collect_links(vec![input])
+            .await
+            .map(|r| r.unwrap().uri)
+            .collect()
+            .await;
+
+        let page = |path: &str| website(&format!("{}{path}", mock_server.uri()));
+        let expected_links =
+            HashSet::from_iter([page("/docs"), page("/private"), page("/docs/intro")]);
+        assert_eq!(links, expected_links);
+    }
+
+    #[tokio::test]
+    async fn test_read_inputs_concurrently() {
+        let delay = std::time::Duration::from_secs(2);
+        let slow_server = mock_server!(
+            StatusCode::OK,
+            set_delay(delay),
+            set_body_string("https://example.com/slow")
+        );
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        // The slow input comes first, so that it would block all others if
+        // inputs got read one after the other
+        let mut inputs = vec![Input {
+            source: InputSource::RemoteUrl(Box::new(Url::parse(&slow_server.uri()).unwrap())),
+            file_type_hint: None,
+            excluded_paths: None,
+        }];
+        for i in 0..100 {
+            let path = temp_dir.path().join(format!("{i}.txt"));
+            writeln!(File::create(&path).unwrap(), "https://example.com/{i}").unwrap();
+            inputs.push(Input {
+                source: InputSource::FsPath(path),
+                file_type_hint: None,
+                excluded_paths: None,
+            });
+        }
+
+        let start = tokio::time::Instant::now();
+        let mut requests = Box::pin(Collector::new(None).collect_links(inputs).await);
+        let first = requests.next().await.unwrap().unwrap();
+        assert!(start.elapsed() < delay);
+        assert_ne!(first.uri, website("https://example.com/slow"));
+
+        // All files get read while waiting for the slow input
+        let rest: Vec<_> = requests.map(|r| r.unwrap().uri).collect().await;
+        assert_eq!(rest.len(), 100);
+        assert_eq!(rest.last(), Some(&website("https://example.com/slow")));
+    }
 }
@@ -0,0 +1,190 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use super::plaintext::extract_plaintext;
+use crate::types::uri::raw::RawUri;
+
+/// Inline and block macros with a target, followed by their (possibly
+/// multiline) attribute list in square brackets, e.g. `link:url[text]`.
+/// Also matches URLs with a link text (`https://example.com[text]`) and
+/// cross references in the shorthand form (`<<id,text>>`).
+static MACRO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)
+        \b(?P<name>link|image|xref|mailto):{1,2}(?P<target>\+\+[^\n]*?\+\+|[^\s\[]+)\[
+        | \b(?P<url>(?:https?|ftp|irc)://[^\s\[]+)\[
+        | <<(?P<ref>[^,>\n]+)(?:,[^>\n]*)?>>
+        ",
+    )
+    .expect("valid macro pattern")
+});
+
+/// Extract unparsed URL strings from an [AsciiDoc](https://asciidoc.org)
+/// document.
+///
+/// Links get extracted from the `link:`, `mailto:`, `image:` and `xref:`
+/// macros, from `<<id>>` cross references and from autolinks (bare URLs and
+/// email addresses). Cross references to IDs in the same document become
+/// anchors, which are not checked. Comments are skipped, and so are listing
+/// and literal blocks, unless `include_verbatim` is set.
+pub(crate) fn extract_asciidoc(input: &str, include_verbatim: bool) -> Vec<RawUri> {
+    let text = strip_blocks(input, include_verbatim);
+
+    let mut uris = vec![];
+    for captures in MACRO.captures_iter(&text) {
+        uris.push(macro_uri(&captures));
+    }
+    // Autolinks outside of macros
+    uris.extend(extract_plaintext(&MACRO.replace_all(&text, " ")));
+    uris
+}
+
+fn macro_uri(captures: &Captures) -> RawUri {
+    let (element, attribute) = match captures.name("name").map(|name| name.as_str()) {
+        // Emulate HTML tags, like the Markdown extractor does
+        Some("image") => ("img", "src"),
+        _ => ("a", "href"),
+    };
+    let text = if let Some(target) = captures.name("target") {
+        let target = target.as_str();
+        match captures.name("name").map(|name| name.as_str()) {
+            Some("xref") => xref_target(target),
+            Some("mailto") => format!("mailto:{target}"),
+            // Passthrough for targets with special characters (`link:++url++`)
+            _ => target
+                .strip_prefix("++")
+                .and_then(|target| target.strip_suffix("++"))
+                .unwrap_or(target)
+                .to_string(),
+        }
+    } else if let Some(url) = captures.name("url") {
+        url.as_str().to_string()
+    } else {
+        xref_target(captures.name("ref").map_or("", |r| r.as_str()).trim())
+    };
+    RawUri {
+        text,
+        element: Some(element.to_string()),
+        attribute: Some(attribute.to_string()),
+    }
+}
+
+/// A cross reference targets either another document (`other.adoc#id`) or
+/// an ID in the same document, which is turned into an anchor
+fn xref_target(target: &str) -> String {
+    if target.contains(['#', '.', '/']) {
+        target.to_string()
+    } else {
+        format!("#{target}")
+    }
+}
+
+/// Remove comments and (unless `include_verbatim` is set) the content of
+/// listing and literal blocks. Line breaks are kept, so that macros can
+/// still span multiple lines.
+fn strip_blocks(input: &str, include_verbatim: bool) -> String {
+    let mut out = String::with_capacity(input.len());
+    // Delimiter of the currently open block to skip
+    let mut skipped_block: Option<&str> = None;
+    for line in input.lines() {
+        let trimmed = line.trim_end();
+        if let Some(delimiter) = skipped_block {
+            if trimmed == delimiter {
+                skipped_block = None;
+            }
+        } else if is_delimiter(trimmed, '/')
+            || (!include_verbatim && (is_delimiter(trimmed, '-') || is_delimiter(trimmed, '.')))
+        {
+            skipped_block = Some(trimmed);
+        } else if !trimmed.starts_with("//") || trimmed.starts_with("///") {
+            // Not a single-line comment
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Check if `line` is a block delimiter of at least four `c` characters
+fn is_delimiter(line: &str, c: char) -> bool {
+    line.len() >= 4 && line.chars().all(|x| x == c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::load_fixture;
+
+    fn texts(uris: Vec<RawUri>) -> Vec<String> {
+        uris.into_iter().map(|uri| uri.text).collect()
+    }
+
+    #[test]
+    fn test_link_macros() {
+        let input = load_fixture("TEST_ASCIIDOC.adoc");
+        let uris = extract_asciidoc(&input, false);
+        let links: Vec<_> = uris
+            .iter()
+            .filter(|uri| uri.element.as_deref() == Some("a"))
+            .map(|uri| uri.text.as_str())
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://example.com/link",
+                "https://example.com/continued",
+                "https://example.com/with space",
+                "docs/guide.html",
+                "mailto:team@example.com",
+                "other.adoc#install",
+                "#local-section",
+                "reference.adoc#api",
+                "https://example.com/autolink-text",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_image_macros() {
+        let input = load_fixture("TEST_ASCIIDOC.adoc");
+        let images: Vec<_> = extract_asciidoc(&input, false)
+            .into_iter()
+            .filter(|uri| uri.element.as_deref() == Some("img"))
+            .map(|uri| uri.text)
+            .collect();
+        assert_eq!(
+            images,
+            vec!["images/inline.png", "https://example.com/block.png"]
+        );
+    }
+
+    #[test]
+    fn test_autolinks() {
+        let input = load_fixture("TEST_ASCIIDOC.adoc");
+        let autolinks: Vec<_> = extract_asciidoc(&input, false)
+            .into_iter()
+            .filter(|uri| uri.element.is_none())
+            .map(|uri| uri.text)
+            .collect();
+        assert_eq!(
+            autolinks,
+            vec!["https://example.com/bare", "https://example.com/angle"]
+        );
+    }
+
+    #[test]
+    fn test_verbatim_and_comments() {
+        let input = load_fixture("TEST_ASCIIDOC.adoc");
+        let uris = texts(extract_asciidoc(&input, false));
+        assert!(!uris.contains(&"https://example.com/listing".to_string()));
+        assert!(!uris.contains(&"https://example.com/literal".to_string()));
+        assert!(!uris.contains(&"https://example.com/comment".to_string()));
+        assert!(!uris.contains(&"https://example.com/block-comment".to_string()));
+
+        let uris = texts(extract_asciidoc(&input, true));
+        assert!(uris.contains(&"https://example.com/listing".to_string()));
+        assert!(uris.contains(&"https://example.com/literal".to_string()));
+        assert!(!uris.contains(&"https://example.com/comment".to_string()));
+        assert!(!uris.contains(&"https://example.com/block-comment".to_string()));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::types::FileType;
+
+/// A decoded text part of a MIME message
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct TextPart {
+    /// `Html` for `text/html` parts and `Plaintext` for all other text parts
+    pub(crate) file_type: FileType,
+    pub(crate) text: String,
+}
+
+/// Maximum nesting of multipart bodies and attached messages
+const MAX_DEPTH: usize = 8;
+
+/// Get the text parts of an email message (`.eml`) in the
+/// [MIME format](https://datatracker.ietf.org/doc/html/rfc2045).
+///
+/// Multipart bodies are searched recursively, and the `base64` and
+/// `quoted-printable` transfer encodings are decoded. Attachments and
+/// non-text parts (like images) are skipped.
+pub(crate) fn text_parts(message: &str) -> Vec<TextPart> {
+    let mut parts = vec![];
+    collect_parts(message, 0, &mut parts);
+    parts
+}
+
+fn collect_parts(entity: &str, depth: usize, parts: &mut Vec<TextPart>) {
+    let (headers, body) = split_entity(entity);
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    };
+
+    if matches!(header("Content-Disposition"), Some(disposition) if media_type(disposition) == "attachment")
+    {
+        return;
+    }
+    let content_type = header("Content-Type").unwrap_or("text/plain");
+    let media_type = media_type(content_type);
+
+    if media_type.starts_with("multipart/") {
+        if depth >= MAX_DEPTH {
+            return;
+        }
+        if let Some(boundary) = parameter(content_type, "boundary") {
+            for part in split_multipart(body, &boundary) {
+                collect_parts(part, depth + 1, parts);
+            }
+        }
+    } else if media_type == "message/rfc822" {
+        if depth < MAX_DEPTH {
+            collect_parts(body, depth + 1, parts);
+        }
+    } else if media_type.starts_with("text/") {
+        let bytes = match header("Content-Transfer-Encoding").map(str::to_lowercase) {
+            Some(encoding) if encoding == "base64" => decode_base64(body),
+            Some(encoding) if encoding == "quoted-printable" => decode_quoted_printable(body),
+            _ => body.as_bytes().to_vec(),
+        };
+        let file_type = if media_type == "text/html" {
+            FileType::Html
+        } else {
+            FileType::Plaintext
+        };
+        parts.push(TextPart {
+            file_type,
+            text: decode_charset(&bytes, parameter(content_type, "charset").as_deref()),
+        });
+    }
+}
+
+/// Split a MIME entity into its (unfolded) header fields and its body
+fn split_entity(entity: &str) -> (Vec<(String, String)>, &str) {
+    let mut headers: Vec<(String, String)> = vec![];
+    let mut rest = entity;
+    while let Some((line, remainder)) = rest.split_once('\n') {
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            return (headers, remainder);
+        }
+        rest = remainder;
+        if line.starts_with([' ', '\t']) {
+            // Continuation of a folded header field
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, "")
+}
+
+/// Get the lowercase media type of a header field value, e.g. `text/html`
+/// for `text/html; charset=utf-8`
+fn media_type(value: &str) -> String {
+    value
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+/// Get a parameter of a header field value, e.g. the `boundary` of
+/// `multipart/mixed; boundary="abc"`
+fn parameter(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Split the body of a multipart entity into its parts
+fn split_multipart<'a>(body: &'a str, boundary: &str) -> Vec<&'a str> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = vec![];
+    let mut start = None;
+    let mut offset = 0;
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed.starts_with(&delimiter) {
+            if let Some(start) = start {
+                parts.push(&body[start..offset]);
+            }
+            if trimmed[delimiter.len()..].starts_with("--") {
+                return parts;
+            }
+            start = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    // Be lenient about a missing closing delimiter
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+fn decode_base64(body: &str) -> Vec<u8> {
+    let encoded: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+    base64::decode(encoded).unwrap_or_default()
+}
+
+fn decode_quoted_printable(body: &str) -> Vec<u8> {
+    let bytes = body.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'=' {
+            let rest = &bytes[i + 1..];
+            // Soft line break
+            if rest.starts_with(b"\r\n") {
+                i += 3;
+                continue;
+            }
+            if rest.starts_with(b"\n") {
+                i += 2;
+                continue;
+            }
+            let hex = rest
+                .get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = hex {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    decoded
+}
+
+/// Decode text in the given charset. Only UTF-8 (and thereby ASCII) and
+/// Latin-1 are supported; invalid characters get replaced.
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    match charset.map(str::to_lowercase).as_deref() {
+        Some("iso-8859-1" | "latin1") => bytes.iter().map(|&b| char::from(b)).collect(),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_part() {
+        let message = "From: a@example.com\r\nSubject: Hi\r\n\r\nSee https://example.com\r\n";
+        assert_eq!(
+            text_parts(message),
+            vec![TextPart {
+                file_type: FileType::Plaintext,
+                text: "See https://example.com\r\n".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_skip_attachments_and_binary_parts() {
+        let message = "Content-Type: multipart/mixed; boundary=XYZ
+
+--XYZ
+Content-Type: text/plain
+
+Body https://example.com/body
+--XYZ
+Content-Type: text/plain
+Content-Disposition: attachment; filename=\"notes.txt\"
+
+Attached https://example.com/attachment
+--XYZ
+Content-Type: image/png
+Content-Transfer-Encoding: base64
+
+iVBORw0KGgo=
+--XYZ--
+";
+        let parts = text_parts(message);
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].text, "Body https://example.com/body\n");
+    }
+
+    #[test]
+    fn test_header_parameters() {
+        let value = "multipart/alternative;\n boundary=\"b1\"; charset=UTF-8";
+        assert_eq!(media_type(value), "multipart/alternative");
+        assert_eq!(parameter(value, "boundary"), Some("b1".to_string()));
+        assert_eq!(parameter(value, "CHARSET"), Some("UTF-8".to_string()));
+        assert_eq!(parameter(value, "name"), None);
+    }
+
+    #[test]
+    fn test_decode_quoted_printable() {
+        assert_eq!(
+            decode_quoted_printable("https://example.com/a=3Db&c=3D=\r\nd caf=C3=A9"),
+            "https://example.com/a=b&c=d café".as_bytes()
+        );
+        // Invalid escapes are kept as-is
+        assert_eq!(decode_quoted_printable("=ZZ="), b"=ZZ=");
+    }
+
+    #[test]
+    fn test_decode_charset() {
+        assert_eq!(decode_charset(b"caf\xe9", Some("ISO-8859-1")), "café");
+        assert_eq!(decode_charset("café".as_bytes(), None), "café");
+    }
+}
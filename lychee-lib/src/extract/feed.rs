@@ -0,0 +1,220 @@
+use super::xml::{extract_xml_attributes, local_name, root_element, tag_end};
+use crate::types::uri::raw::RawUri;
+
+/// Attributes which hold links in feeds, e.g. `<link href>` in Atom,
+/// `<enclosure url>` in RSS and `<outline xmlUrl htmlUrl>` in OPML
+const LINK_ATTRIBUTES: &[&str] = &["href", "src", "url", "xmlUrl", "htmlUrl"];
+
+/// Root elements of RSS (including the RDF-based RSS 1.0), Atom and OPML
+/// documents
+const ROOT_ELEMENTS: &[&str] = &["rss", "RDF", "feed", "opml"];
+
+/// Check if an XML document is a feed or an OPML outline, e.g. a `feed.xml`,
+/// by its root element
+pub(crate) fn is_feed(input: &str) -> bool {
+    root_element(input).is_some_and(|root| ROOT_ELEMENTS.contains(&root))
+}
+
+/// Extract unparsed URL strings from an RSS or Atom feed or an OPML outline.
+///
+/// Links get extracted like from any other XML document, e.g. from the text
+/// of `<link>` and `<url>` elements, plus from the link attributes of feeds
+/// and outlines, such as the `xmlUrl` of the feeds listed in an OPML file.
+pub(crate) fn extract_feed(input: &str) -> Vec<RawUri> {
+    extract_xml_attributes(input, LINK_ATTRIBUTES)
+}
+
+/// Check that a feed is well-formed enough to trust the extracted links,
+/// i.e. that it has an RSS, Atom or OPML root element and that all elements
+/// are closed in the right order. Truncated downloads fail this check, too.
+///
+/// # Errors
+///
+/// Returns a description of the first problem, including its line
+pub(crate) fn validate_feed(input: &str) -> Result<(), String> {
+    let line = |rest: &str| input[..input.len() - rest.len()].matches('\n').count() + 1;
+
+    let mut root = None;
+    let mut open = vec![];
+    let mut rest = input;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata
+                .find("]]>")
+                .ok_or_else(|| format!("unterminated CDATA section on line {}", line(rest)))?;
+            rest = &cdata[end + "]]>".len()..];
+            continue;
+        }
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment
+                .find("-->")
+                .ok_or_else(|| format!("unterminated comment on line {}", line(rest)))?;
+            rest = &comment[end + "-->".len()..];
+            continue;
+        }
+        let end =
+            tag_end(rest).ok_or_else(|| format!("unterminated tag on line {}", line(rest)))?;
+        let tag = &rest[1..end];
+
+        match tag.strip_prefix('/') {
+            // Skip processing instructions and declarations
+            _ if tag.starts_with(['?', '!']) => {}
+            Some(name) => {
+                let name = name.trim();
+                match open.pop() {
+                    Some(expected) if expected == name => {}
+                    Some(expected) => {
+                        return Err(format!(
+                            "expected `</{expected}>`, found `</{name}>` on line {}",
+                            line(rest)
+                        ))
+                    }
+                    None => return Err(format!("unexpected `</{name}>` on line {}", line(rest))),
+                }
+            }
+            None => {
+                let name = tag
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or_default();
+                if root.is_none() {
+                    if !ROOT_ELEMENTS.contains(&local_name(name)) {
+                        return Err(format!(
+                            "expected an RSS, Atom or OPML document, found `<{name}>`"
+                        ));
+                    }
+                    root = Some(name);
+                } else if open.is_empty() {
+                    return Err(format!(
+                        "unexpected `<{name}>` after the root element on line {}",
+                        line(rest)
+                    ));
+                }
+                if !tag.ends_with('/') {
+                    open.push(name);
+                }
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+
+    match (root, open.last()) {
+        (None, _) => Err("no root element found".to_string()),
+        (Some(_), Some(name)) => Err(format!("missing `</{name}>` at the end of the document")),
+        (Some(_), None) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::load_fixture;
+
+    fn links(input: &str) -> Vec<String> {
+        extract_feed(input)
+            .into_iter()
+            .map(|uri| uri.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_rss_feed() {
+        let input = load_fixture("TEST_FEED.rss");
+        assert_eq!(validate_feed(&input), Ok(()));
+        assert_eq!(
+            links(&input),
+            vec![
+                "https://example.com/feed.rss",
+                "https://example.com/podcast.mp3",
+                "https://example.com/",
+                "https://example.com/logo.png",
+                "https://example.com/",
+                "https://example.com/posts/hello",
+                "https://example.com/docs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_opml_outline() {
+        let input = load_fixture("TEST_OPML.opml");
+        assert_eq!(validate_feed(&input), Ok(()));
+        assert_eq!(
+            links(&input),
+            vec![
+                "https://example.com/feed.rss",
+                "https://example.com/",
+                "https://example.org/atom.xml",
+                "https://example.net/blog/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_atom_feed() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <link rel="self" href="https://example.com/atom.xml"/>
+  <entry><link href="https://example.com/posts/1"/></entry>
+</feed>"#;
+        assert_eq!(validate_feed(input), Ok(()));
+        assert_eq!(
+            links(input),
+            vec![
+                "https://example.com/atom.xml",
+                "https://example.com/posts/1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_feed() {
+        assert!(is_feed(&load_fixture("TEST_FEED.rss")));
+        assert!(is_feed(&load_fixture("TEST_OPML.opml")));
+        assert!(is_feed(
+            r#"<?xml version="1.0"?><feed xmlns="http://www.w3.org/2005/Atom"/>"#
+        ));
+        assert!(is_feed(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">"#
+        ));
+        assert!(!is_feed(
+            "<urlset><url><loc>https://example.com</loc></url></urlset>"
+        ));
+        assert!(!is_feed("text mentioning <rss>"));
+    }
+
+    #[test]
+    fn test_malformed_feeds() {
+        for (input, error) in [
+            ("", "no root element found"),
+            (
+                "<html><body></body></html>",
+                "expected an RSS, Atom or OPML document, found `<html>`",
+            ),
+            (
+                "<rss>\n<channel>\n<link>https://example.com</channel>\n</rss>",
+                "expected `</link>`, found `</channel>` on line 3",
+            ),
+            (
+                "<rss>\n<channel>\n<link>https://exa",
+                "missing `</link>` at the end of the document",
+            ),
+            (
+                "<rss>\n<channel>\n<link href=\"https://example.com/>",
+                "unterminated tag on line 3",
+            ),
+            (
+                "<rss></rss>\n<rss></rss>",
+                "unexpected `<rss>` after the root element on line 2",
+            ),
+            (
+                "<opml></body></opml>",
+                "expected `</opml>`, found `</body>` on line 1",
+            ),
+            ("<feed></feed></feed>", "unexpected `</feed>` on line 1"),
+        ] {
+            assert_eq!(validate_feed(input), Err(error.to_string()), "{input}");
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use url::Url;
+
+use crate::types::uri::raw::RawUri;
+
+/// YAML front matter delimiter, e.g. used by Jekyll
+const YAML_DELIMITER: &str = "---";
+/// TOML front matter delimiter, e.g. used by Hugo and Zola
+const TOML_DELIMITER: &str = "+++";
+
+/// Extract URLs from the YAML (`---`) or TOML (`+++`) front matter at the
+/// beginning of a Markdown document.
+///
+/// Returns the extracted URLs and the remaining document without the front
+/// matter. Only string values that are absolute URLs are collected; all other
+/// values are ignored. Returns `None` if there is no (valid) front matter.
+pub(crate) fn extract_front_matter(input: &str) -> Option<(Vec<RawUri>, &str)> {
+    let first_line = input.lines().next()?.trim_end();
+    let delimiter = [YAML_DELIMITER, TOML_DELIMITER]
+        .into_iter()
+        .find(|delimiter| first_line == *delimiter)?;
+
+    // Find the closing delimiter, keeping track of the byte offsets
+    let mut offset = input.find('\n')? + 1;
+    let start = offset;
+    loop {
+        let rest = &input[offset..];
+        let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+        if rest[..line_end].trim_end() == delimiter {
+            let front_matter = &input[start..offset];
+            let body = &input[offset + line_end..];
+            let mut values = vec![];
+            if delimiter == YAML_DELIMITER {
+                // Front matter is always a mapping. This avoids mistaking a
+                // thematic break (`---`) followed by text for front matter.
+                let yaml: serde_yaml::Mapping = serde_yaml::from_str(front_matter).ok()?;
+                collect_yaml_strings(&serde_yaml::Value::Mapping(yaml), &mut values);
+            } else {
+                let toml: toml::Value = toml::from_str(front_matter).ok()?;
+                collect_toml_strings(&toml, &mut values);
+            }
+            let uris = values
+                .into_iter()
+                .filter(|value| is_absolute_url(value))
+                .map(|text| RawUri {
+                    text,
+                    element: None,
+                    attribute: None,
+                })
+                .collect();
+            return Some((uris, body));
+        }
+        if line_end == 0 {
+            // No closing delimiter
+            return None;
+        }
+        offset += line_end;
+    }
+}
+
+fn is_absolute_url(value: &str) -> bool {
+    matches!(Url::parse(value.trim()), Ok(url) if url.has_host())
+}
+
+fn collect_yaml_strings(value: &serde_yaml::Value, values: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::String(s) => values.push(s.clone()),
+        serde_yaml::Value::Sequence(seq) => {
+            for value in seq {
+                collect_yaml_strings(value, values);
+            }
+        }
+        serde_yaml::Value::Mapping(map) => {
+            for (_, value) in map {
+                collect_yaml_strings(value, values);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn collect_toml_strings(value: &toml::Value, values: &mut Vec<String>) {
+    match value {
+        toml::Value::String(s) => values.push(s.clone()),
+        toml::Value::Array(array) => {
+            for value in array {
+                collect_toml_strings(value, values);
+            }
+        }
+        toml::Value::Table(table) => {
+            for value in table.values() {
+                collect_toml_strings(value, values);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(uris: Vec<RawUri>) -> Vec<String> {
+        uris.into_iter().map(|uri| uri.text).collect()
+    }
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let input = "---\ntitle: Hello\ncanonical: https://example.com/post\ntags: [a, b]\nimages:\n  - https://example.com/a.png\n  - /relative.png\n---\n# Body\n";
+        let (uris, body) = extract_front_matter(input).unwrap();
+        assert_eq!(
+            texts(uris),
+            vec!["https://example.com/post", "https://example.com/a.png"]
+        );
+        assert_eq!(body, "# Body\n");
+    }
+
+    #[test]
+    fn test_toml_front_matter() {
+        let input = "+++\ntitle = \"Hello\"\nimage = \"https://example.com/image.png\"\n[extra]\nsource = \"https://example.com/source\"\n+++\nBody";
+        let (uris, body) = extract_front_matter(input).unwrap();
+        assert_eq!(
+            texts(uris),
+            vec![
+                "https://example.com/source",
+                "https://example.com/image.png"
+            ]
+        );
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn test_no_front_matter() {
+        assert!(extract_front_matter("# Title\n---\n").is_none());
+        assert!(extract_front_matter("---\nunclosed: https://example.com\n").is_none());
+        assert!(extract_front_matter("").is_none());
+        // Thematic breaks around a paragraph are not front matter
+        assert!(extract_front_matter("---\nSee https://example.com\n---\n").is_none());
+    }
+}
@@ -2,16 +2,20 @@ use pulldown_cmark::{Event, Parser, Tag};
 
 use crate::{extract::plaintext::extract_plaintext, types::uri::raw::RawUri};
 
-use super::html5gum::extract_html;
+use super::{front_matter::extract_front_matter, html5gum::extract_html};
 
 /// Extract unparsed URL strings from a Markdown string.
+///
+/// URLs in YAML or TOML front matter are extracted as well.
 pub(crate) fn extract_markdown(input: &str, include_verbatim: bool) -> Vec<RawUri> {
+    let (mut uris, input) = extract_front_matter(input).unwrap_or((vec![], input));
+
     // In some cases it is undesirable to extract links from within code blocks,
     // which is why we keep track of entries and exits while traversing the input.
     let mut inside_code_block = false;
 
     let parser = Parser::new(input);
-    parser
+    let links = parser
         .filter_map(|event| match event {
             // A link. The first field is the link type, the second the destination URL and the third is a title.
             Event::Start(Tag::Link(_, uri, _)) => {
@@ -73,8 +77,9 @@ pub(crate) fn extract_markdown(input: &str, include_verbatim: bool) -> Vec<RawUr
             // Silently skip over other events
             _ => None,
         })
-        .flatten()
-        .collect()
+        .flatten();
+    uris.extend(links);
+    uris
 }
 
 #[cfg(test)]
@@ -1,12 +1,24 @@
 use crate::types::{uri::raw::RawUri, FileType, InputContent};
 
+mod asciidoc;
+mod eml;
+mod feed;
+mod front_matter;
 mod html5ever;
 mod html5gum;
 mod markdown;
+mod notebook;
 mod plaintext;
+mod xml;
 
+use asciidoc::extract_asciidoc;
+use feed::extract_feed;
+pub(crate) use feed::{is_feed, validate_feed};
 use markdown::extract_markdown;
+use notebook::extract_notebook;
 use plaintext::extract_plaintext;
+use xml::extract_xml;
+pub(crate) use xml::root_element;
 
 /// Check if the given element is in the list of preformatted ("verbatim") tags.
 ///
@@ -49,19 +61,33 @@ impl Extractor {
     }
 
     /// Main entrypoint for extracting links from various sources
-    /// (Markdown, HTML, and plaintext)
+    /// (Markdown, HTML, Jupyter notebooks, emails, `AsciiDoc`, XML, feeds, and
+    /// plaintext)
     #[must_use]
     pub fn extract(&self, input_content: &InputContent) -> Vec<RawUri> {
-        match input_content.file_type {
-            FileType::Markdown => extract_markdown(&input_content.content, self.include_verbatim),
+        self.extract_content(input_content.file_type, &input_content.content)
+    }
+
+    fn extract_content(self, file_type: FileType, content: &str) -> Vec<RawUri> {
+        match file_type {
+            FileType::Markdown => extract_markdown(content, self.include_verbatim),
             FileType::Html => {
                 if self.use_html5ever {
-                    html5ever::extract_html(&input_content.content, self.include_verbatim)
+                    html5ever::extract_html(content, self.include_verbatim)
                 } else {
-                    html5gum::extract_html(&input_content.content, self.include_verbatim)
+                    html5gum::extract_html(content, self.include_verbatim)
                 }
             }
-            FileType::Plaintext => extract_plaintext(&input_content.content),
+            FileType::Notebook => extract_notebook(content, self.include_verbatim),
+            FileType::Email => eml::text_parts(content)
+                .into_iter()
+                .flat_map(|part| self.extract_content(part.file_type, &part.text))
+                .collect(),
+            FileType::AsciiDoc => extract_asciidoc(content, self.include_verbatim),
+            FileType::Xml if is_feed(content) => extract_feed(content),
+            FileType::Xml => extract_xml(content),
+            FileType::Feed => extract_feed(content),
+            FileType::Plaintext => extract_plaintext(content),
         }
     }
 }
@@ -283,6 +309,37 @@ mod tests {
         assert_eq!(links, expected_links);
     }
 
+    #[test]
+    fn test_extract_yaml_front_matter() {
+        let input = load_fixture("TEST_FRONT_MATTER_YAML.md");
+        let links = extract_uris(&input, FileType::Markdown);
+
+        let expected_links = IntoIterator::into_iter([
+            website("https://example.com/canonical"),
+            website("https://example.com/image.png"),
+            website("https://example.com/jane"),
+            website("https://example.com/body"),
+        ])
+        .collect::<HashSet<Uri>>();
+
+        assert_eq!(links, expected_links);
+    }
+
+    #[test]
+    fn test_extract_toml_front_matter() {
+        let input = load_fixture("TEST_FRONT_MATTER_TOML.md");
+        let links = extract_uris(&input, FileType::Markdown);
+
+        let expected_links = IntoIterator::into_iter([
+            website("https://example.com/canonical"),
+            website("https://example.com/image.png"),
+            website("https://example.com/body"),
+        ])
+        .collect::<HashSet<Uri>>();
+
+        assert_eq!(links, expected_links);
+    }
+
     #[test]
     fn test_extract_urls_with_at_sign_properly() {
         // note that these used to parse as emails
@@ -298,6 +355,34 @@ mod tests {
         assert_eq!(links, expected_links);
     }
 
+    #[test]
+    fn test_extract_email() {
+        let input = load_fixture("TEST_EMAIL.eml");
+        let links = extract_uris(&input, FileType::Email);
+
+        let expected_links = IntoIterator::into_iter([
+            website("https://example.com/plain?issue=42&lang=en"),
+            website("https://example.com/café"),
+            website("https://example.com/newsletter"),
+        ])
+        .collect::<HashSet<Uri>>();
+
+        assert_eq!(links, expected_links);
+    }
+
+    #[test]
+    fn test_extract_feed_from_xml() {
+        // Feeds are often served as `feed.xml` or `atom.xml`
+        let input = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <link href="https://example.com/atom.xml"/>
+</feed>"#;
+        let links = extract_uris(input, FileType::Xml);
+        assert_eq!(
+            links,
+            HashSet::from([website("https://example.com/atom.xml")])
+        );
+    }
+
     #[test]
     fn test_extract_link_at_end_of_line() {
         let input = "https://www.apache.org/licenses/LICENSE-2.0\n";
@@ -0,0 +1,117 @@
+use serde::Deserialize;
+
+use super::{markdown::extract_markdown, plaintext::extract_plaintext};
+use crate::types::uri::raw::RawUri;
+
+/// A Jupyter notebook in the [nbformat 4](https://nbformat.readthedocs.io)
+/// JSON format. Only the fields relevant for link extraction are parsed.
+#[derive(Deserialize)]
+struct Notebook {
+    cells: Vec<Cell>,
+}
+
+#[derive(Deserialize)]
+struct Cell {
+    #[serde(rename = "cell_type")]
+    kind: String,
+    #[serde(default)]
+    source: Text,
+    #[serde(default)]
+    outputs: Vec<Output>,
+}
+
+#[derive(Deserialize)]
+struct Output {
+    /// Text of `stream` outputs, e.g. from `print()`
+    #[serde(default)]
+    text: Text,
+}
+
+/// Multiline text, which is either a single string or a list of lines
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Text {
+    Lines(Vec<String>),
+    String(String),
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self::String(String::new())
+    }
+}
+
+impl Text {
+    fn join(&self) -> String {
+        match self {
+            Text::Lines(lines) => lines.concat(),
+            Text::String(s) => s.clone(),
+        }
+    }
+}
+
+/// Extract unparsed URL strings from a Jupyter notebook.
+///
+/// Links get extracted from Markdown cells. Code cells and their outputs are
+/// skipped, unless `include_verbatim` is set. Falls back to plaintext
+/// extraction if the notebook cannot be parsed.
+pub(crate) fn extract_notebook(input: &str, include_verbatim: bool) -> Vec<RawUri> {
+    let notebook: Notebook = match serde_json::from_str(input) {
+        Ok(notebook) => notebook,
+        Err(_) => return extract_plaintext(input),
+    };
+
+    let mut uris = vec![];
+    for cell in notebook.cells {
+        match cell.kind.as_str() {
+            "markdown" => uris.extend(extract_markdown(&cell.source.join(), include_verbatim)),
+            "code" if include_verbatim => {
+                uris.extend(extract_plaintext(&cell.source.join()));
+                for output in cell.outputs {
+                    uris.extend(extract_plaintext(&output.text.join()));
+                }
+            }
+            _ => (),
+        }
+    }
+    uris
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::load_fixture;
+
+    fn texts(uris: Vec<RawUri>) -> Vec<String> {
+        uris.into_iter().map(|uri| uri.text).collect()
+    }
+
+    #[test]
+    fn test_extract_markdown_cells() {
+        let input = load_fixture("TEST_NOTEBOOK.ipynb");
+        assert_eq!(
+            texts(extract_notebook(&input, false)),
+            vec![
+                "https://jupyter.org/",
+                "https://example.com/docs",
+                "https://example.com/single-string-source"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_code_cells_if_verbatim() {
+        let input = load_fixture("TEST_NOTEBOOK.ipynb");
+        let uris = texts(extract_notebook(&input, true));
+        assert!(uris.contains(&"https://example.com/api".to_string()));
+        assert!(uris.contains(&"https://example.com/output".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_notebook() {
+        assert_eq!(
+            texts(extract_notebook("not json https://example.com", false)),
+            vec!["https://example.com"]
+        );
+    }
+}
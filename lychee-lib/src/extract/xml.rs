@@ -0,0 +1,273 @@
+use std::borrow::Cow;
+
+use super::plaintext::extract_plaintext;
+use crate::types::uri::raw::RawUri;
+
+/// Extract unparsed URL strings from an XML document, e.g. the documentation
+/// files generated for Java or .NET projects.
+///
+/// Links get extracted from the `href` and `src` attributes of any element
+/// and from the text content, including `CDATA` sections. Namespace prefixes
+/// are ignored, so `xlink:href` counts as `href`. Like in plain text, links
+/// are also found in comments and in the values of all other attributes
+/// (e.g. `url` or `alt`), except for namespace declarations, whose values are
+/// identifiers rather than links. Markup which can't be parsed (e.g. a tag
+/// without a closing `>`) gets treated as plain text.
+pub(crate) fn extract_xml(input: &str) -> Vec<RawUri> {
+    extract_xml_attributes(input, &["href", "src"])
+}
+
+/// Extract unparsed URL strings from an XML document like [`extract_xml`],
+/// but from the given link `attributes` (by local name) instead of `href`
+/// and `src`
+pub(super) fn extract_xml_attributes(input: &str, attributes: &[&str]) -> Vec<RawUri> {
+    let mut uris = vec![];
+    let mut text = String::new();
+    let mut rest = input;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&unescape(&rest[..start]));
+        rest = &rest[start..];
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").unwrap_or(cdata.len());
+            text.push_str(&cdata[..end]);
+            rest = cdata.get(end + "]]>".len()..).unwrap_or_default();
+        } else if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").unwrap_or(comment.len());
+            text.push_str(&comment[..end]);
+            rest = comment.get(end + "-->".len()..).unwrap_or_default();
+        } else {
+            let Some(end) = tag_end(rest) else {
+                break;
+            };
+            let tag = &rest[1..end];
+            // Skip end tags, processing instructions and declarations
+            if !tag.starts_with(['/', '?', '!']) {
+                uris.extend(tag_uris(tag, attributes));
+            }
+            rest = &rest[end + 1..];
+        }
+        // Keep the text of neighboring elements apart
+        text.push('\n');
+    }
+    text.push_str(&unescape(rest));
+    uris.extend(extract_plaintext(&text));
+    uris
+}
+
+/// Get the local name of the root element of an XML document, e.g. `rss` or
+/// `urlset`, skipping the XML declaration, comments and the doctype
+///
+/// Returns `None` if the document doesn't start with an element, e.g. for
+/// plain text which only mentions an element later on.
+pub(crate) fn root_element(input: &str) -> Option<&str> {
+    let mut rest = input.trim_start_matches('\u{feff}').trim_start();
+    loop {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = &comment[comment.find("-->")? + "-->".len()..];
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            rest = &rest[tag_end(rest)? + 1..];
+        } else {
+            let tag = rest.strip_prefix('<')?;
+            let name = tag
+                .split(|c: char| c.is_whitespace() || matches!(c, '/' | '>'))
+                .next()
+                .filter(|name| !name.is_empty())?;
+            return Some(local_name(name));
+        }
+        rest = rest.trim_start();
+    }
+}
+
+/// Find the end (`>`) of the tag at the start of `markup`, skipping over
+/// quoted attribute values
+pub(super) fn tag_end(markup: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in markup.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Get the links from the given attributes of a start tag (without the angle
+/// brackets)
+fn tag_uris(tag: &str, link_attributes: &[&str]) -> Vec<RawUri> {
+    let tag = tag.trim_end_matches('/');
+    let (name, mut attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+    let element = local_name(name);
+
+    let mut uris = vec![];
+    while let Some((attribute, value)) = attributes.split_once('=') {
+        let attribute = attribute.trim();
+        let value = value.trim_start();
+        let Some(quote) = value.chars().next().filter(|c| matches!(c, '"' | '\'')) else {
+            break;
+        };
+        let Some((value, remaining)) = value[1..].split_once(quote) else {
+            break;
+        };
+        attributes = remaining;
+
+        if attribute.starts_with("xmlns") {
+            continue;
+        }
+        let attribute = local_name(attribute);
+        if link_attributes.contains(&attribute) {
+            uris.push(RawUri {
+                text: unescape(value.trim()).into_owned(),
+                element: Some(element.to_string()),
+                attribute: Some(attribute.to_string()),
+            });
+        } else {
+            uris.extend(
+                extract_plaintext(&unescape(value))
+                    .into_iter()
+                    .map(|uri| RawUri {
+                        element: Some(element.to_string()),
+                        attribute: Some(attribute.to_string()),
+                        ..uri
+                    }),
+            );
+        }
+    }
+    uris
+}
+
+/// Strip the namespace prefix of a name, e.g. `xlink:href` becomes `href`
+pub(super) fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Replace entity and character references, e.g. `&amp;` or `&#x26;`.
+/// Unknown references are kept as-is.
+fn unescape(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let reference = rest
+            .find(';')
+            .and_then(|end| Some((reference(&rest[1..end])?, end)));
+        if let Some((c, end)) = reference {
+            out.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Resolve the name of a predefined entity or a (decimal or hexadecimal)
+/// character reference
+fn reference(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = match name.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::load_fixture;
+
+    #[test]
+    fn test_attribute_links() {
+        let input = load_fixture("TEST_XML_DOC.xml");
+        let links: Vec<_> = extract_xml(&input)
+            .into_iter()
+            .filter(|uri| uri.attribute.is_some())
+            .map(|uri| (uri.element.unwrap(), uri.attribute.unwrap(), uri.text))
+            .collect();
+        let link = |element: &str, attribute: &str, text: &str| {
+            (element.to_string(), attribute.to_string(), text.to_string())
+        };
+        assert_eq!(
+            links,
+            vec![
+                link("see", "href", "https://example.com/see-href"),
+                link("a", "href", "https://example.com/a-href?a=1&b=2"),
+                link("seealso", "href", "https://example.com/xlink"),
+                link("img", "src", "images/diagram.png"),
+                link("img", "alt", "https://example.com/alt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_links() {
+        let input = load_fixture("TEST_XML_DOC.xml");
+        let links: Vec<_> = extract_xml(&input)
+            .into_iter()
+            .filter(|uri| uri.attribute.is_none())
+            .map(|uri| uri.text)
+            .collect();
+        // No links from namespace declarations
+        assert_eq!(
+            links,
+            vec![
+                "https://example.com/comment",
+                "https://example.com/api",
+                "https://example.com/cdata"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_markup() {
+        let links = |input: &str| -> Vec<_> {
+            extract_xml(input).into_iter().map(|uri| uri.text).collect()
+        };
+        // The rest of the document is treated as plain text
+        assert_eq!(
+            links("<a href=\"https://example.com/a\" https://example.com/b"),
+            vec!["https://example.com/a", "https://example.com/b"]
+        );
+        assert_eq!(
+            links("<!-- https://example.com/a <b>https://example.com/b</b>"),
+            vec!["https://example.com/a", "https://example.com/b"]
+        );
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape("a &lt;b&gt; &amp;&#38;&#x26;"), "a <b> &&&");
+        assert_eq!(unescape("AT&T &unknown; &#xZZ;"), "AT&T &unknown; &#xZZ;");
+    }
+
+    #[test]
+    fn test_root_element() {
+        assert_eq!(
+            root_element(
+                "\u{feff}<?xml version=\"1.0\"?>\n<!-- <html> -->\n<!DOCTYPE x>\n<atom:feed xmlns:atom=\"\">"
+            ),
+            Some("feed")
+        );
+        assert_eq!(root_element("<urlset/>"), Some("urlset"));
+        assert_eq!(root_element("  <rss>"), Some("rss"));
+        assert_eq!(root_element("text <urlset>"), None);
+        assert_eq!(root_element("<!-- unterminated"), None);
+        assert_eq!(root_element(""), None);
+    }
+}
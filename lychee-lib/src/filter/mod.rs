@@ -82,6 +82,12 @@ pub struct Filter {
     pub exclude_loopback_ips: bool,
     /// Example: octocat@github.com
     pub exclude_mail: bool,
+    /// Hosts excluded from checking together with their subdomains, e.g.
+    /// `example.com` also excludes `www.example.com`. Expected in lowercase.
+    pub exclude_hosts: HashSet<String>,
+    /// If not empty, only hosts in this set are checked together with their
+    /// subdomains, and all other URIs are excluded. Expected in lowercase.
+    pub include_hosts: HashSet<String>,
 }
 
 impl Filter {
@@ -108,8 +114,25 @@ impl Filter {
     #[must_use]
     /// Whether the host is excluded from checking
     pub fn is_host_excluded(&self, uri: &Uri) -> bool {
+        let Some(domain) = uri.domain() else {
+            return false;
+        };
         // If loopback IPs are excluded, exclude localhost as well, which usually maps to a loopback IP
-        self.exclude_loopback_ips && uri.domain() == Some("localhost")
+        if self.exclude_loopback_ips && domain == "localhost" {
+            return true;
+        }
+        matches_host(domain, &self.exclude_hosts)
+    }
+
+    #[must_use]
+    /// Whether the URI is excluded, because it doesn't point to one of the
+    /// included hosts (if any)
+    pub fn is_host_not_included(&self, uri: &Uri) -> bool {
+        if self.include_hosts.is_empty() {
+            return false;
+        }
+        !uri.domain()
+            .is_some_and(|domain| matches_host(domain, &self.include_hosts))
     }
 
     #[inline]
@@ -150,6 +173,7 @@ impl Filter {
     ///   - If it's a mail address and it's configured to ignore mail addresses.
     ///   - If the IP address belongs to a type that is configured to exclude.
     ///   - If the host belongs to a type that is configured to exclude.
+    ///   - If only some hosts are included and the host isn't one of them.
     ///   - If the scheme of URI is not the allowed scheme.
     /// 2. Decide whether the URI is *presumably included* or *explicitly included*:
     ///    - When both excludes and includes rules are empty, it's *presumably included* unless
@@ -167,6 +191,7 @@ impl Filter {
         if self.is_mail_excluded(uri)
             || self.is_ip_excluded(uri)
             || self.is_host_excluded(uri)
+            || self.is_host_not_included(uri)
             || self.is_scheme_excluded(uri)
             || is_example_domain(uri)
         {
@@ -202,8 +227,20 @@ impl Filter {
     }
 }
 
+/// Whether `domain` or one of its parent domains is in `hosts`
+fn matches_host(domain: &str, hosts: &HashSet<String>) -> bool {
+    let domain = domain.trim_end_matches('.');
+    domain
+        .match_indices('.')
+        .map(|(i, _)| &domain[i + 1..])
+        .chain([domain])
+        .any(|host| hosts.contains(host))
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use regex::RegexSet;
     use reqwest::Url;
     use url::Host;
@@ -341,6 +378,46 @@ mod tests {
         assert!(!filter.is_excluded(&website("http://bar.dev")));
     }
 
+    #[test]
+    fn test_exclude_hosts() {
+        let filter = Filter {
+            exclude_hosts: HashSet::from_iter(["example.com".to_string()]),
+            ..Filter::default()
+        };
+
+        assert!(filter.is_excluded(&website("https://example.com")));
+        assert!(filter.is_excluded(&website("https://www.example.com/path")));
+        assert!(filter.is_excluded(&website("https://a.b.EXAMPLE.com.")));
+        assert!(!filter.is_excluded(&website("https://notexample.com")));
+        assert!(!filter.is_excluded(&website("https://example.com.evil.org")));
+        assert!(!filter.is_excluded(&website("https://example.org")));
+        assert!(!filter.is_excluded(&mail("mail@example.com")));
+    }
+
+    #[test]
+    fn test_include_hosts() {
+        let filter = Filter {
+            include_hosts: HashSet::from_iter(["docs.example.com".to_string()]),
+            ..Filter::default()
+        };
+
+        assert!(!filter.is_excluded(&website("https://docs.example.com")));
+        assert!(!filter.is_excluded(&website("https://v2.docs.EXAMPLE.com/page")));
+        assert!(filter.is_excluded(&website("https://example.com")));
+        assert!(filter.is_excluded(&website("https://notdocs.example.com")));
+        assert!(filter.is_excluded(&website("https://docs.example.com.evil.org")));
+        assert!(filter.is_excluded(&mail("mail@docs.example.com")));
+
+        // Excluded hosts take precedence
+        let filter = Filter {
+            include_hosts: HashSet::from_iter(["example.com".to_string()]),
+            exclude_hosts: HashSet::from_iter(["old.example.com".to_string()]),
+            ..Filter::default()
+        };
+        assert!(!filter.is_excluded(&website("https://example.com")));
+        assert!(filter.is_excluded(&website("https://old.example.com")));
+    }
+
     #[test]
     fn test_exclude_regex() {
         let excludes = Excludes {
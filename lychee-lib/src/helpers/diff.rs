@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+/// Extract the added lines from a unified diff (e.g. the output of `git diff`)
+///
+/// Returns the path of each changed file (as seen after the change) together
+/// with its added lines. Removed and context lines are skipped, as are deleted
+/// files.
+pub(crate) fn added_lines(diff: &str) -> Vec<(PathBuf, Vec<&str>)> {
+    let mut files: Vec<(PathBuf, Vec<&str>)> = Vec::new();
+    let mut current: Option<PathBuf> = None;
+    // Remaining number of old and new lines in the current hunk
+    let mut remaining_old = 0;
+    let mut remaining_new = 0;
+
+    for line in diff.lines() {
+        if remaining_old > 0 || remaining_new > 0 {
+            match line.chars().next() {
+                Some('+') => {
+                    remaining_new -= 1;
+                    if let Some(path) = &current {
+                        match files.last_mut() {
+                            Some((last, lines)) if last == path => lines.push(&line[1..]),
+                            _ => files.push((path.clone(), vec![&line[1..]])),
+                        }
+                    }
+                }
+                Some('-') => remaining_old -= 1,
+                // "\ No newline at end of file"
+                Some('\\') => (),
+                _ => {
+                    remaining_old -= 1;
+                    remaining_new -= 1;
+                }
+            }
+            // Guard against malformed hunk headers
+            remaining_old = remaining_old.max(0);
+            remaining_new = remaining_new.max(0);
+        } else if let Some(header) = line.strip_prefix("+++ ") {
+            current = parse_path(header);
+        } else if let Some(hunk) = line.strip_prefix("@@ ") {
+            if let Some((old, new)) = parse_hunk_header(hunk) {
+                remaining_old = old;
+                remaining_new = new;
+            }
+        }
+    }
+    files
+}
+
+/// Parse the new file path from a `+++ b/path` header
+fn parse_path(header: &str) -> Option<PathBuf> {
+    // Some diff tools append a timestamp separated by a tab
+    let path = header.split('\t').next()?.trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(PathBuf::from(path.strip_prefix("b/").unwrap_or(path)))
+}
+
+/// Parse the line counts from a hunk header like `-1,3 +1,4 @@`
+fn parse_hunk_header(hunk: &str) -> Option<(i64, i64)> {
+    let mut ranges = hunk.split_whitespace();
+    let old = ranges.next()?.strip_prefix('-')?;
+    let new = ranges.next()?.strip_prefix('+')?;
+    Some((range_len(old)?, range_len(new)?))
+}
+
+/// The length of a range like `1,3`, which defaults to 1 if omitted
+fn range_len(range: &str) -> Option<i64> {
+    match range.split_once(',') {
+        Some((_, len)) => len.parse().ok(),
+        None => Some(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/README.md b/README.md
+index 1234567..89abcde 100644
+--- a/README.md
++++ b/README.md
+@@ -1,3 +1,4 @@
+ # Title
+-Old link https://old.example.com
++New link https://new.example.com
++--- not a header
+ Unchanged https://unchanged.example.com
+diff --git a/removed.md b/removed.md
+deleted file mode 100644
+--- a/removed.md
++++ /dev/null
+@@ -1 +0,0 @@
+-https://removed.example.com
+diff --git a/docs/new.html b/docs/new.html
+new file mode 100644
+--- /dev/null
++++ b/docs/new.html
+@@ -0,0 +1 @@
++<a href=\"https://added.example.com\">Added</a>
+";
+
+    #[test]
+    fn test_added_lines() {
+        let files = added_lines(DIFF);
+        assert_eq!(
+            files,
+            vec![
+                (
+                    PathBuf::from("README.md"),
+                    vec!["New link https://new.example.com", "--- not a header"]
+                ),
+                (
+                    PathBuf::from("docs/new.html"),
+                    vec!["<a href=\"https://added.example.com\">Added</a>"]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_hunk_header() {
+        assert_eq!(parse_hunk_header("-1,3 +1,4 @@ fn main()"), Some((3, 4)));
+        assert_eq!(parse_hunk_header("-1 +1 @@"), Some((1, 1)));
+        assert_eq!(parse_hunk_header("garbage"), None);
+    }
+}
@@ -0,0 +1,42 @@
+use html5gum::{Token, Tokenizer};
+
+/// Count the anchors in an HTML document which match the given fragment.
+///
+/// An element is an anchor if its `id` attribute matches the fragment.
+/// For `<a>` elements, the (legacy) `name` attribute is considered as well.
+/// More than one match means that the fragment is ambiguous.
+pub(crate) fn count_anchors(html: &str, fragment: &str) -> usize {
+    let fragment = fragment.as_bytes();
+    Tokenizer::new(html)
+        .infallible()
+        .filter(|token| match token {
+            Token::StartTag(tag) => {
+                let matches =
+                    |attr: &[u8]| tag.attributes.get(attr).map(|v| v.as_slice()) == Some(fragment);
+                matches(b"id") || (tag.name.as_slice() == b"a" && matches(b"name"))
+            }
+            _ => false,
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::load_fixture;
+
+    #[test]
+    fn test_count_anchors() {
+        let html = r#"<h1 id="intro">Intro</h1><a name="usage"></a><p id="usage">Usage</p>"#;
+        assert_eq!(count_anchors(html, "intro"), 1);
+        assert_eq!(count_anchors(html, "usage"), 2);
+        assert_eq!(count_anchors(html, "missing"), 0);
+    }
+
+    #[test]
+    fn test_duplicate_anchors_fixture() {
+        let html = load_fixture("TEST_DUPLICATE_ANCHORS.html");
+        assert_eq!(count_anchors(&html, "duplicate"), 2);
+        assert_eq!(count_anchors(&html, "unique"), 1);
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use glob::{MatchOptions, Pattern};
+
+/// Name of the files with patterns of paths to ignore
+const GITIGNORE_FILE: &str = ".gitignore";
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Rules from `.gitignore` files, which are used to skip files and
+/// directories (e.g. vendored or generated ones) when collecting inputs.
+///
+/// Supports the commonly used subset of the
+/// [gitignore format](https://git-scm.com/docs/gitignore): comments,
+/// negation (`!`), directory-only patterns (trailing `/`), anchored patterns
+/// (containing a `/`) and glob wildcards including `**`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct GitIgnore {
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    /// Directory of the `.gitignore` file the rule comes from
+    base: PathBuf,
+    pattern: Pattern,
+    negated: bool,
+    dir_only: bool,
+    /// Match the path relative to `base` instead of only the file name
+    anchored: bool,
+}
+
+impl GitIgnore {
+    /// Add the rules of the `.gitignore` file in `dir` (if there is one)
+    pub(crate) fn add_dir(&mut self, dir: &Path) {
+        if let Ok(content) = fs::read_to_string(dir.join(GITIGNORE_FILE)) {
+            self.add_rules(dir, &content);
+        }
+    }
+
+    fn add_rules(&mut self, base: &Path, content: &str) {
+        for line in content.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (negated, line) = match line.strip_prefix('!') {
+                Some(line) => (true, line),
+                None => (false, line),
+            };
+            let (dir_only, line) = match line.strip_suffix('/') {
+                Some(line) => (true, line),
+                None => (false, line),
+            };
+            let anchored = line.contains('/');
+            if let Ok(pattern) = Pattern::new(line.trim_start_matches('/')) {
+                self.rules.push(Rule {
+                    base: base.to_path_buf(),
+                    pattern,
+                    negated,
+                    dir_only,
+                    anchored,
+                });
+            }
+        }
+    }
+
+    /// Check if `path` is ignored by any of the rules.
+    /// Later rules take precedence over earlier ones.
+    pub(crate) fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(&rule.base) else {
+                continue;
+            };
+            let matches = if rule.anchored {
+                rule.pattern.matches_path_with(relative, MATCH_OPTIONS)
+            } else {
+                matches!(relative.file_name(), Some(name)
+                    if rule.pattern.matches_with(&name.to_string_lossy(), MATCH_OPTIONS))
+            };
+            if matches {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// The `.gitignore` rules for the paths below a `root` directory, e.g. to
+/// filter the paths matched by a glob pattern
+///
+/// The `.gitignore` files of each directory get read and parsed only once.
+#[derive(Debug)]
+pub(crate) struct GitIgnoreTree {
+    root: PathBuf,
+    /// Rules of all `.gitignore` files from `root` down to a directory
+    dirs: HashMap<PathBuf, GitIgnore>,
+}
+
+impl GitIgnoreTree {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            dirs: HashMap::new(),
+        }
+    }
+
+    /// Check if `path` or one of its parent directories below `root` is
+    /// ignored by the `.gitignore` files in `root` and its subdirectories
+    pub(crate) fn is_ignored(&mut self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        let mut dir = self.root.clone();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            let current = dir.join(component);
+            if let Component::Normal(_) = component {
+                let is_dir = components.peek().is_some() || current.is_dir();
+                if self.rules(&dir).is_ignored(&current, is_dir) {
+                    return true;
+                }
+            }
+            dir = current;
+        }
+        false
+    }
+
+    /// Get the rules which apply to the entries of `dir`
+    fn rules(&mut self, dir: &Path) -> &GitIgnore {
+        if !self.dirs.contains_key(dir) {
+            let mut gitignore = match dir.parent() {
+                Some(parent) if dir != self.root => self.rules(parent).clone(),
+                _ => GitIgnore::default(),
+            };
+            gitignore.add_dir(dir);
+            self.dirs.insert(dir.to_path_buf(), gitignore);
+        }
+        &self.dirs[dir]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gitignore(content: &str) -> GitIgnore {
+        let mut gitignore = GitIgnore::default();
+        gitignore.add_rules(Path::new("root"), content);
+        gitignore
+    }
+
+    #[test]
+    fn test_file_name_patterns() {
+        let gitignore = gitignore("# comment\n*.log\n\nvendor\n");
+        assert!(gitignore.is_ignored(Path::new("root/debug.log"), false));
+        assert!(gitignore.is_ignored(Path::new("root/a/b/debug.log"), false));
+        assert!(gitignore.is_ignored(Path::new("root/a/vendor"), true));
+        assert!(!gitignore.is_ignored(Path::new("root/README.md"), false));
+        assert!(!gitignore.is_ignored(Path::new("other/debug.log"), false));
+    }
+
+    #[test]
+    fn test_anchored_and_dir_only_patterns() {
+        let gitignore = gitignore("/target/\ndocs/generated\n");
+        assert!(gitignore.is_ignored(Path::new("root/target"), true));
+        assert!(!gitignore.is_ignored(Path::new("root/target"), false));
+        assert!(!gitignore.is_ignored(Path::new("root/sub/target"), true));
+        assert!(gitignore.is_ignored(Path::new("root/docs/generated"), true));
+        assert!(!gitignore.is_ignored(Path::new("root/sub/docs/generated"), true));
+    }
+
+    #[test]
+    fn test_negation_and_double_star() {
+        let gitignore = gitignore("**/build/*.html\n!**/build/index.html\n");
+        assert!(gitignore.is_ignored(Path::new("root/a/build/page.html"), false));
+        assert!(!gitignore.is_ignored(Path::new("root/a/build/index.html"), false));
+    }
+
+    #[test]
+    fn test_tree() {
+        let root = tempfile::tempdir().unwrap();
+        let sub = root.path().join("sub");
+        fs::create_dir_all(sub.join("generated")).unwrap();
+        fs::write(root.path().join(GITIGNORE_FILE), "*.log\n").unwrap();
+        fs::write(sub.join(GITIGNORE_FILE), "generated/\n!keep.log\n").unwrap();
+
+        let mut tree = GitIgnoreTree::new(root.path().to_path_buf());
+        assert!(tree.is_ignored(&root.path().join("debug.log")));
+        assert!(tree.is_ignored(&sub.join("debug.log")));
+        assert!(!tree.is_ignored(&sub.join("keep.log")));
+        assert!(tree.is_ignored(&sub.join("generated/index.html")));
+        assert!(!tree.is_ignored(&sub.join("index.html")));
+        assert!(!tree.is_ignored(Path::new("/elsewhere/debug.log")));
+        // Each `.gitignore` file got parsed once
+        assert_eq!(tree.dirs.len(), 2);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use http::header::{HeaderMap, STRICT_TRANSPORT_SECURITY};
+use reqwest::Url;
+
+/// Hosts which sent an HTTP Strict Transport Security (HSTS) policy during
+/// the current run, so that later `http` links to them get upgraded to
+/// `https`, like browsers do.
+///
+/// Links are checked concurrently, so only links checked after the response
+/// with the policy arrived get upgraded.
+#[derive(Debug, Default)]
+pub(crate) struct Hsts {
+    /// Known hosts, mapped to whether the policy includes their subdomains
+    hosts: Mutex<HashMap<String, bool>>,
+}
+
+impl Hsts {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember the HSTS policy of a response from `url`, if it has one.
+    ///
+    /// Only policies received over HTTPS count, as policies sent over HTTP
+    /// could have been injected by anyone (RFC 6797, section 8.1). Hosts
+    /// which are IP addresses are ignored as well (ibid.). A `max-age` of 0
+    /// removes the host again.
+    pub(crate) fn record(&self, url: &Url, headers: &HeaderMap) {
+        if url.scheme() != "https" {
+            return;
+        }
+        let Some(host) = url.domain() else {
+            return;
+        };
+        let Some((max_age, include_subdomains)) = headers
+            .get(STRICT_TRANSPORT_SECURITY)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_policy)
+        else {
+            return;
+        };
+        let mut hosts = self.hosts.lock().expect("HSTS lock poisoned");
+        if max_age == 0 {
+            hosts.remove(host);
+        } else {
+            hosts.insert(host.to_ascii_lowercase(), include_subdomains);
+        }
+    }
+
+    /// Get the `https` version of an `http` URL, if its host (or a parent
+    /// domain including its subdomains) sent an HSTS policy
+    pub(crate) fn upgrade(&self, url: &Url) -> Option<Url> {
+        if url.scheme() != "http" {
+            return None;
+        }
+        let host = url.domain()?;
+        let hosts = self.hosts.lock().expect("HSTS lock poisoned");
+        let known = hosts.contains_key(host)
+            || host
+                .match_indices('.')
+                .any(|(i, _)| hosts.get(&host[i + 1..]) == Some(&true));
+        if !known {
+            return None;
+        }
+
+        let mut https = url.clone();
+        https.set_scheme("https").ok()?;
+        // The default port of HTTP becomes the one of HTTPS, other ports
+        // are kept (RFC 6797, section 8.3)
+        if url.port() == Some(80) {
+            https.set_port(None).ok()?;
+        }
+        Some(https)
+    }
+}
+
+/// Parse the `max-age` (in seconds) and `includeSubDomains` directives of a
+/// `Strict-Transport-Security` header. Policies without a valid `max-age`
+/// are invalid.
+fn parse_policy(value: &str) -> Option<(u64, bool)> {
+    let mut max_age = None;
+    let mut include_subdomains = false;
+    for directive in value.split(';').map(str::trim) {
+        let (name, value) = directive.split_once('=').unwrap_or((directive, ""));
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("max-age") {
+            max_age = Some(value.trim().trim_matches('"').parse().ok()?);
+        } else if name.eq_ignore_ascii_case("includeSubDomains") {
+            include_subdomains = true;
+        }
+    }
+    Some((max_age?, include_subdomains))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn headers(policy: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_str(policy).unwrap(),
+        );
+        headers
+    }
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn test_upgrade_after_policy() {
+        let hsts = Hsts::new();
+        let link = url("http://example.com:80/page?q=1");
+        assert_eq!(hsts.upgrade(&link), None);
+
+        hsts.record(&url("https://example.com/"), &headers("max-age=31536000"));
+        assert_eq!(
+            hsts.upgrade(&link),
+            Some(url("https://example.com/page?q=1"))
+        );
+        // Other ports are kept
+        assert_eq!(
+            hsts.upgrade(&url("http://example.com:8080/")),
+            Some(url("https://example.com:8080/"))
+        );
+        // Subdomains only with `includeSubDomains`
+        assert_eq!(hsts.upgrade(&url("http://www.example.com/")), None);
+        assert_eq!(hsts.upgrade(&url("http://example.org/")), None);
+
+        hsts.record(&url("https://example.com/"), &headers("max-age=0"));
+        assert_eq!(hsts.upgrade(&link), None);
+    }
+
+    #[test]
+    fn test_include_subdomains() {
+        let hsts = Hsts::new();
+        hsts.record(
+            &url("https://example.com/"),
+            &headers(r#"max-age="600"; includeSubDomains; preload"#),
+        );
+        assert_eq!(
+            hsts.upgrade(&url("http://docs.www.example.com/")),
+            Some(url("https://docs.www.example.com/"))
+        );
+    }
+
+    #[test]
+    fn test_ignored_policies() {
+        let hsts = Hsts::new();
+        // Policies over HTTP don't count
+        hsts.record(&url("http://example.com/"), &headers("max-age=600"));
+        // Policies without a valid `max-age` are invalid
+        hsts.record(&url("https://example.org/"), &headers("includeSubDomains"));
+        hsts.record(&url("https://example.net/"), &headers("max-age=soon"));
+        // Policies of IP addresses don't count
+        hsts.record(&url("https://127.0.0.1/"), &headers("max-age=600"));
+        hsts.record(&url("https://[::1]/"), &headers("max-age=600"));
+        for link in [
+            "http://example.com/",
+            "http://example.org/",
+            "http://example.net/",
+            "http://127.0.0.1/",
+            "http://[::1]/",
+        ] {
+            assert_eq!(hsts.upgrade(&url(link)), None);
+        }
+    }
+}
@@ -1,4 +1,14 @@
+pub(crate) mod diff;
+pub(crate) mod fragment;
+pub(crate) mod gitignore;
+pub(crate) mod hsts;
 pub(crate) mod path;
 pub(crate) mod request;
 pub(crate) mod reqwest;
+pub(crate) mod sitemap;
+pub(crate) mod sniff;
+pub(crate) mod throttle;
 pub(crate) mod url;
+pub(crate) mod warmup;
+pub(crate) mod wayback;
+pub(crate) mod websocket;
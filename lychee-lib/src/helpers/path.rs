@@ -46,7 +46,7 @@ pub(crate) fn resolve(src: &Path, dst: &Path, base: &Option<Base>) -> Result<Opt
             // Find `dst` in the parent directory of `src`
             let parent = match src.parent() {
                 Some(parent) => parent,
-                None => return Err(ErrorKind::FileNotFound(relative.to_path_buf())),
+                None => return Err(ErrorKind::FileNotFound(relative.to_path_buf(), None)),
             };
             parent.join(relative)
         }
@@ -69,7 +69,7 @@ pub(crate) fn resolve(src: &Path, dst: &Path, base: &Option<Base>) -> Result<Opt
             };
             join(dir.to_path_buf(), absolute)
         }
-        _ => return Err(ErrorKind::FileNotFound(dst.to_path_buf())),
+        _ => return Err(ErrorKind::FileNotFound(dst.to_path_buf(), None)),
     };
     Ok(Some(absolute_path(resolved)))
 }
@@ -103,6 +103,46 @@ pub(crate) fn contains(parent: &PathBuf, child: &PathBuf) -> Result<bool> {
     Ok(child.starts_with(parent))
 }
 
+/// Find the existing file in the same directory as the missing file at
+/// `path`, which has the most similar name (e.g. after a typo or a rename).
+/// Names are compared case-insensitively by their edit distance, and only
+/// close matches are returned. Relative paths are resolved against the
+/// current working directory.
+pub(crate) fn similar_file(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+    let parent = path.parent()?;
+    let dir = if parent.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        parent
+    };
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .map(|sibling| (edit_distance(&name, &sibling.to_lowercase()), sibling))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, sibling)| parent.join(sibling))
+}
+
+/// Levenshtein distance between two strings, i.e. the number of characters
+/// which need to be inserted, deleted or substituted to turn one into the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
 #[cfg(test)]
 mod test_path {
     use super::*;
@@ -188,6 +228,33 @@ mod test_path {
         assert_eq!(contains(&parent.to_owned(), &child.to_owned()), Ok(true));
     }
 
+    #[test]
+    fn test_similar_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("readme.md"), "").unwrap();
+        fs::write(dir.path().join("guide.md"), "").unwrap();
+
+        assert_eq!(
+            similar_file(&dir.path().join("README.md")),
+            Some(dir.path().join("readme.md"))
+        );
+        assert_eq!(
+            similar_file(&dir.path().join("giude.md")),
+            Some(dir.path().join("guide.md"))
+        );
+        assert_eq!(similar_file(&dir.path().join("changelog.md")), None);
+        assert_eq!(similar_file(&dir.path().join("missing/readme.md")), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("guide.md", "guide.md"), 0);
+        assert_eq!(edit_distance("guide.md", "giude.md"), 2);
+        assert_eq!(edit_distance("guide.md", "guides.md"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
     #[test]
     fn test_contains_not() {
         let dir1 = tempfile::tempdir().unwrap();
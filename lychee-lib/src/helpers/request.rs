@@ -15,12 +15,15 @@ use crate::{
 const MAX_TRUNCATED_STR_LEN: usize = 100;
 
 /// Create requests out of the collected URLs.
-/// Only keeps "valid" URLs. This filters out anchors for example.
+/// Only keeps "valid" URLs. This filters out anchors for example, unless
+/// `include_anchors` is set: then they point into the document itself.
+/// The requests are in the order of the URLs, without duplicates.
 pub(crate) fn create(
     uris: Vec<RawUri>,
     input_content: &InputContent,
     base: &Option<Base>,
-) -> Result<HashSet<Request>> {
+    include_anchors: bool,
+) -> Result<Vec<Request>> {
     let base_url = Base::from_source(&input_content.source);
 
     let requests: Result<Vec<Option<Request>>> = uris
@@ -41,7 +44,15 @@ pub(crate) fn create(
                 c => c.clone(),
             };
 
-            if let Ok(uri) = Uri::try_from(raw_uri) {
+            let anchor = if include_anchors && is_anchor {
+                anchor_url(&input_content.source, &text)
+            } else {
+                None
+            };
+
+            if let Some(url) = anchor {
+                Ok(Some(Request::new(Uri { url }, source, element, attribute)))
+            } else if let Ok(uri) = Uri::try_from(raw_uri) {
                 Ok(Some(Request::new(uri, source, element, attribute)))
             } else if let Some(url) = base.as_ref().and_then(|u| u.join(&text)) {
                 Ok(Some(Request::new(Uri { url }, source, element, attribute)))
@@ -73,8 +84,24 @@ pub(crate) fn create(
             }
         })
         .collect();
-    let requests: Vec<Request> = requests?.into_iter().flatten().collect();
-    Ok(HashSet::from_iter(requests))
+    Ok(unique(requests?.into_iter().flatten()))
+}
+
+/// Remove duplicate requests, keeping the first one
+pub(crate) fn unique(requests: impl IntoIterator<Item = Request>) -> Vec<Request> {
+    let mut seen = HashSet::new();
+    requests
+        .into_iter()
+        .filter(|request| seen.insert(request.clone()))
+        .collect()
+}
+
+/// Resolve the fragment-only link `text` (e.g. `#top`) against the document
+/// it was found in
+fn anchor_url(source: &InputSource, text: &str) -> Option<Url> {
+    let mut url = source.document_url()?;
+    url.set_fragment(Some(text.strip_prefix('#')?));
+    Some(url)
 }
 
 fn construct_url(base: &Option<Url>, text: &str) -> Option<Result<Url>> {
@@ -109,6 +136,34 @@ fn create_uri_from_path(src: &Path, dst: &str, base: &Option<Base>) -> Result<Op
 mod tests {
     use super::*;
 
+    fn create_with_base(uris: &[&str], base: &str) -> HashSet<String> {
+        let input_content = InputContent::from_string("", crate::FileType::Html);
+        let base = Some(Base::try_from(base).unwrap());
+        let uris = uris.iter().map(|uri| RawUri::from(*uri)).collect();
+        create(uris, &input_content, &base, false)
+            .unwrap()
+            .into_iter()
+            .map(|request| request.uri.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_relative_links_with_base_url() {
+        let requests = create_with_base(
+            &["/about", "../x", "https://other.com/page"],
+            "https://example.com/docs/guide/",
+        );
+        let expected = [
+            "https://example.com/about",
+            "https://example.com/docs/x",
+            "https://other.com/page",
+        ]
+        .into_iter()
+        .map(ToString::to_string)
+        .collect();
+        assert_eq!(requests, expected);
+    }
+
     #[test]
     fn test_create_uri_from_path() {
         let result =
@@ -1,3 +1,7 @@
+use std::error::Error;
+
+use crate::ErrorKind;
+
 /// Extract the most relevant parts from a reqwest error
 ///
 /// The reqwest `Error` fields aren't public as they are an implementation
@@ -12,6 +16,55 @@ pub(crate) fn trim_error_output(e: &reqwest::Error) -> String {
     trim_inner(e.to_string())
 }
 
+/// Check whether a request failed because the host name could not be resolved
+///
+/// The underlying resolver error is not exposed by reqwest, so we look for
+/// hyper's DNS connect error in the error source chain instead.
+pub(crate) fn is_dns_error(e: &reqwest::Error) -> bool {
+    if !e.is_connect() {
+        return false;
+    }
+    let mut source = e.source();
+    while let Some(err) = source {
+        if err.to_string().starts_with("dns error") {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Read the body of a response, but stop as soon as at least `limit` bytes
+/// were received.
+///
+/// The remaining body is not downloaded; the connection gets dropped
+/// together with the response. Returns the bytes read so far.
+pub(crate) async fn read_body_limited(
+    response: &mut reqwest::Response,
+    limit: usize,
+) -> reqwest::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    while body.len() < limit {
+        match response.chunk().await? {
+            Some(chunk) => body.extend_from_slice(&chunk),
+            None => break,
+        }
+    }
+    Ok(body)
+}
+
+/// Convert an error while reading the body of a response
+///
+/// Compressed bodies (`gzip`, `br` and `deflate`) get decoded transparently
+/// while reading them, so a decoding error means that the body is corrupt.
+pub(crate) fn body_error(e: reqwest::Error) -> ErrorKind {
+    if e.is_decode() {
+        ErrorKind::InvalidContentEncoding(e)
+    } else {
+        ErrorKind::ReadResponseBody(e)
+    }
+}
+
 /// Get meaningful information from a reqwest error string.
 ///
 /// At the moment we only extract everything after "error trying to connect",
@@ -25,7 +78,32 @@ fn trim_inner(text: String) -> String {
 
 #[cfg(test)]
 mod tests {
+    use http::StatusCode;
+
     use super::*;
+    use crate::mock_server;
+
+    #[tokio::test]
+    async fn test_read_body_limited() {
+        const BODY_SIZE: usize = 10 * 1024 * 1024;
+        const LIMIT: usize = 1024;
+
+        let mock_server = mock_server!(StatusCode::OK, set_body_bytes(vec![0u8; BODY_SIZE]));
+        let mut response = reqwest::get(mock_server.uri()).await.unwrap();
+        let body = read_body_limited(&mut response, LIMIT).await.unwrap();
+
+        assert!(body.len() >= LIMIT);
+        assert!(body.len() < BODY_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_read_body_limited_small_body() {
+        let mock_server = mock_server!(StatusCode::OK, set_body_string("hello"));
+        let mut response = reqwest::get(mock_server.uri()).await.unwrap();
+        let body = read_body_limited(&mut response, 1024).await.unwrap();
+
+        assert_eq!(body, b"hello");
+    }
 
     #[test]
     fn test_extract_reqwest_error() {
@@ -0,0 +1,140 @@
+use std::io::{self, Read};
+
+use flate2::read::GzDecoder;
+
+use crate::extract::root_element;
+
+/// Magic bytes at the start of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The entries of an XML sitemap
+///
+/// See <https://www.sitemaps.org/protocol.html>
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Sitemap {
+    /// A `<urlset>` with the URLs of pages to check
+    Urls(Vec<String>),
+    /// A `<sitemapindex>` with the URLs of further sitemaps
+    Index(Vec<String>),
+}
+
+/// Parse an XML sitemap or sitemap index
+///
+/// Returns `None` if the content is not a sitemap, i.e. if its root element
+/// is neither `<urlset>` nor `<sitemapindex>`. Only the `<loc>` entries are
+/// extracted; all other metadata (like `<lastmod>`) is ignored.
+pub(crate) fn parse(content: &str) -> Option<Sitemap> {
+    match root_element(content)? {
+        "sitemapindex" => Some(Sitemap::Index(locations(content))),
+        "urlset" => Some(Sitemap::Urls(locations(content))),
+        _ => None,
+    }
+}
+
+/// Decode the raw bytes of a sitemap, decompressing it if it is gzipped
+pub(crate) fn decode(bytes: &[u8]) -> io::Result<String> {
+    let mut content = String::new();
+    if bytes.starts_with(&GZIP_MAGIC) {
+        GzDecoder::new(bytes).read_to_string(&mut content)?;
+    } else {
+        content = String::from_utf8(bytes.to_vec())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    }
+    Ok(content)
+}
+
+/// Collect the text of all `<loc>` elements
+fn locations(content: &str) -> Vec<String> {
+    let mut locations = vec![];
+    let mut rest = content;
+    while let Some(start) = rest.find("<loc>") {
+        rest = &rest[start + "<loc>".len()..];
+        let Some(end) = rest.find("</loc>") else {
+            break;
+        };
+        let location = rest[..end].trim();
+        let location = location
+            .strip_prefix("<![CDATA[")
+            .and_then(|l| l.strip_suffix("]]>"))
+            .map_or_else(|| unescape(location), ToString::to_string);
+        if !location.is_empty() {
+            locations.push(location);
+        }
+        rest = &rest[end..];
+    }
+    locations
+}
+
+/// Replace the predefined XML entities, which sitemaps are required to escape
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_urlset() {
+        let sitemap = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>https://example.com/</loc>
+    <lastmod>2022-01-01</lastmod>
+  </url>
+  <url><loc> https://example.com/search?q=a&amp;page=2 </loc></url>
+  <url><loc><![CDATA[https://example.com/cdata?a=1&b=2]]></loc></url>
+</urlset>"#;
+        assert_eq!(
+            parse(sitemap),
+            Some(Sitemap::Urls(vec![
+                "https://example.com/".to_string(),
+                "https://example.com/search?q=a&page=2".to_string(),
+                "https://example.com/cdata?a=1&b=2".to_string(),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_index() {
+        let sitemap = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>https://example.com/sitemap1.xml</loc></sitemap>
+  <sitemap><loc>https://example.com/sitemap2.xml.gz</loc></sitemap>
+</sitemapindex>"#;
+        assert_eq!(
+            parse(sitemap),
+            Some(Sitemap::Index(vec![
+                "https://example.com/sitemap1.xml".to_string(),
+                "https://example.com/sitemap2.xml.gz".to_string(),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_no_sitemap() {
+        assert_eq!(parse("<html><body>https://example.com</body></html>"), None);
+        // Text which only mentions sitemap elements
+        assert_eq!(
+            parse("Sitemaps start with <urlset> and list <loc>https://example.com</loc>"),
+            None
+        );
+        assert_eq!(parse("<html><pre><urlset></urlset></pre></html>"), None);
+    }
+
+    #[test]
+    fn test_decode_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"<urlset></urlset>").unwrap();
+        let bytes = encoder.finish().unwrap();
+        assert_eq!(decode(&bytes).unwrap(), "<urlset></urlset>");
+        assert_eq!(decode(b"plain").unwrap(), "plain");
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+
+/// Number of bytes needed to detect all known formats
+pub(crate) const SNIFF_LEN: usize = 16;
+
+/// Magic numbers at the start of files, with the corresponding MIME type
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"\x00\x00\x01\x00", "image/x-icon"),
+    (b"BM", "image/bmp"),
+    (b"%PDF-", "application/pdf"),
+];
+
+/// Get the MIME type of a binary asset (like an image) from the extension of
+/// the last path segment of `path`.
+///
+/// Returns `None` for all other files, e.g. text-based formats like SVG,
+/// whose content can't be reliably detected from the first few bytes.
+pub(crate) fn expected_type(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+    let mime = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "bmp" => "image/bmp",
+        "pdf" => "application/pdf",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Detect the MIME type of content from its first bytes.
+///
+/// Besides the known binary formats, HTML is detected, since servers commonly
+/// respond with an HTML error page instead of the requested asset.
+pub(crate) fn sniff(bytes: &[u8]) -> Option<&'static str> {
+    if let Some((_, mime)) = SIGNATURES
+        .iter()
+        .find(|(signature, _)| bytes.starts_with(signature))
+    {
+        return Some(mime);
+    }
+    if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_start().to_lowercase();
+    if text.starts_with("<!doctype html") || text.starts_with("<html") {
+        return Some("text/html");
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_type() {
+        assert_eq!(expected_type("/img/logo.png"), Some("image/png"));
+        assert_eq!(expected_type("/photo.JPG"), Some("image/jpeg"));
+        assert_eq!(expected_type("/docs/manual.pdf"), Some("application/pdf"));
+        assert_eq!(expected_type("/logo.svg"), None);
+        assert_eq!(expected_type("/index.html"), None);
+        assert_eq!(expected_type("/"), None);
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\x00\x00"), Some("image/png"));
+        assert_eq!(sniff(b"GIF89a\x01\x00"), Some("image/gif"));
+        assert_eq!(sniff(b"RIFF\x24\x00\x00\x00WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff(b"  <!DOCTYPE html><html>"), Some("text/html"));
+        assert_eq!(sniff(b"<html><head>"), Some("text/html"));
+        assert_eq!(sniff(b"plain text"), None);
+        assert_eq!(sniff(b""), None);
+    }
+}
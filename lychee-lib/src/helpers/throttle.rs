@@ -0,0 +1,105 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use regex::Regex;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Enforces a minimum delay between requests to the same host
+///
+/// Requests to different hosts are not throttled.
+#[derive(Debug)]
+pub(crate) struct HostThrottle {
+    delay: Duration,
+    /// Earliest point in time at which the next request to a host may start
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostThrottle {
+    pub(crate) fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait until a request to `host` is allowed
+    pub(crate) async fn wait(&self, host: &str) {
+        let slot = {
+            let now = Instant::now();
+            let mut next_slot = self.next_slot.lock().expect("throttle lock poisoned");
+            let slot = match next_slot.get(host) {
+                Some(next) if *next > now => *next,
+                _ => now,
+            };
+            // Reserve the slot before waiting, so that concurrent requests
+            // to the same host queue up behind each other
+            next_slot.insert(host.to_string(), slot + self.delay);
+            slot
+        };
+        tokio::time::sleep_until(slot.into()).await;
+    }
+}
+
+/// Limits the number of concurrent requests to hosts matching a pattern
+///
+/// The first matching pattern applies, and its limit is shared by all
+/// matching hosts. Requests to other hosts are not limited.
+#[derive(Debug)]
+pub(crate) struct HostConcurrency {
+    limits: Vec<(Regex, Arc<Semaphore>)>,
+}
+
+impl HostConcurrency {
+    pub(crate) fn new(limits: Vec<(Regex, usize)>) -> Self {
+        let limits = limits
+            .into_iter()
+            // A limit of zero would block all requests forever
+            .map(|(pattern, limit)| (pattern, Arc::new(Semaphore::new(limit.max(1)))))
+            .collect();
+        Self { limits }
+    }
+
+    /// Wait until a request to `host` is allowed. The returned permit must
+    /// be held until the request is done.
+    pub(crate) async fn acquire(&self, host: &str) -> Option<OwnedSemaphorePermit> {
+        let (_, semaphore) = self
+            .limits
+            .iter()
+            .find(|(pattern, _)| pattern.is_match(host))?;
+        semaphore.clone().acquire_owned().await.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_host_concurrency() {
+        let concurrency = HostConcurrency::new(vec![
+            (Regex::new(r"^example\.com$").unwrap(), 2),
+            (Regex::new(r"\.org$").unwrap(), 0),
+        ]);
+
+        let first = concurrency.acquire("example.com").await;
+        let second = concurrency.acquire("example.com").await;
+        assert!(first.is_some() && second.is_some());
+        // The limit is reached
+        assert!(tokio::time::timeout(
+            Duration::from_millis(50),
+            concurrency.acquire("example.com")
+        )
+        .await
+        .is_err());
+        drop(first);
+        assert!(concurrency.acquire("example.com").await.is_some());
+
+        // A limit of zero allows one request at a time
+        assert!(concurrency.acquire("example.org").await.is_some());
+        // Other hosts are not limited
+        assert!(concurrency.acquire("example.net").await.is_none());
+    }
+}
@@ -1,6 +1,9 @@
+use std::{borrow::Cow, collections::HashMap};
+
 use linkify::LinkFinder;
 
 use once_cell::sync::Lazy;
+use reqwest::Url;
 
 static LINK_FINDER: Lazy<LinkFinder> = Lazy::new(LinkFinder::new);
 
@@ -18,6 +21,61 @@ pub(crate) fn remove_get_params_and_fragment(url: &str) -> &str {
     path
 }
 
+/// Remove the given query parameters from a URL.
+/// A parameter ending in `*` matches all parameters with that prefix
+/// (e.g. `utm_*`). The query gets removed entirely if no parameters are left.
+pub(crate) fn strip_query_params(url: &mut Url, params: &[String]) {
+    if url.query().is_none() {
+        return;
+    }
+    let is_stripped = |key: &str| {
+        params.iter().any(|param| match param.strip_suffix('*') {
+            Some(prefix) => key.starts_with(prefix),
+            None => key == param,
+        })
+    };
+    let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    let kept: Vec<&(String, String)> = pairs.iter().filter(|(key, _)| !is_stripped(key)).collect();
+    // Leave the query untouched (including its encoding) if nothing matched
+    if kept.len() == pairs.len() {
+        return;
+    }
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+}
+
+/// Substitute `${NAME}` placeholders in a link with the values of
+/// `variables`. Placeholders without a closing brace are kept as they are.
+///
+/// # Errors
+///
+/// Returns the name of the first variable which has no value
+pub(crate) fn substitute_variables<'a>(
+    link: &'a str,
+    variables: &HashMap<String, String>,
+) -> Result<Cow<'a, str>, String> {
+    if !link.contains("${") {
+        return Ok(Cow::Borrowed(link));
+    }
+    let mut out = String::with_capacity(link.len());
+    let mut rest = link;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + len];
+        let value = variables.get(name).ok_or_else(|| name.to_string())?;
+        out.push_str(&rest[..start]);
+        out.push_str(value);
+        rest = &rest[start + 2 + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(Cow::Owned(out))
+}
+
 // Use `LinkFinder` to offload the raw link searching in plaintext
 pub(crate) fn find_links(input: &str) -> impl Iterator<Item = linkify::Link> {
     LINK_FINDER.links(input)
@@ -27,6 +85,60 @@ pub(crate) fn find_links(input: &str) -> impl Iterator<Item = linkify::Link> {
 mod test_fs_tree {
     use super::*;
 
+    fn strip(url: &str, params: &[&str]) -> String {
+        let mut url = Url::parse(url).unwrap();
+        let params: Vec<String> = params.iter().map(ToString::to_string).collect();
+        strip_query_params(&mut url, &params);
+        url.to_string()
+    }
+
+    #[test]
+    fn test_strip_query_params() {
+        let params = ["utm_*", "fbclid"];
+        assert_eq!(
+            strip("https://example.com/?utm_source=x&utm_medium=y", &params),
+            "https://example.com/"
+        );
+        assert_eq!(
+            strip("https://example.com/?page=2&fbclid=abc#top", &params),
+            "https://example.com/?page=2#top"
+        );
+        assert_eq!(
+            strip("https://example.com/?fbclid_keep=1", &params),
+            "https://example.com/?fbclid_keep=1"
+        );
+        assert_eq!(
+            strip("https://example.com/", &params),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let variables = HashMap::from([
+            ("HOST".to_string(), "example.com".to_string()),
+            ("VERSION".to_string(), "v2".to_string()),
+        ]);
+        let substitute = |link| substitute_variables(link, &variables);
+
+        assert_eq!(
+            substitute("https://${HOST}/docs/${VERSION}/").unwrap(),
+            "https://example.com/docs/v2/"
+        );
+        assert!(matches!(
+            substitute("https://example.com/?$filter=x").unwrap(),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(
+            substitute("https://example.com/${HOST").unwrap(),
+            "https://example.com/${HOST"
+        );
+        assert_eq!(
+            substitute("https://${HOST}/${PORT}/"),
+            Err("PORT".to_string())
+        );
+    }
+
     #[test]
     fn test_remove_get_params_and_fragment() {
         assert_eq!(remove_get_params_and_fragment("/"), "/");
@@ -0,0 +1,116 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use reqwest::Url;
+use tokio::sync::OnceCell;
+
+use super::reqwest::{is_dns_error, trim_error_output};
+use crate::ErrorKind;
+
+/// Why the warmup request to a host failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Failure {
+    /// The host name could not be resolved
+    Dns,
+    /// No connection to the host could be established
+    Connect(String),
+}
+
+/// Sends a single warmup request to each host (more precisely: each origin)
+/// before its links get checked, to detect hosts which can't be reached at
+/// all. Links to such hosts fail right away, without sending a request for
+/// each of them.
+///
+/// Only DNS and connection errors count as a failed warmup. Any response,
+/// even an error status, means that the host is reachable. Timeouts don't
+/// count either, as the host might just be slow.
+#[derive(Debug, Default)]
+pub(crate) struct HostWarmup {
+    origins: Mutex<HashMap<String, Arc<OnceCell<Option<Failure>>>>>,
+}
+
+impl HostWarmup {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the error for links to the host of `url`, if its warmup request
+    /// failed. The first link to a host sends the warmup request, and
+    /// concurrent links to the same host wait for it.
+    pub(crate) async fn check(&self, client: &reqwest::Client, url: &Url) -> Option<ErrorKind> {
+        let host = url.host_str()?;
+        let cell = self
+            .origins
+            .lock()
+            .expect("warmup lock poisoned")
+            .entry(url.origin().ascii_serialization())
+            .or_default()
+            .clone();
+        let failure = cell.get_or_init(|| warmup(client, url)).await;
+        match failure.as_ref()? {
+            Failure::Dns => Some(ErrorKind::Dns {
+                host: host.to_string(),
+            }),
+            Failure::Connect(reason) => Some(ErrorKind::HostUnreachable {
+                host: host.to_string(),
+                reason: reason.clone(),
+            }),
+        }
+    }
+}
+
+/// Send a `HEAD` request to the root of the host of `url`
+async fn warmup(client: &reqwest::Client, url: &Url) -> Option<Failure> {
+    let mut root = url.clone();
+    root.set_path("/");
+    root.set_query(None);
+    root.set_fragment(None);
+    match client.head(root).send().await {
+        Err(e) if is_dns_error(&e) => Some(Failure::Dns),
+        Err(e) if e.is_connect() => Some(Failure::Connect(trim_error_output(&e))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reachable_host() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let warmup = HostWarmup::new();
+        let client = reqwest::Client::new();
+
+        for path in ["/a", "/b?q=1", "/c#top"] {
+            let url = Url::parse(&mock_server.uri()).unwrap().join(path).unwrap();
+            assert_eq!(warmup.check(&client, &url).await, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_host() {
+        // Nothing listens on the port anymore, so connections get refused
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let warmup = HostWarmup::new();
+        let client = reqwest::Client::new();
+        let url = Url::parse(&format!("http://127.0.0.1:{port}/page")).unwrap();
+        assert!(matches!(
+            warmup.check(&client, &url).await,
+            Some(ErrorKind::HostUnreachable { host, .. }) if host == "127.0.0.1"
+        ));
+    }
+}
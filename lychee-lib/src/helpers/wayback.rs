@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use log::debug;
+use reqwest::Url;
+use serde::Deserialize;
+
+use super::throttle::HostThrottle;
+use crate::Uri;
+
+/// Minimum delay between two lookups, as the Internet Archive rate limits its
+/// APIs
+const LOOKUP_DELAY: Duration = Duration::from_secs(1);
+
+/// Looks up snapshots of dead links in the Wayback Machine
+///
+/// Lookups use a client of their own, so that the custom headers of link
+/// checks (e.g. credentials) are not sent to the Internet Archive.
+#[derive(Debug)]
+pub(crate) struct Wayback {
+    client: reqwest::Client,
+    /// Availability API, e.g. [`crate::DEFAULT_WAYBACK_API`]
+    api: Url,
+    throttle: HostThrottle,
+}
+
+impl Wayback {
+    pub(crate) fn new(client: reqwest::Client, api: Url) -> Self {
+        Self {
+            client,
+            api,
+            throttle: HostThrottle::new(LOOKUP_DELAY),
+        }
+    }
+
+    /// Look up the closest snapshot of `uri`, see [`snapshot`]
+    pub(crate) async fn snapshot(&self, uri: &Uri) -> Option<Uri> {
+        if let Some(host) = self.api.host_str() {
+            self.throttle.wait(host).await;
+        }
+        snapshot(&self.client, &self.api, uri).await
+    }
+}
+
+/// Response of the availability API of the Wayback Machine, see
+/// <https://archive.org/help/wayback_api.php>
+#[derive(Debug, Deserialize)]
+struct Availability {
+    #[serde(default)]
+    archived_snapshots: Snapshots,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Snapshots {
+    closest: Option<Snapshot>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Snapshot {
+    #[serde(default)]
+    available: bool,
+    url: String,
+    /// Status code of the archived response, e.g. `"200"`
+    status: Option<String>,
+}
+
+impl Availability {
+    /// URL of the closest snapshot, if it is available and was archived
+    /// from a successful response
+    fn snapshot(self) -> Option<Uri> {
+        let snapshot = self.archived_snapshots.closest?;
+        let archived_ok = snapshot
+            .status
+            .as_deref()
+            .is_none_or(|status| status.starts_with('2'));
+        if !snapshot.available || !archived_ok {
+            return None;
+        }
+        Url::parse(&snapshot.url).ok().map(Uri::from)
+    }
+}
+
+/// Look up the closest snapshot of `uri` in the Wayback Machine via its
+/// availability `api` (e.g. [`crate::DEFAULT_WAYBACK_API`]).
+///
+/// Failed lookups are treated like missing snapshots, as they only add a
+/// suggestion to a link which is dead anyway.
+async fn snapshot(client: &reqwest::Client, api: &Url, uri: &Uri) -> Option<Uri> {
+    match availability(client, api, uri).await {
+        Ok(availability) => availability.snapshot(),
+        Err(e) => {
+            debug!("Cannot look up {uri} in the Wayback Machine: {e}");
+            None
+        }
+    }
+}
+
+async fn availability(
+    client: &reqwest::Client,
+    api: &Url,
+    uri: &Uri,
+) -> Result<Availability, Box<dyn std::error::Error + Send + Sync>> {
+    let response = client
+        .get(api.clone())
+        .query(&[("url", uri.as_str())])
+        .send()
+        .await?
+        .error_for_status()?;
+    // Boxed, as the response body decoders make the future too large to keep
+    // it on the stack
+    let body = Box::pin(response.text()).await?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(json: &str) -> Option<String> {
+        serde_json::from_str::<Availability>(json)
+            .unwrap()
+            .snapshot()
+            .map(|uri| uri.to_string())
+    }
+
+    #[test]
+    fn test_snapshot() {
+        assert_eq!(
+            snapshot(
+                r#"{"archived_snapshots": {"closest": {"available": true,
+                "url": "http://web.archive.org/web/20200101000000/https://example.com/",
+                "timestamp": "20200101000000", "status": "200"}}}"#
+            ),
+            Some("http://web.archive.org/web/20200101000000/https://example.com/".to_string())
+        );
+    }
+
+    #[test]
+    fn test_no_snapshot() {
+        assert_eq!(snapshot(r#"{"archived_snapshots": {}}"#), None);
+        assert_eq!(snapshot(r#"{"url": "example.com"}"#), None);
+        assert_eq!(
+            snapshot(
+                r#"{"archived_snapshots": {"closest": {"available": true,
+                "url": "http://web.archive.org/web/2020/https://example.com/",
+                "status": "404"}}}"#
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use ring::{
+    digest::{digest, SHA1_FOR_LEGACY_USE_ONLY},
+    rand::{SecureRandom, SystemRandom},
+};
+
+/// Magic string which gets appended to the handshake key as defined in
+/// [RFC 6455, section 1.3](https://datatracker.ietf.org/doc/html/rfc6455#section-1.3)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Generate a random `Sec-WebSocket-Key` for the opening handshake.
+/// The key is a base64-encoded 16-byte nonce.
+pub(crate) fn generate_key() -> String {
+    let mut nonce = [0u8; 16];
+    // Falling back to a zeroed nonce is fine here, as we only verify the
+    // handshake and never exchange any data over the connection.
+    let _ = SystemRandom::new().fill(&mut nonce);
+    base64::encode(nonce)
+}
+
+/// Compute the `Sec-WebSocket-Accept` value a server is expected to return
+/// for the given key
+pub(crate) fn accept_key(key: &str) -> String {
+    let hash = digest(
+        &SHA1_FOR_LEGACY_USE_ONLY,
+        format!("{key}{WEBSOCKET_GUID}").as_bytes(),
+    );
+    base64::encode(hash.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_key() {
+        // Example taken from RFC 6455
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn test_generate_key() {
+        let key = generate_key();
+        assert_eq!(base64::decode(&key).unwrap().len(), 16);
+        assert_ne!(key, generate_key());
+    }
+}
@@ -82,13 +82,17 @@ use ring as _; // required for apple silicon
 pub use crate::{
     // Constants get exposed so that the CLI can use the same defaults as the library
     client::{
-        check, Client, ClientBuilder, DEFAULT_MAX_REDIRECTS, DEFAULT_MAX_RETRIES,
+        check, Client, ClientBuilder, StatusHook, DEFAULT_GITHUB_CONCURRENCY,
+        DEFAULT_MAX_CONCURRENCY, DEFAULT_MAX_REDIRECTS, DEFAULT_MAX_RETRIES,
         DEFAULT_RETRY_WAIT_TIME_SECS, DEFAULT_TIMEOUT_SECS, DEFAULT_USER_AGENT,
+        DEFAULT_WAYBACK_API,
     },
     collector::Collector,
     filter::{Excludes, Filter, Includes},
     types::{
-        uri::valid::Uri, Base, CacheStatus, ErrorKind, FileType, Input, InputContent, InputSource,
-        Request, Response, ResponseBody, Result, Status,
+        uri::valid::Uri, Base, CacheStatus, ErrorKind, ExclusionSource, FileType, Input,
+        InputContent, InputSource, Progress, ProgressCallback, ProgressCounter, ReportedError,
+        Request, RequestGroup, Response, ResponseBody, Result, RetryJitter, Status, StatusFilter,
+        Timings, TrailingSlash,
     },
 };
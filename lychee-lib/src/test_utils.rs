@@ -1,5 +1,24 @@
-use std::{convert::TryFrom, fs, path::Path};
+use std::{
+    convert::TryFrom,
+    fs,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
 
+use openssl::{
+    asn1::Asn1Time,
+    bn::BigNum,
+    ec::{EcGroup, EcKey},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::PKey,
+    ssl::{select_next_proto, AlpnError, ErrorCode, SslAcceptor, SslMethod, SslStream},
+    x509::{X509Builder, X509NameBuilder},
+};
 use reqwest::Url;
 
 use crate::{ClientBuilder, ErrorKind, Request, Uri};
@@ -57,3 +76,99 @@ pub(crate) fn load_fixture(filename: &str) -> String {
         .join(filename);
     fs::read_to_string(fixture_path).unwrap()
 }
+
+/// Serve `mock_server` over HTTPS with a self-signed certificate, to test
+/// behavior which only applies to HTTPS, like HSTS or HTTP/2, which gets
+/// negotiated via ALPN.
+///
+/// Returns the base URL of the HTTPS endpoint. Clients need
+/// `allow_insecure` to accept the certificate.
+pub(crate) fn https_proxy(mock_server: &wiremock::MockServer) -> String {
+    let acceptor = tls_acceptor();
+    let target = *mock_server.address();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let acceptor = acceptor.clone();
+            thread::spawn(move || {
+                if let (Ok(tls), Ok(upstream)) =
+                    (acceptor.accept(stream), TcpStream::connect(target))
+                {
+                    pipe(tls, upstream);
+                }
+            });
+        }
+    });
+    format!("https://127.0.0.1:{port}")
+}
+
+/// TLS acceptor with a self-signed certificate for `localhost`, which
+/// prefers HTTP/2
+fn tls_acceptor() -> Arc<SslAcceptor> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_text("CN", "localhost").unwrap();
+    let name = name.build();
+
+    let mut cert = X509Builder::new().unwrap();
+    cert.set_version(2).unwrap();
+    let serial = BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap();
+    cert.set_serial_number(&serial).unwrap();
+    cert.set_subject_name(&name).unwrap();
+    cert.set_issuer_name(&name).unwrap();
+    cert.set_pubkey(&key).unwrap();
+    cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    cert.set_not_after(&Asn1Time::days_from_now(1).unwrap())
+        .unwrap();
+    cert.sign(&key, MessageDigest::sha256()).unwrap();
+
+    let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+    acceptor.set_private_key(&key).unwrap();
+    acceptor.set_certificate(&cert.build()).unwrap();
+    acceptor.set_alpn_select_callback(|_, client| {
+        select_next_proto(b"\x02h2\x08http/1.1", client).ok_or(AlpnError::NOACK)
+    });
+    Arc::new(acceptor.build())
+}
+
+/// Forward data between a TLS connection and the mock server until one of
+/// them closes the connection
+///
+/// `SslStream` can't be split into a reading and a writing half, so both
+/// directions get polled in turns.
+fn pipe(mut tls: SslStream<TcpStream>, mut upstream: TcpStream) {
+    let poll = Some(Duration::from_millis(1));
+    if tls.get_ref().set_read_timeout(poll).is_err() || upstream.set_read_timeout(poll).is_err() {
+        return;
+    }
+    let mut buf = [0; 16 * 1024];
+    loop {
+        match tls.ssl_read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => {
+                if upstream.write_all(&buf[..n]).is_err() {
+                    return;
+                }
+            }
+            Err(e) if e.code() == ErrorCode::WANT_READ => {}
+            Err(_) => return,
+        }
+        match upstream.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => {
+                if tls.write_all(&buf[..n]).is_err() {
+                    return;
+                }
+            }
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(_) => return,
+        }
+    }
+}
@@ -69,10 +69,15 @@ impl From<&Status> for CacheStatus {
             // TODO: Use accepted status codes to decide whether this is a
             // success or failure
             Status::Ok(code) | Status::UnknownStatusCode(code) => Self::Ok(code.as_u16()),
-            Status::Excluded => Self::Excluded,
+            // Skipped links were never checked, so there is no result to
+            // cache. They won't get inserted into the cache anyway.
+            Status::Excluded | Status::Skipped(_) => Self::Excluded,
             Status::Unsupported(_) => Self::Unsupported,
             Status::Redirected(code) => Self::Error(Some(code.as_u16())),
             Status::Timeout(code) => Self::Error(code.map(|code| code.as_u16())),
+            // Warnings can't be represented in the cache. Treat them like
+            // errors, so that they don't get lost as cached successes
+            Status::Warning(_) => Self::Error(None),
             Status::Error(e) => match e {
                 ErrorKind::NetworkRequest(e)
                 | ErrorKind::ReadResponseBody(e)
@@ -1,11 +1,17 @@
-use serde::{Serialize, Serializer};
+use http::StatusCode;
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
 use std::error::Error;
+use std::fmt::Display;
 use std::hash::Hash;
-use std::{convert::Infallible, path::PathBuf};
+use std::time::Duration;
+use std::{
+    convert::Infallible,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 use tokio::task::JoinError;
 
-use super::InputContent;
+use super::{InputContent, InputSource};
 use crate::{helpers, Uri};
 
 /// Kinds of status errors
@@ -31,6 +37,13 @@ pub enum ErrorKind {
     /// Cannot read the body of the received response
     #[error("Error reading response body")]
     ReadResponseBody(#[source] reqwest::Error),
+    /// The body of the received response can't be decompressed, because it
+    /// doesn't match its `Content-Encoding` (`gzip`, `br` or `deflate`)
+    #[error("Cannot decode response body, its content encoding is corrupt")]
+    InvalidContentEncoding(#[source] reqwest::Error),
+    /// The URI has a scheme which lychee has no way to check, e.g. `gopher`
+    #[error("URI scheme `{0}` is not supported")]
+    UnsupportedScheme(String),
     /// The network client required for making requests cannot be created
     #[error("Error creating request client")]
     BuildRequestClient(#[source] reqwest::Error),
@@ -39,22 +52,57 @@ pub enum ErrorKind {
     BuildGithubClient(#[source] octocrab::Error),
     /// Network error while using Github API
     #[error("Network error (GitHub client)")]
-    GithubRequest(#[from] octocrab::Error),
+    GithubRequest(#[source] octocrab::Error),
+    /// A GitHub repository does not exist (e.g. because it got deleted) or
+    /// is not accessible with the given token
+    #[error("GitHub repository not found")]
+    GithubNotFound,
+    /// The GitHub API rejected the token, e.g. because it is invalid or
+    /// expired
+    #[error("GitHub token is invalid (bad credentials)")]
+    GithubUnauthorized,
+    /// The GitHub API rate limit for the token (or for unauthenticated
+    /// requests) is exceeded
+    #[error("GitHub API rate limit exceeded")]
+    GithubRateLimited,
     /// Invalid Github URL
     #[error("Github URL is invalid: {0}")]
     InvalidGithubUrl(String),
+    /// The host name of the URL could not be resolved
+    #[error("Cannot resolve host `{host}`")]
+    Dns {
+        /// The host which could not be resolved
+        host: String,
+    },
+    /// The warmup request to a host failed with a connection error, so its
+    /// links were not checked
+    #[error("Cannot connect to host `{host}`: {reason}")]
+    HostUnreachable {
+        /// The host which could not be reached
+        host: String,
+        /// Why the connection failed
+        reason: String,
+    },
     /// The given string can not be parsed into a valid URL, e-mail address, or file path
     #[error("Cannot parse string `{1}` as website url: {0}")]
     ParseUrl(#[source] url::ParseError, String),
-    /// The given URI cannot be converted to a file path
+    /// The given URI cannot be converted to a file path, along with an
+    /// existing file with a similar name, see [`ErrorKind::suggestion`]
     #[error("Cannot find file")]
-    InvalidFilePath(Uri),
+    InvalidFilePath(Uri, Option<PathBuf>),
+    /// Error while reading the resource behind a URI (e.g. a `file://` URI)
+    #[error("Cannot read `{1}`: {0}")]
+    ReadUri(#[source] std::io::Error, Uri),
     /// The given path cannot be converted to a URI
     #[error("Invalid path to URL conversion: {0}")]
     InvalidUrlFromPath(PathBuf),
     /// The given mail address is unreachable
     #[error("Unreachable mail address: {0}: {1}")]
     UnreachableEmailAddress(Uri, String),
+    /// The given mail address belongs to a disposable (temporary) mail
+    /// provider
+    #[error("Mail address `{0}` uses the disposable mail domain `{1}`")]
+    DisposableMail(Uri, String),
     /// The given header could not be parsed.
     /// A possible error when converting a `HeaderValue` from a string or byte
     /// slice.
@@ -66,9 +114,10 @@ pub enum ErrorKind {
     /// The given input can not be parsed into a valid URI remapping
     #[error("Error handling URI remap expression. Cannot parse into URI remapping: `{0}`")]
     InvalidUriRemap(String),
-    /// The given path does not resolve to a valid file
+    /// The given path does not resolve to a valid file, along with an
+    /// existing file with a similar name, see [`ErrorKind::suggestion`]
     #[error("Cannot find local file {0}")]
-    FileNotFound(PathBuf),
+    FileNotFound(PathBuf, Option<PathBuf>),
     /// Error while traversing an input directory
     #[error("Cannot traverse input directory")]
     DirTraversal(#[from] jwalk::Error),
@@ -76,11 +125,85 @@ pub enum ErrorKind {
     #[error("UNIX glob pattern is invalid")]
     InvalidGlobPattern(#[from] glob::PatternError),
     /// The Github API could not be called because of a missing Github token.
-    #[error("GitHub token not specified. To check GitHub links reliably, use `--github-token` flag / `GITHUB_TOKEN` env var.")]
+    #[error("GitHub token not specified. To check GitHub links reliably, use `--github-token` flag / `GITHUB_TOKEN` or `GH_TOKEN` env var.")]
     MissingGitHubToken,
     /// Used an insecure URI where a secure variant was reachable
     #[error("This URI is available in HTTPS protocol, but HTTP is provided, use '{0}' instead")]
     InsecureURL(Uri),
+    /// An insecure URI was checked via HTTPS instead, as its host sent an
+    /// HTTP Strict Transport Security (HSTS) policy before
+    #[error("The host only allows HTTPS (HSTS), use '{0}' instead")]
+    HstsUpgrade(Uri),
+    /// A request got redirected to a different host, e.g. a domain parking page
+    #[error("Redirected to a different host: {0}")]
+    CrossHostRedirect(Uri),
+    /// A request got redirected to a different URL, which should be linked
+    /// directly instead
+    #[error("Redirected to {0}")]
+    Redirect(Uri),
+    /// A request got redirected to a URL which only differs by a trailing
+    /// slash, which is the canonical form of the link
+    #[error("Redirected to {0}, which only differs by a trailing slash")]
+    TrailingSlashRedirect(Uri),
+    /// A link is dead (e.g. `404 Not Found`), but the Wayback Machine of the
+    /// Internet Archive has a snapshot of it
+    #[error("Dead link ({0}), but the Wayback Machine has a snapshot: {1}")]
+    Archived(StatusCode, Uri),
+    /// A link has an empty target, e.g. `href=""` or `href="#"`
+    #[error("Empty URL found in `{0}`")]
+    EmptyUrl(InputSource),
+    /// An RSS or Atom feed or an OPML outline is malformed
+    #[error("Invalid feed `{0}`: {1}")]
+    InvalidFeed(InputSource, String),
+    /// A link contains a `${NAME}` placeholder of an unknown variable, see
+    /// [`Collector::variables`](crate::Collector::variables)
+    #[error("Unresolved variable `${{{0}}}` in link `{1}` of `{2}`")]
+    UnresolvedVariable(String, String, InputSource),
+    /// A link does not end on HTTPS after following all redirects
+    #[error("Final URL `{0}` is not using HTTPS")]
+    InsecureFinalUrl(Uri),
+    /// A website does not allow cross-origin requests (CORS) from the
+    /// configured origin
+    #[error("Cross-origin requests are not allowed by `{0}` (missing or mismatching `Access-Control-Allow-Origin` header)")]
+    MissingCorsHeader(Uri),
+    /// The content of a link doesn't match the type expected from its
+    /// extension, e.g. a `.png` link serving an HTML error page
+    #[error("Content is of type `{actual}`, expected `{expected}`")]
+    ContentTypeMismatch {
+        /// The expected MIME type
+        expected: String,
+        /// The detected MIME type
+        actual: String,
+    },
+    /// A response body is shorter or longer than its `Content-Length` header
+    /// announced, e.g. because the download got cut off
+    #[error("Received {actual} bytes, but the Content-Length is {expected} bytes")]
+    ContentLengthMismatch {
+        /// The announced length in bytes
+        expected: u64,
+        /// The number of bytes received
+        actual: u64,
+    },
+    /// A link responded with a different status code than the one expected
+    /// for it
+    #[error("Expected status code {expected}, but got {actual}")]
+    UnexpectedStatusCode {
+        /// The expected status code
+        expected: StatusCode,
+        /// The actual status code
+        actual: StatusCode,
+    },
+    /// An insecure subresource is embedded into a secure page
+    #[error("Insecure resource `{0}` is loaded on a secure page (mixed content)")]
+    MixedContent(Uri),
+    /// A link into the document it was found in references a fragment
+    /// which doesn't match any anchor of the document
+    #[error("The fragment of `{0}` doesn't match any anchor in the document")]
+    MissingFragment(Uri),
+    /// The fragment of a link into a local HTML file matches more than one
+    /// anchor, so it is ambiguous which one is referenced
+    #[error("The fragment of `{0}` matches {1} anchors in the document")]
+    AmbiguousFragment(Uri, usize),
     /// Error while sending/receiving messages from MPSC channel
     #[error("Cannot send/receive message from channel")]
     Channel(#[from] tokio::sync::mpsc::error::SendError<InputContent>),
@@ -93,8 +216,121 @@ pub enum ErrorKind {
     /// Regex error
     #[error("Error when using regex engine: {0}")]
     Regex(#[from] regex::Error),
+    /// A `slack://` deep link is malformed
+    #[error("Invalid Slack deep link `{0}`: {1}")]
+    InvalidSlackUri(Uri, String),
+    /// The WebSocket opening handshake was rejected or invalid
+    #[error("WebSocket handshake failed: {0}")]
+    WebSocket(String),
+    /// The link was not checked, because the run exceeded its time budget
+    #[error("Not checked, time budget of {0:?} exceeded")]
+    TimeBudgetExceeded(Duration),
+    /// The link was not checked, because the run reached its maximum number
+    /// of checks
+    #[error("Not checked, limit of {0} checks reached")]
+    CheckLimitReached(usize),
+    /// An error read back from a serialized response, e.g. from the report
+    /// of an earlier run
+    #[error("{0}")]
+    Reported(ReportedError),
 }
 
+/// An error of a serialized response, e.g. from the JSON report of an earlier
+/// run. The original error can't be restored, so only its kind and message
+/// are known.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReportedError {
+    kind: Option<&'static str>,
+    message: String,
+}
+
+impl ReportedError {
+    /// Create a reported error from the serialized kind and message. Unknown
+    /// kinds (e.g. of newer releases) are dropped.
+    pub(crate) fn new(kind: Option<&str>, message: String) -> Self {
+        let kind = kind.and_then(|kind| KINDS.iter().copied().find(|k| *k == kind));
+        Self { kind, message }
+    }
+
+    /// The kind of the original error (see [`ErrorKind::kind`]), if known.
+    /// Reports of older releases don't contain it.
+    #[must_use]
+    pub const fn kind(&self) -> Option<&'static str> {
+        self.kind
+    }
+
+    /// The message of the original error
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ReportedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// All values of [`ErrorKind::kind`], to restore the kind of reported errors
+const KINDS: &[&str] = &[
+    "RuntimeJoin",
+    "ReadFileInput",
+    "ReadStdinInput",
+    "Utf8",
+    "NetworkRequest",
+    "ReadResponseBody",
+    "InvalidContentEncoding",
+    "BuildRequestClient",
+    "BuildGithubClient",
+    "GithubRequest",
+    "GithubNotFound",
+    "GithubUnauthorized",
+    "GithubRateLimited",
+    "InvalidGithubUrl",
+    "Dns",
+    "HostUnreachable",
+    "ParseUrl",
+    "InvalidFilePath",
+    "ReadUri",
+    "InvalidUrlFromPath",
+    "UnreachableEmailAddress",
+    "DisposableMail",
+    "InvalidHeader",
+    "InvalidBase",
+    "InvalidUriRemap",
+    "UnsupportedScheme",
+    "FileNotFound",
+    "DirTraversal",
+    "InvalidGlobPattern",
+    "MissingGitHubToken",
+    "InsecureURL",
+    "HstsUpgrade",
+    "CrossHostRedirect",
+    "Redirect",
+    "TrailingSlashRedirect",
+    "Archived",
+    "TimeBudgetExceeded",
+    "CheckLimitReached",
+    "EmptyUrl",
+    "InvalidFeed",
+    "UnresolvedVariable",
+    "InsecureFinalUrl",
+    "MissingCorsHeader",
+    "ContentTypeMismatch",
+    "ContentLengthMismatch",
+    "UnexpectedStatusCode",
+    "MixedContent",
+    "MissingFragment",
+    "AmbiguousFragment",
+    "Channel",
+    "InvalidUrlHost",
+    "InvalidURI",
+    "Regex",
+    "InvalidSlackUri",
+    "WebSocket",
+];
+
 impl ErrorKind {
     /// Return more details from the given [`ErrorKind`]
     ///
@@ -120,42 +356,293 @@ impl ErrorKind {
                 octocrab::Error::GitHub { source, .. } => Some(source.message.to_string()),
                 _ => None,
             },
+            ErrorKind::FileNotFound(..) | ErrorKind::InvalidFilePath(..) => self
+                .suggestion()
+                .map(|similar| format!("Did you mean `{}`?", similar.display())),
             _ => self.source().map(ToString::to_string),
         }
     }
+
+    /// Suggest an existing file for a missing local file, which has a similar
+    /// name in the same directory (e.g. `readme.md` for `README.md`)
+    ///
+    /// The suggestion gets looked up once, when the error is created.
+    #[must_use]
+    pub fn suggestion(&self) -> Option<&Path> {
+        match self {
+            ErrorKind::FileNotFound(_, similar) | ErrorKind::InvalidFilePath(_, similar) => {
+                similar.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the input of an error which only affects a single link or input,
+    /// e.g. an [`ErrorKind::EmptyUrl`]
+    ///
+    /// The [`Collector`](crate::Collector) yields these errors along with the
+    /// links of the other inputs instead of stopping, so that they can be
+    /// reported like a failed check with [`Response::from_input_error`].
+    ///
+    /// [`Response::from_input_error`]: crate::Response::from_input_error
+    #[must_use]
+    pub const fn input_source(&self) -> Option<&InputSource> {
+        match self {
+            ErrorKind::EmptyUrl(source)
+            | ErrorKind::InvalidFeed(source, _)
+            | ErrorKind::UnresolvedVariable(_, _, source) => Some(source),
+            _ => None,
+        }
+    }
+
+    /// Returns a stable, machine-readable name of the error kind
+    ///
+    /// Unlike the error message, the name does not change between releases,
+    /// so it can be used by tools processing the (e.g. JSON) output.
+    /// It is the name of the enum variant, e.g. `ParseUrl` for
+    /// [`ErrorKind::ParseUrl`]. Reported errors keep the kind of the
+    /// original error if it is known.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::RuntimeJoin(..) => "RuntimeJoin",
+            Self::ReadFileInput(..) => "ReadFileInput",
+            Self::ReadStdinInput(..) => "ReadStdinInput",
+            Self::Utf8(..) => "Utf8",
+            Self::NetworkRequest(..) => "NetworkRequest",
+            Self::ReadResponseBody(..) => "ReadResponseBody",
+            Self::InvalidContentEncoding(..) => "InvalidContentEncoding",
+            Self::BuildRequestClient(..) => "BuildRequestClient",
+            Self::BuildGithubClient(..) => "BuildGithubClient",
+            Self::GithubRequest(..) => "GithubRequest",
+            Self::GithubNotFound => "GithubNotFound",
+            Self::GithubUnauthorized => "GithubUnauthorized",
+            Self::GithubRateLimited => "GithubRateLimited",
+            Self::InvalidGithubUrl(..) => "InvalidGithubUrl",
+            Self::Dns { .. } => "Dns",
+            Self::HostUnreachable { .. } => "HostUnreachable",
+            Self::ParseUrl(..) => "ParseUrl",
+            Self::InvalidFilePath(..) => "InvalidFilePath",
+            Self::ReadUri(..) => "ReadUri",
+            Self::InvalidUrlFromPath(..) => "InvalidUrlFromPath",
+            Self::UnreachableEmailAddress(..) => "UnreachableEmailAddress",
+            Self::DisposableMail(..) => "DisposableMail",
+            Self::InvalidHeader(..) => "InvalidHeader",
+            Self::InvalidBase(..) => "InvalidBase",
+            Self::InvalidUriRemap(..) => "InvalidUriRemap",
+            Self::UnsupportedScheme(..) => "UnsupportedScheme",
+            Self::FileNotFound(..) => "FileNotFound",
+            Self::DirTraversal(..) => "DirTraversal",
+            Self::InvalidGlobPattern(..) => "InvalidGlobPattern",
+            Self::MissingGitHubToken => "MissingGitHubToken",
+            Self::InsecureURL(..) => "InsecureURL",
+            Self::HstsUpgrade(..) => "HstsUpgrade",
+            Self::CrossHostRedirect(..) => "CrossHostRedirect",
+            Self::Redirect(..) => "Redirect",
+            Self::TrailingSlashRedirect(..) => "TrailingSlashRedirect",
+            Self::Archived(..) => "Archived",
+            Self::TimeBudgetExceeded(..) => "TimeBudgetExceeded",
+            Self::CheckLimitReached(..) => "CheckLimitReached",
+            Self::Reported(e) => match e.kind {
+                Some(kind) => kind,
+                None => "Reported",
+            },
+            Self::EmptyUrl(..) => "EmptyUrl",
+            Self::InvalidFeed(..) => "InvalidFeed",
+            Self::UnresolvedVariable(..) => "UnresolvedVariable",
+            Self::InsecureFinalUrl(..) => "InsecureFinalUrl",
+            Self::MissingCorsHeader(..) => "MissingCorsHeader",
+            Self::ContentTypeMismatch { .. } => "ContentTypeMismatch",
+            Self::ContentLengthMismatch { .. } => "ContentLengthMismatch",
+            Self::UnexpectedStatusCode { .. } => "UnexpectedStatusCode",
+            Self::MixedContent(..) => "MixedContent",
+            Self::MissingFragment(..) => "MissingFragment",
+            Self::AmbiguousFragment(..) => "AmbiguousFragment",
+            Self::Channel(..) => "Channel",
+            Self::InvalidUrlHost => "InvalidUrlHost",
+            Self::InvalidURI(..) => "InvalidURI",
+            Self::Regex(..) => "Regex",
+            Self::InvalidSlackUri(..) => "InvalidSlackUri",
+            Self::WebSocket(..) => "WebSocket",
+        }
+    }
 }
 
-#[allow(clippy::match_same_arms)]
+#[allow(clippy::match_same_arms, clippy::too_many_lines)]
 impl PartialEq for ErrorKind {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::NetworkRequest(e1), Self::NetworkRequest(e2)) => {
                 e1.to_string() == e2.to_string()
             }
-            (Self::ReadResponseBody(e1), Self::ReadResponseBody(e2)) => {
+            (Self::ReadResponseBody(e1), Self::ReadResponseBody(e2))
+            | (Self::InvalidContentEncoding(e1), Self::InvalidContentEncoding(e2)) => {
                 e1.to_string() == e2.to_string()
             }
             (Self::BuildRequestClient(e1), Self::BuildRequestClient(e2)) => {
                 e1.to_string() == e2.to_string()
             }
+            (Self::BuildGithubClient(e1), Self::BuildGithubClient(e2)) => {
+                e1.to_string() == e2.to_string()
+            }
             (Self::RuntimeJoin(e1), Self::RuntimeJoin(e2)) => e1.to_string() == e2.to_string(),
+            (Self::DirTraversal(e1), Self::DirTraversal(e2)) => e1.to_string() == e2.to_string(),
+            (Self::Channel(e1), Self::Channel(e2)) => e1.to_string() == e2.to_string(),
+            (Self::Regex(e1), Self::Regex(e2)) => e1.to_string() == e2.to_string(),
+            (Self::Utf8(e1), Self::Utf8(e2)) => e1 == e2,
             (Self::ReadFileInput(e1, s1), Self::ReadFileInput(e2, s2)) => {
                 e1.kind() == e2.kind() && s1 == s2
             }
             (Self::ReadStdinInput(e1), Self::ReadStdinInput(e2)) => e1.kind() == e2.kind(),
+            (Self::ReadUri(e1, u1), Self::ReadUri(e2, u2)) => e1.kind() == e2.kind() && u1 == u2,
             (Self::GithubRequest(e1), Self::GithubRequest(e2)) => e1.to_string() == e2.to_string(),
-            (Self::InvalidGithubUrl(s1), Self::InvalidGithubUrl(s2)) => s1 == s2,
+            (Self::InvalidGithubUrl(s1), Self::InvalidGithubUrl(s2))
+            | (Self::InvalidUriRemap(s1), Self::InvalidUriRemap(s2))
+            | (Self::UnsupportedScheme(s1), Self::UnsupportedScheme(s2)) => s1 == s2,
+            (Self::InvalidBase(b1, s1), Self::InvalidBase(b2, s2)) => b1 == b2 && s1 == s2,
+            (Self::FileNotFound(p1, _), Self::FileNotFound(p2, _))
+            | (Self::InvalidUrlFromPath(p1), Self::InvalidUrlFromPath(p2)) => p1 == p2,
+            (Self::InvalidURI(u1), Self::InvalidURI(u2))
+            | (Self::InvalidFilePath(u1, _), Self::InvalidFilePath(u2, _)) => u1 == u2,
             (Self::ParseUrl(s1, e1), Self::ParseUrl(s2, e2)) => s1 == s2 && e1 == e2,
             (Self::UnreachableEmailAddress(u1, ..), Self::UnreachableEmailAddress(u2, ..)) => {
                 u1 == u2
             }
-            (Self::InsecureURL(u1), Self::InsecureURL(u2)) => u1 == u2,
+            (Self::DisposableMail(u1, d1), Self::DisposableMail(u2, d2)) => u1 == u2 && d1 == d2,
+            (Self::InsecureURL(u1), Self::InsecureURL(u2))
+            | (Self::HstsUpgrade(u1), Self::HstsUpgrade(u2)) => u1 == u2,
+            (Self::MixedContent(u1), Self::MixedContent(u2))
+            | (Self::MissingFragment(u1), Self::MissingFragment(u2)) => u1 == u2,
+            (Self::AmbiguousFragment(u1, c1), Self::AmbiguousFragment(u2, c2)) => {
+                u1 == u2 && c1 == c2
+            }
+            (Self::CrossHostRedirect(u1), Self::CrossHostRedirect(u2))
+            | (Self::Redirect(u1), Self::Redirect(u2))
+            | (Self::TrailingSlashRedirect(u1), Self::TrailingSlashRedirect(u2)) => u1 == u2,
+            (Self::Archived(c1, u1), Self::Archived(c2, u2)) => c1 == c2 && u1 == u2,
+            (Self::MissingCorsHeader(u1), Self::MissingCorsHeader(u2)) => u1 == u2,
+            (Self::InsecureFinalUrl(u1), Self::InsecureFinalUrl(u2)) => u1 == u2,
+            (Self::EmptyUrl(s1), Self::EmptyUrl(s2)) => s1 == s2,
+            (Self::InvalidFeed(s1, r1), Self::InvalidFeed(s2, r2)) => s1 == s2 && r1 == r2,
+            (Self::UnresolvedVariable(n1, l1, s1), Self::UnresolvedVariable(n2, l2, s2)) => {
+                n1 == n2 && l1 == l2 && s1 == s2
+            }
+            (
+                Self::ContentTypeMismatch {
+                    expected: e1,
+                    actual: a1,
+                },
+                Self::ContentTypeMismatch {
+                    expected: e2,
+                    actual: a2,
+                },
+            ) => e1 == e2 && a1 == a2,
+            (
+                Self::ContentLengthMismatch {
+                    expected: e1,
+                    actual: a1,
+                },
+                Self::ContentLengthMismatch {
+                    expected: e2,
+                    actual: a2,
+                },
+            ) => e1 == e2 && a1 == a2,
+            (
+                Self::UnexpectedStatusCode {
+                    expected: e1,
+                    actual: a1,
+                },
+                Self::UnexpectedStatusCode {
+                    expected: e2,
+                    actual: a2,
+                },
+            ) => e1 == e2 && a1 == a2,
+            (Self::InvalidSlackUri(u1, s1), Self::InvalidSlackUri(u2, s2)) => u1 == u2 && s1 == s2,
+            (Self::WebSocket(s1), Self::WebSocket(s2)) => s1 == s2,
+            (Self::TimeBudgetExceeded(d1), Self::TimeBudgetExceeded(d2)) => d1 == d2,
+            (Self::CheckLimitReached(n1), Self::CheckLimitReached(n2)) => n1 == n2,
+            (Self::Reported(e1), Self::Reported(e2)) => e1 == e2,
+            (Self::Dns { host: h1 }, Self::Dns { host: h2 }) => h1 == h2,
+            (
+                Self::HostUnreachable {
+                    host: h1,
+                    reason: r1,
+                },
+                Self::HostUnreachable {
+                    host: h2,
+                    reason: r2,
+                },
+            ) => h1 == h2 && r1 == r2,
             (Self::InvalidGlobPattern(e1), Self::InvalidGlobPattern(e2)) => {
                 e1.msg == e2.msg && e1.pos == e2.pos
             }
             (Self::InvalidHeader(_), Self::InvalidHeader(_))
-            | (Self::MissingGitHubToken, Self::MissingGitHubToken) => true,
-            _ => false,
+            | (Self::MissingGitHubToken, Self::MissingGitHubToken)
+            | (Self::GithubNotFound, Self::GithubNotFound)
+            | (Self::GithubUnauthorized, Self::GithubUnauthorized)
+            | (Self::GithubRateLimited, Self::GithubRateLimited)
+            | (Self::InvalidUrlHost, Self::InvalidUrlHost) => true,
+            // Different variants. They are listed instead of using a
+            // wildcard, so that new variants can't be forgotten above.
+            (
+                Self::RuntimeJoin(..)
+                | Self::ReadFileInput(..)
+                | Self::ReadStdinInput(..)
+                | Self::Utf8(..)
+                | Self::NetworkRequest(..)
+                | Self::ReadResponseBody(..)
+                | Self::InvalidContentEncoding(..)
+                | Self::BuildRequestClient(..)
+                | Self::BuildGithubClient(..)
+                | Self::GithubRequest(..)
+                | Self::GithubNotFound
+                | Self::GithubUnauthorized
+                | Self::GithubRateLimited
+                | Self::InvalidGithubUrl(..)
+                | Self::Dns { .. }
+                | Self::HostUnreachable { .. }
+                | Self::ParseUrl(..)
+                | Self::InvalidFilePath(..)
+                | Self::ReadUri(..)
+                | Self::InvalidUrlFromPath(..)
+                | Self::UnreachableEmailAddress(..)
+                | Self::DisposableMail(..)
+                | Self::InvalidHeader(..)
+                | Self::InvalidBase(..)
+                | Self::InvalidUriRemap(..)
+                | Self::UnsupportedScheme(..)
+                | Self::FileNotFound(..)
+                | Self::DirTraversal(..)
+                | Self::InvalidGlobPattern(..)
+                | Self::MissingGitHubToken
+                | Self::InsecureURL(..)
+                | Self::HstsUpgrade(..)
+                | Self::CrossHostRedirect(..)
+                | Self::Redirect(..)
+                | Self::TrailingSlashRedirect(..)
+                | Self::Archived(..)
+                | Self::EmptyUrl(..)
+                | Self::InvalidFeed(..)
+                | Self::UnresolvedVariable(..)
+                | Self::InsecureFinalUrl(..)
+                | Self::MissingCorsHeader(..)
+                | Self::ContentTypeMismatch { .. }
+                | Self::ContentLengthMismatch { .. }
+                | Self::UnexpectedStatusCode { .. }
+                | Self::MixedContent(..)
+                | Self::MissingFragment(..)
+                | Self::AmbiguousFragment(..)
+                | Self::Channel(..)
+                | Self::InvalidUrlHost
+                | Self::InvalidURI(..)
+                | Self::Regex(..)
+                | Self::InvalidSlackUri(..)
+                | Self::WebSocket(..)
+                | Self::TimeBudgetExceeded(..)
+                | Self::CheckLimitReached(..)
+                | Self::Reported(..),
+                _,
+            ) => false,
         }
     }
 }
@@ -172,40 +659,132 @@ impl Hash for ErrorKind {
             Self::RuntimeJoin(e) => e.to_string().hash(state),
             Self::ReadFileInput(e, s) => (e.kind(), s).hash(state),
             Self::ReadStdinInput(e) => e.kind().hash(state),
+            Self::ReadUri(e, u) => (e.kind(), u).hash(state),
             Self::NetworkRequest(e) => e.to_string().hash(state),
-            Self::ReadResponseBody(e) => e.to_string().hash(state),
+            Self::ReadResponseBody(e) | Self::InvalidContentEncoding(e) => {
+                e.to_string().hash(state);
+            }
             Self::BuildRequestClient(e) => e.to_string().hash(state),
             Self::BuildGithubClient(e) => e.to_string().hash(state),
             Self::GithubRequest(e) => e.to_string().hash(state),
             Self::InvalidGithubUrl(s) => s.hash(state),
             Self::DirTraversal(e) => e.to_string().hash(state),
-            Self::FileNotFound(e) => e.to_string_lossy().hash(state),
+            Self::FileNotFound(p, _) => p.hash(state),
             Self::ParseUrl(e, s) => (e.to_string(), s).hash(state),
             Self::InvalidURI(u) => u.hash(state),
             Self::InvalidUrlFromPath(p) => p.hash(state),
             Self::Utf8(e) => e.to_string().hash(state),
-            Self::InvalidFilePath(u) => u.hash(state),
+            Self::InvalidFilePath(u, _) => u.hash(state),
             Self::UnreachableEmailAddress(u, ..) => u.hash(state),
-            Self::InsecureURL(u, ..) => u.hash(state),
+            Self::DisposableMail(u, d) => (u, d).hash(state),
+            Self::InsecureURL(u, ..) | Self::HstsUpgrade(u) => u.hash(state),
+            Self::MixedContent(u) | Self::MissingFragment(u) => u.hash(state),
+            Self::AmbiguousFragment(u, c) => (u, c).hash(state),
+            Self::CrossHostRedirect(u) | Self::Redirect(u) | Self::TrailingSlashRedirect(u) => {
+                u.hash(state);
+            }
+            Self::Archived(c, u) => (c, u).hash(state),
+            Self::MissingCorsHeader(u) => u.hash(state),
+            Self::InsecureFinalUrl(u) => u.hash(state),
+            Self::EmptyUrl(s) => s.hash(state),
+            Self::InvalidFeed(s, r) => (s, r).hash(state),
+            Self::UnresolvedVariable(n, l, s) => (n, l, s).hash(state),
+            Self::ContentTypeMismatch { expected, actual } => (expected, actual).hash(state),
+            Self::ContentLengthMismatch { expected, actual } => (expected, actual).hash(state),
+            Self::UnexpectedStatusCode { expected, actual } => (expected, actual).hash(state),
             Self::InvalidBase(base, e) => (base, e).hash(state),
             Self::InvalidUriRemap(remap) => (remap).hash(state),
+            Self::UnsupportedScheme(scheme) => scheme.hash(state),
             Self::InvalidHeader(e) => e.to_string().hash(state),
             Self::InvalidGlobPattern(e) => e.to_string().hash(state),
             Self::Channel(e) => e.to_string().hash(state),
-            Self::MissingGitHubToken | Self::InvalidUrlHost => {
+            Self::MissingGitHubToken
+            | Self::InvalidUrlHost
+            | Self::GithubNotFound
+            | Self::GithubUnauthorized
+            | Self::GithubRateLimited => {
                 std::mem::discriminant(self).hash(state);
             }
             Self::Regex(e) => e.to_string().hash(state),
+            Self::InvalidSlackUri(u, s) => (u, s).hash(state),
+            Self::WebSocket(s) => s.hash(state),
+            Self::Dns { host } => host.hash(state),
+            Self::HostUnreachable { host, reason } => (host, reason).hash(state),
+            Self::TimeBudgetExceeded(d) => d.hash(state),
+            Self::CheckLimitReached(n) => n.hash(state),
+            Self::Reported(e) => e.hash(state),
         }
     }
 }
 
+/// Errors are serialized as an object with the stable [`ErrorKind::kind`]
+/// and the human-readable error message, e.g.
+/// `{"kind": "InvalidUrlHost", "message": "URL is missing a host"}`
 impl Serialize for ErrorKind {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.collect_str(self)
+        let mut s = serializer.serialize_struct("ErrorKind", 2)?;
+        s.serialize_field("kind", self.kind())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.end()
+    }
+}
+
+/// The original error can't be restored from its serialized form, so errors
+/// get deserialized into [`ErrorKind::Reported`]. It serializes exactly like
+/// the original error.
+impl<'de> Deserialize<'de> for ErrorKind {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Serialized {
+            kind: String,
+            message: String,
+        }
+
+        let Serialized { kind, message } = Serialized::deserialize(deserializer)?;
+        Ok(Self::Reported(ReportedError::new(Some(&kind), message)))
+    }
+}
+
+/// Errors reported by the GitHub API are classified by their message, as
+/// the status code doesn't get passed on by `octocrab`. All other errors
+/// become an [`ErrorKind::GithubRequest`].
+impl From<octocrab::Error> for ErrorKind {
+    fn from(e: octocrab::Error) -> Self {
+        match &e {
+            octocrab::Error::GitHub { source, .. } => {
+                github_error_kind(&source.message).unwrap_or(Self::GithubRequest(e))
+            }
+            _ => Self::GithubRequest(e),
+        }
+    }
+}
+
+/// Classify an error message of the GitHub API, see
+/// <https://docs.github.com/en/rest/overview/resources-in-the-rest-api>
+fn github_error_kind(message: &str) -> Option<ErrorKind> {
+    let message = message.to_lowercase();
+    if message == "not found" {
+        Some(ErrorKind::GithubNotFound)
+    } else if message == "bad credentials" || message == "requires authentication" {
+        Some(ErrorKind::GithubUnauthorized)
+    } else if message.contains("rate limit") {
+        // Both the primary (`API rate limit exceeded for ...`) and the
+        // secondary rate limit (`You have exceeded a secondary rate limit`)
+        Some(ErrorKind::GithubRateLimited)
+    } else {
+        None
+    }
+}
+
+impl From<(Uri, std::io::Error)> for ErrorKind {
+    fn from((uri, e): (Uri, std::io::Error)) -> Self {
+        Self::ReadUri(e, uri)
     }
 }
 
@@ -215,3 +794,286 @@ impl From<Infallible> for ErrorKind {
         unreachable!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::hash_map::DefaultHasher, hash::Hasher, io};
+
+    use super::*;
+    use crate::FileType;
+
+    fn hash(error: &ErrorKind) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        error.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// One error of each variant, except for those wrapping errors which
+    /// can't be created outside of their crate (e.g. `JoinError`)
+    fn errors() -> Vec<ErrorKind> {
+        let uri = |s| Uri::try_from(s).unwrap();
+        let io_error = || io::Error::new(io::ErrorKind::NotFound, "No such file or directory");
+        vec![
+            ErrorKind::ReadFileInput(io_error(), PathBuf::from("missing.md")),
+            ErrorKind::ReadStdinInput(io_error()),
+            ErrorKind::Utf8(String::from_utf8(vec![0xff]).unwrap_err().utf8_error()),
+            ErrorKind::NetworkRequest(reqwest::Client::new().get("http://").build().unwrap_err()),
+            ErrorKind::ReadResponseBody(reqwest::Client::new().get("http://").build().unwrap_err()),
+            ErrorKind::InvalidContentEncoding(
+                reqwest::Client::new().get("http://").build().unwrap_err(),
+            ),
+            ErrorKind::BuildRequestClient(
+                reqwest::Client::new().get("http://").build().unwrap_err(),
+            ),
+            ErrorKind::GithubNotFound,
+            ErrorKind::GithubUnauthorized,
+            ErrorKind::GithubRateLimited,
+            ErrorKind::InvalidGithubUrl("https://github.com".to_string()),
+            ErrorKind::Dns {
+                host: "example.invalid".to_string(),
+            },
+            ErrorKind::HostUnreachable {
+                host: "127.0.0.1".to_string(),
+                reason: "Connection refused".to_string(),
+            },
+            ErrorKind::ParseUrl(url::ParseError::EmptyHost, "http://".to_string()),
+            ErrorKind::InvalidFilePath(uri("file:///missing"), None),
+            ErrorKind::ReadUri(io_error(), uri("file:///missing")),
+            ErrorKind::InvalidUrlFromPath(PathBuf::from("missing")),
+            ErrorKind::UnreachableEmailAddress(uri("mailto:a@example.com"), "No MX".to_string()),
+            ErrorKind::DisposableMail(uri("mailto:a@yopmail.com"), "yopmail.com".to_string()),
+            ErrorKind::InvalidHeader(http::HeaderValue::from_str("\n").unwrap_err()),
+            ErrorKind::InvalidBase("base".to_string(), "invalid".to_string()),
+            ErrorKind::InvalidUriRemap("remap".to_string()),
+            ErrorKind::UnsupportedScheme("gopher".to_string()),
+            ErrorKind::FileNotFound(PathBuf::from("missing"), None),
+            ErrorKind::InvalidGlobPattern(glob::Pattern::new("[").unwrap_err()),
+            ErrorKind::MissingGitHubToken,
+            ErrorKind::InsecureURL(uri("https://example.com")),
+            ErrorKind::HstsUpgrade(uri("https://example.com")),
+            ErrorKind::CrossHostRedirect(uri("https://example.com")),
+            ErrorKind::Redirect(uri("https://example.com")),
+            ErrorKind::TrailingSlashRedirect(uri("https://example.com")),
+            ErrorKind::Archived(
+                StatusCode::NOT_FOUND,
+                uri("https://web.archive.org/web/2020/https://example.com"),
+            ),
+            ErrorKind::EmptyUrl(InputSource::Stdin),
+            ErrorKind::InvalidFeed(InputSource::Stdin, "unterminated tag".to_string()),
+            ErrorKind::UnresolvedVariable(
+                "HOST".to_string(),
+                "https://${HOST}/".to_string(),
+                InputSource::Stdin,
+            ),
+            ErrorKind::InsecureFinalUrl(uri("https://example.com")),
+            ErrorKind::MissingCorsHeader(uri("https://example.com")),
+            ErrorKind::ContentTypeMismatch {
+                expected: "image/png".to_string(),
+                actual: "text/html".to_string(),
+            },
+            ErrorKind::ContentLengthMismatch {
+                expected: 2,
+                actual: 1,
+            },
+            ErrorKind::UnexpectedStatusCode {
+                expected: StatusCode::UNAUTHORIZED,
+                actual: StatusCode::OK,
+            },
+            ErrorKind::MixedContent(uri("http://example.com")),
+            ErrorKind::MissingFragment(uri("file:///index.html#top")),
+            ErrorKind::AmbiguousFragment(uri("file:///index.html#top"), 2),
+            ErrorKind::Channel(tokio::sync::mpsc::error::SendError(
+                InputContent::from_string("", FileType::Plaintext),
+            )),
+            ErrorKind::InvalidUrlHost,
+            ErrorKind::InvalidURI(uri("https://example.com")),
+            ErrorKind::Regex(regex::Regex::new(&"(".repeat(2)).unwrap_err()),
+            ErrorKind::InvalidSlackUri(uri("slack://open"), "missing team".to_string()),
+            ErrorKind::WebSocket("rejected".to_string()),
+            ErrorKind::TimeBudgetExceeded(Duration::from_secs(1)),
+            ErrorKind::CheckLimitReached(10),
+            ErrorKind::Reported(ReportedError::new(
+                Some("InvalidUrlHost"),
+                "URL is missing a host".to_string(),
+            )),
+        ]
+    }
+
+    #[test]
+    fn test_eq_and_hash() {
+        for (i, error) in errors().iter().enumerate() {
+            for (j, other) in errors().iter().enumerate() {
+                if i == j {
+                    assert_eq!(error, other);
+                    assert_eq!(hash(error), hash(other), "{}", error.kind());
+                } else {
+                    assert_ne!(error, other);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_eq_compares_payload() {
+        assert_ne!(
+            ErrorKind::FileNotFound(PathBuf::from("a"), None),
+            ErrorKind::FileNotFound(PathBuf::from("b"), None)
+        );
+        assert_ne!(
+            ErrorKind::InvalidURI(Uri::try_from("https://a.example").unwrap()),
+            ErrorKind::InvalidURI(Uri::try_from("https://b.example").unwrap())
+        );
+        assert_ne!(
+            ErrorKind::InvalidUrlFromPath(PathBuf::from("a")),
+            ErrorKind::InvalidUrlFromPath(PathBuf::from("b"))
+        );
+    }
+
+    #[test]
+    fn test_read_uri_error_message() {
+        let uri = Uri::try_from("file:///tmp/missing.html").unwrap();
+        let e = io::Error::new(io::ErrorKind::NotFound, "No such file or directory");
+        let error = ErrorKind::from((uri.clone(), e));
+
+        assert!(matches!(&error, ErrorKind::ReadUri(_, u) if *u == uri));
+        assert_eq!(
+            error.to_string(),
+            "Cannot read `file:///tmp/missing.html`: No such file or directory"
+        );
+    }
+
+    #[test]
+    fn test_suggestion() {
+        let error = ErrorKind::FileNotFound(
+            PathBuf::from("docs/README.md"),
+            Some(PathBuf::from("docs/readme.md")),
+        );
+        assert_eq!(error.suggestion(), Some(Path::new("docs/readme.md")));
+        assert_eq!(
+            error.details(),
+            Some("Did you mean `docs/readme.md`?".to_string())
+        );
+
+        let error = ErrorKind::FileNotFound(PathBuf::from("CHANGELOG.md"), None);
+        assert_eq!(error.suggestion(), None);
+        assert_eq!(error.details(), None);
+    }
+
+    #[test]
+    fn test_serialize_parse_error() {
+        let e = url::Url::parse("http://").unwrap_err();
+        let error = ErrorKind::ParseUrl(e, "http://".to_string());
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["kind"], "ParseUrl");
+        assert_eq!(json["message"], error.to_string());
+    }
+
+    #[test]
+    fn test_serialize_io_error() {
+        let e = io::Error::new(io::ErrorKind::NotFound, "No such file or directory");
+        let error = ErrorKind::ReadFileInput(e, PathBuf::from("missing.md"));
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["kind"], "ReadFileInput");
+        assert_eq!(
+            json["message"],
+            "Cannot read input content from file `missing.md`"
+        );
+    }
+
+    #[test]
+    fn test_github_error_kind() {
+        assert_eq!(
+            github_error_kind("Not Found"),
+            Some(ErrorKind::GithubNotFound)
+        );
+        assert_eq!(
+            github_error_kind("Bad credentials"),
+            Some(ErrorKind::GithubUnauthorized)
+        );
+        assert_eq!(
+            github_error_kind("Requires authentication"),
+            Some(ErrorKind::GithubUnauthorized)
+        );
+        assert_eq!(
+            github_error_kind(
+                "API rate limit exceeded for 192.0.2.1. (But here's the good news: \
+                Authenticated requests get a higher rate limit.)"
+            ),
+            Some(ErrorKind::GithubRateLimited)
+        );
+        assert_eq!(
+            github_error_kind(
+                "You have exceeded a secondary rate limit. \
+                Please wait a few minutes before you try again."
+            ),
+            Some(ErrorKind::GithubRateLimited)
+        );
+        assert_eq!(github_error_kind("Repository access blocked"), None);
+    }
+
+    #[tokio::test]
+    async fn test_classify_github_errors() {
+        use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        for (repo, status, message) in [
+            ("deleted", 404, "Not Found"),
+            ("bad-token", 401, "Bad credentials"),
+            ("limited", 403, "API rate limit exceeded for user ID 1."),
+            ("blocked", 451, "Repository access blocked"),
+        ] {
+            Mock::given(path(format!("/repos/lycheeverse/{repo}")))
+                .respond_with(
+                    ResponseTemplate::new(status).set_body_json(serde_json::json!({
+                        "message": message,
+                        "documentation_url": "https://docs.github.com/rest"
+                    })),
+                )
+                .mount(&mock_server)
+                .await;
+        }
+        let client = octocrab::Octocrab::builder()
+            .base_url(mock_server.uri())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        for (repo, kind) in [
+            ("deleted", "GithubNotFound"),
+            ("bad-token", "GithubUnauthorized"),
+            ("limited", "GithubRateLimited"),
+            ("blocked", "GithubRequest"),
+        ] {
+            let e = Box::pin(client.repos("lycheeverse", repo).get())
+                .await
+                .unwrap_err();
+            assert_eq!(ErrorKind::from(e).kind(), kind);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_error() {
+        let error = ErrorKind::InvalidUrlHost;
+        let json = serde_json::to_value(&error).unwrap();
+        let deserialized: ErrorKind = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(deserialized.kind(), "InvalidUrlHost");
+        assert_eq!(deserialized.to_string(), error.to_string());
+        assert_eq!(serde_json::to_value(&deserialized).unwrap(), json);
+
+        // Kinds of newer releases are unknown
+        let json = serde_json::json!({"kind": "FutureKind", "message": "Future"});
+        let deserialized: ErrorKind = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.kind(), "Reported");
+        assert_eq!(deserialized.to_string(), "Future");
+    }
+
+    #[test]
+    fn test_kinds() {
+        for error in errors() {
+            assert!(KINDS.contains(&error.kind()), "{}", error.kind());
+        }
+    }
+}
@@ -7,6 +7,18 @@ pub enum FileType {
     Html,
     /// File in Markdown format
     Markdown,
+    /// Jupyter notebook (`.ipynb`), whose Markdown cells get checked
+    Notebook,
+    /// Email message in the MIME format (`.eml`), whose HTML and text parts
+    /// get checked
+    Email,
+    /// File in `AsciiDoc` format (`.adoc`)
+    AsciiDoc,
+    /// XML document (`.xml`), e.g. generated API documentation. Documents
+    /// with the root element of a feed are handled like a [`FileType::Feed`]
+    Xml,
+    /// RSS or Atom feed (`.rss`, `.atom`) or OPML outline (`.opml`)
+    Feed,
     /// Generic text file without syntax-specific parsing
     Plaintext,
 }
@@ -18,7 +30,7 @@ impl Default for FileType {
 }
 
 impl<P: AsRef<Path>> From<P> for FileType {
-    /// Detect if the given path points to a Markdown, HTML, or plaintext file.
+    /// Detect if the given path points to a Markdown, HTML, notebook, email, `AsciiDoc`, XML, feed, or plaintext file.
     fn from(p: P) -> FileType {
         let path = p.as_ref();
         // Assume HTML in case of no extension.
@@ -39,6 +51,11 @@ impl<P: AsRef<Path>> From<P> for FileType {
         {
             Some("md" | "markdown") => FileType::Markdown,
             Some("htm" | "html") => FileType::Html,
+            Some("ipynb") => FileType::Notebook,
+            Some("eml") => FileType::Email,
+            Some("adoc" | "asciidoc") => FileType::AsciiDoc,
+            Some("xml") => FileType::Xml,
+            Some("rss" | "atom" | "opml") => FileType::Feed,
             None if is_url => FileType::Html,
             _ => FileType::Plaintext,
         }
@@ -62,6 +79,20 @@ mod tests {
         assert_eq!(FileType::from(Path::new("test.txt")), FileType::Plaintext);
         assert_eq!(FileType::from(Path::new("README.TXT")), FileType::Plaintext);
 
+        assert_eq!(
+            FileType::from(Path::new("analysis.ipynb")),
+            FileType::Notebook
+        );
+        assert_eq!(FileType::from(Path::new("newsletter.eml")), FileType::Email);
+        assert_eq!(FileType::from(Path::new("guide.adoc")), FileType::AsciiDoc);
+        assert_eq!(FileType::from(Path::new("Example.XML")), FileType::Xml);
+        assert_eq!(FileType::from(Path::new("news.rss")), FileType::Feed);
+        assert_eq!(FileType::from(Path::new("blog.atom")), FileType::Feed);
+        assert_eq!(
+            FileType::from(Path::new("subscriptions.opml")),
+            FileType::Feed
+        );
+
         assert_eq!(FileType::from(Path::new("test.htm")), FileType::Html);
         assert_eq!(FileType::from(Path::new("index.html")), FileType::Html);
         assert_eq!(
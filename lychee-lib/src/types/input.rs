@@ -1,23 +1,41 @@
+use crate::helpers::{
+    gitignore::{GitIgnore, GitIgnoreTree},
+    reqwest::body_error,
+    sitemap::Sitemap,
+};
 use crate::types::FileType;
-use crate::{helpers, ErrorKind, Result};
+use crate::{helpers, ErrorKind, Result, Uri};
 use async_stream::try_stream;
-use futures::stream::Stream;
+use futures::stream::{Stream, StreamExt};
 use glob::glob_with;
-use jwalk::WalkDir;
+use jwalk::WalkDirGeneric;
+use log::warn;
 use reqwest::Url;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 use shellexpand::tilde;
+use std::collections::VecDeque;
+use std::ffi::OsStr;
 use std::fmt::Display;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tokio::io::{stdin, AsyncReadExt};
 
 const STDIN: &str = "-";
+/// Maximum nesting of sitemap indexes, to guard against cycles
+const MAX_SITEMAP_DEPTH: usize = 3;
 
 // Check the extension of the given path against the list of known/accepted
 // file extensions
 fn valid_extension(p: &Path) -> bool {
-    matches!(FileType::from(p), FileType::Markdown | FileType::Html)
+    matches!(
+        FileType::from(p),
+        FileType::Markdown
+            | FileType::Html
+            | FileType::Notebook
+            | FileType::Email
+            | FileType::AsciiDoc
+            | FileType::Feed
+    )
 }
 
 #[derive(Debug)]
@@ -42,6 +60,24 @@ impl InputContent {
             content: s.to_owned(),
         }
     }
+
+    #[must_use]
+    /// Create one instance of `InputContent` per changed file in a unified
+    /// diff (e.g. the output of `git diff`)
+    ///
+    /// Only the added lines are kept as content, so that only new or modified
+    /// links get checked. The source of each instance is the path of the
+    /// changed file.
+    pub fn from_diff(diff: &str) -> Vec<Self> {
+        helpers::diff::added_lines(diff)
+            .into_iter()
+            .map(|(path, lines)| Self {
+                file_type: FileType::from(path.as_path()),
+                source: InputSource::FsPath(path),
+                content: lines.join("\n"),
+            })
+            .collect()
+    }
 }
 
 impl TryFrom<&PathBuf> for InputContent {
@@ -92,6 +128,30 @@ impl Serialize for InputSource {
     }
 }
 
+/// Input sources get deserialized from their string representation like
+/// inputs given on the command line, except that paths don't need to exist.
+/// Raw string inputs can't be told apart from paths, so they become paths.
+impl<'de> Deserialize<'de> for InputSource {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let source = String::deserialize(deserializer)?;
+        Ok(if source == STDIN {
+            Self::Stdin
+        } else if let Ok(url) = Url::parse(&source) {
+            Self::RemoteUrl(Box::new(url))
+        } else if glob::Pattern::escape(&source) != source {
+            Self::FsGlob {
+                pattern: source,
+                ignore_case: false,
+            }
+        } else {
+            Self::FsPath(PathBuf::from(source))
+        })
+    }
+}
+
 impl Display for InputSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
@@ -104,14 +164,34 @@ impl Display for InputSource {
     }
 }
 
+impl InputSource {
+    /// The URL of the document itself, without a fragment, to resolve
+    /// fragment-only links and recognize links to the same document.
+    /// Only remote URLs and files have one.
+    pub(crate) fn document_url(&self) -> Option<Url> {
+        let mut url = match self {
+            Self::RemoteUrl(url) => url.as_ref().clone(),
+            Self::FsPath(path) => {
+                Url::from_file_path(helpers::path::absolute_path(path.clone())).ok()?
+            }
+            _ => return None,
+        };
+        url.set_fragment(None);
+        Some(url)
+    }
+}
+
 /// Lychee Input with optional file hint for parsing
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Input {
     /// Origin of input
     pub source: InputSource,
-    /// Hint to indicate which extractor to use
+    /// Hint to indicate which extractor to use.
+    /// Overrides the file type detected from file extensions.
     pub file_type_hint: Option<FileType>,
-    /// Excluded paths that will be skipped when reading content
+    /// Excluded paths that will be skipped when reading content.
+    /// Paths containing wildcards (e.g. `**/CHANGELOG.md`) are matched as
+    /// glob patterns.
     pub excluded_paths: Option<Vec<PathBuf>>,
 }
 
@@ -149,7 +229,8 @@ impl Input {
                     InputSource::FsPath(path)
                 } else if path.is_relative() {
                     // If the file does not exist and it is a relative path, exit immediately
-                    return Err(ErrorKind::FileNotFound(path));
+                    let similar = helpers::path::similar_file(&path);
+                    return Err(ErrorKind::FileNotFound(path, similar));
                 } else {
                     // Invalid path; check if a valid URL can be constructed from the input
                     // by prefixing it with a `http://` scheme.
@@ -171,6 +252,9 @@ impl Input {
 
     /// Retrieve the contents from the input
     ///
+    /// If a file type hint is set, it is used for all contents, regardless of
+    /// file extensions.
+    ///
     /// # Errors
     ///
     /// Returns an error if the contents can not be retrieved
@@ -180,6 +264,18 @@ impl Input {
         self,
         skip_missing: bool,
     ) -> impl Stream<Item = Result<InputContent>> {
+        let file_type_hint = self.file_type_hint;
+        self.read_contents(skip_missing).await.map(move |content| {
+            content.map(|mut content| {
+                if let Some(file_type) = file_type_hint {
+                    content.file_type = file_type;
+                }
+                content
+            })
+        })
+    }
+
+    async fn read_contents(self, skip_missing: bool) -> impl Stream<Item = Result<InputContent>> {
         try_stream! {
             match self.source {
                 InputSource::RemoteUrl(ref url) => {
@@ -187,7 +283,11 @@ impl Input {
                     match content {
                         Err(_) if skip_missing => (),
                         Err(e) => Err(e)?,
-                        Ok(content) => yield content,
+                        Ok(content) => {
+                            for content in Self::expand_sitemap(content).await? {
+                                yield content;
+                            }
+                        }
                     }
                 },
                 InputSource::FsGlob {
@@ -201,8 +301,13 @@ impl Input {
                 }
                 InputSource::FsPath(ref path) => {
                     if path.is_dir() {
-                        for entry in WalkDir::new(path).skip_hidden(true)
-                        .process_read_dir(move |_, _, _, children| {
+                        for entry in WalkDirGeneric::<(GitIgnore, ())>::new(path).skip_hidden(true)
+                        .process_read_dir(move |depth, dir, gitignore, children| {
+                            // The root directory itself is never ignored
+                            // (depth is `None` when processing it)
+                            if depth.is_some() {
+                                gitignore.add_dir(dir);
+                            }
                             children.retain(|child| {
                                 let entry = match child.as_ref() {
                                     Ok(x) => x,
@@ -213,6 +318,12 @@ impl Input {
                                     return false;
                                 }
 
+                                if depth.is_some()
+                                    && gitignore.is_ignored(&entry.path(), entry.file_type().is_dir())
+                                {
+                                    return false;
+                                }
+
                                 let file_type = entry.file_type();
 
                                 if file_type.is_dir() {
@@ -243,7 +354,11 @@ impl Input {
                         match content {
                             Err(_) if skip_missing => (),
                             Err(e) => Err(e)?,
-                            Ok(content) => yield content,
+                            Ok(content) => {
+                                for content in Self::expand_sitemap(content).await? {
+                                    yield content;
+                                }
+                            }
                         };
                     }
                 },
@@ -259,26 +374,86 @@ impl Input {
         }
     }
 
-    async fn url_contents(url: &Url) -> Result<InputContent> {
-        // Assume HTML for default paths
-        let file_type = if url.path().is_empty() || url.path() == "/" {
+    /// Detect the file type of a remote input from its URL
+    pub(crate) fn url_file_type(url: &Url) -> FileType {
+        // Assume HTML for paths without an extension, e.g. the default path
+        if Path::new(url.path()).extension().is_none() {
             FileType::Html
         } else {
-            FileType::from(url.as_str())
-        };
+            FileType::from(url.path())
+        }
+    }
 
+    pub(crate) async fn url_contents(url: &Url) -> Result<InputContent> {
         let res = reqwest::get(url.clone())
             .await
             .map_err(ErrorKind::NetworkRequest)?;
+        Self::response_contents(url, res).await
+    }
+
+    /// Read the contents of a remote input from the response to the request
+    /// for `url`
+    pub(crate) async fn response_contents(
+        url: &Url,
+        res: reqwest::Response,
+    ) -> Result<InputContent> {
+        let file_type = Self::url_file_type(url);
+        let content = if Path::new(url.path()).extension() == Some(OsStr::new("gz")) {
+            let bytes = Box::pin(res.bytes()).await.map_err(body_error)?;
+            helpers::sitemap::decode(&bytes)
+                .map_err(|e| ErrorKind::from((Uri::from(url.clone()), e)))?
+        } else {
+            Box::pin(res.text()).await.map_err(body_error)?
+        };
         let input_content = InputContent {
             source: InputSource::RemoteUrl(Box::new(url.clone())),
             file_type,
-            content: res.text().await.map_err(ErrorKind::ReadResponseBody)?,
+            content,
         };
 
         Ok(input_content)
     }
 
+    /// Replace an XML sitemap with the URLs it lists
+    ///
+    /// Sitemap indexes are resolved by fetching the sitemaps they reference,
+    /// up to a depth of `MAX_SITEMAP_DEPTH`. Contents which are not a sitemap
+    /// are returned unchanged. Plain-text sitemaps need no special handling,
+    /// as they get checked like any other plaintext file.
+    async fn expand_sitemap(content: InputContent) -> Result<Vec<InputContent>> {
+        // Only XML and plaintext inputs (e.g. `sitemap.xml`) can be sitemaps
+        if !matches!(content.file_type, FileType::Xml | FileType::Plaintext) {
+            return Ok(vec![content]);
+        }
+
+        let mut contents = vec![];
+        let mut pending = VecDeque::from([(content, 0)]);
+        while let Some((content, depth)) = pending.pop_front() {
+            match helpers::sitemap::parse(&content.content) {
+                None => contents.push(content),
+                Some(Sitemap::Urls(urls)) => contents.push(InputContent {
+                    source: content.source,
+                    file_type: FileType::Plaintext,
+                    content: urls.join("\n"),
+                }),
+                Some(Sitemap::Index(_)) if depth >= MAX_SITEMAP_DEPTH => {
+                    warn!(
+                        "Skipping sitemap index {}: nested too deeply",
+                        content.source
+                    );
+                }
+                Some(Sitemap::Index(sitemaps)) => {
+                    for sitemap in sitemaps {
+                        let url = Url::parse(&sitemap)
+                            .map_err(|e| ErrorKind::ParseUrl(e, sitemap.clone()))?;
+                        pending.push_back((Self::url_contents(&url).await?, depth + 1));
+                    }
+                }
+            }
+        }
+        Ok(contents)
+    }
+
     async fn glob_contents(
         &self,
         path_glob: &str,
@@ -289,6 +464,19 @@ impl Input {
 
         match_opts.case_sensitive = !ignore_case;
 
+        // `.gitignore` files are only considered below the non-wildcard
+        // part of the pattern, e.g. `docs` for `docs/**/*.md`
+        let root: PathBuf = Path::new(&glob_expanded)
+            .components()
+            .take_while(|c| {
+                !c.as_os_str()
+                    .to_string_lossy()
+                    .contains(['*', '?', '[', '{'])
+            })
+            .collect();
+
+        let mut gitignore = GitIgnoreTree::new(root);
+
         try_stream! {
             for entry in glob_with(&glob_expanded, match_opts)? {
                 match entry {
@@ -304,8 +492,13 @@ impl Input {
                         if self.is_excluded_path(&path) {
                             continue;
                         }
+                        if gitignore.is_ignored(&path) {
+                            continue;
+                        }
                         let content: InputContent = Self::path_content(&path).await?;
-                        yield content;
+                        for content in Self::expand_sitemap(content).await? {
+                            yield content;
+                        }
                     }
                     Err(e) => eprintln!("{e:?}"),
                 }
@@ -330,9 +523,14 @@ impl Input {
         path: P,
     ) -> Result<InputContent> {
         let path = path.into();
-        let content = tokio::fs::read_to_string(&path)
-            .await
-            .map_err(|e| ErrorKind::ReadFileInput(e, path.clone()))?;
+        let content = if path.extension() == Some(OsStr::new("gz")) {
+            tokio::fs::read(&path)
+                .await
+                .and_then(|bytes| helpers::sitemap::decode(&bytes))
+        } else {
+            tokio::fs::read_to_string(&path).await
+        }
+        .map_err(|e| ErrorKind::ReadFileInput(e, path.clone()))?;
         let input_content = InputContent {
             file_type: FileType::from(&path),
             source: InputSource::FsPath(path),
@@ -366,16 +564,96 @@ impl Input {
 /// This is a standalone function to allow for easier testing
 fn is_excluded_path(excluded_paths: &[PathBuf], path: &PathBuf) -> bool {
     for excluded in excluded_paths {
-        if let Ok(true) = helpers::path::contains(excluded, path) {
+        if let Some(pattern) = exclusion_glob(excluded) {
+            if pattern.matches_path_with(path, EXCLUSION_MATCH_OPTIONS) {
+                return true;
+            }
+        } else if let Ok(true) = helpers::path::contains(excluded, path) {
             return true;
         }
     }
     false
 }
 
+const EXCLUSION_MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Get the glob pattern of an excluded path, if it contains any wildcards.
+/// A leading `./` is dropped, as it never appears in matched paths.
+fn exclusion_glob(excluded: &Path) -> Option<glob::Pattern> {
+    let excluded = excluded.to_str()?;
+    if glob::Pattern::escape(excluded) == excluded {
+        return None;
+    }
+    glob::Pattern::new(excluded.trim_start_matches("./")).ok()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Write;
+
+    use flate2::{
+        write::{GzEncoder, ZlibEncoder},
+        Compression,
+    };
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
     use super::*;
+    use crate::helpers::fragment;
+
+    const HTML: &str = r#"<html><body><h1 id="usage">Usage</h1></body></html>"#;
+
+    async fn serve_encoded(encoding: &str, body: Vec<u8>) -> (MockServer, Url) {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Encoding", encoding)
+                    .insert_header("Content-Type", "text/html")
+                    .set_body_bytes(body),
+            )
+            .mount(&mock_server)
+            .await;
+        let url = Url::parse(&mock_server.uri())
+            .unwrap()
+            .join("page")
+            .unwrap();
+        (mock_server, url)
+    }
+
+    #[tokio::test]
+    async fn test_url_contents_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(HTML.as_bytes()).unwrap();
+        let (_server, url) = serve_encoded("gzip", encoder.finish().unwrap()).await;
+
+        let contents = Input::url_contents(&url).await.unwrap();
+        assert_eq!(contents.content, HTML);
+        assert_eq!(fragment::count_anchors(&contents.content, "usage"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_url_contents_deflate() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(HTML.as_bytes()).unwrap();
+        let (_server, url) = serve_encoded("deflate", encoder.finish().unwrap()).await;
+
+        let contents = Input::url_contents(&url).await.unwrap();
+        assert_eq!(contents.content, HTML);
+    }
+
+    #[tokio::test]
+    async fn test_url_contents_corrupt_encoding() {
+        let (_server, url) = serve_encoded("gzip", HTML.as_bytes().to_vec()).await;
+
+        assert!(matches!(
+            Input::url_contents(&url).await,
+            Err(ErrorKind::InvalidContentEncoding(_))
+        ));
+    }
 
     #[test]
     fn test_input_handles_real_relative_paths() {
@@ -409,7 +687,7 @@ mod tests {
         assert!(input.is_err());
         assert!(matches!(
             input,
-            Err(ErrorKind::FileNotFound(PathBuf { .. }))
+            Err(ErrorKind::FileNotFound(PathBuf { .. }, None))
         ));
     }
 
@@ -448,4 +726,29 @@ mod tests {
             &child.to_path_buf()
         ));
     }
+
+    #[test]
+    fn test_excluded_glob() {
+        let excluded = [PathBuf::from("**/CHANGELOG.md")];
+        assert!(is_excluded_path(&excluded, &PathBuf::from("CHANGELOG.md")));
+        assert!(is_excluded_path(
+            &excluded,
+            &PathBuf::from("docs/api/CHANGELOG.md")
+        ));
+        assert!(is_excluded_path(
+            &excluded,
+            &PathBuf::from("/tmp/project/CHANGELOG.md")
+        ));
+        assert!(!is_excluded_path(&excluded, &PathBuf::from("README.md")));
+
+        let excluded = [PathBuf::from("docs/*.html")];
+        assert!(is_excluded_path(
+            &excluded,
+            &PathBuf::from("docs/index.html")
+        ));
+        assert!(!is_excluded_path(
+            &excluded,
+            &PathBuf::from("docs/api/index.html")
+        ));
+    }
 }
@@ -1,4 +1,9 @@
+use std::collections::HashSet;
+
 use check_if_email_exists::{CheckEmailOutput, Reachable};
+use percent_encoding::percent_decode_str;
+
+use crate::Uri;
 
 /// A crude way to extract error details from the mail output.
 /// This was added because `CheckEmailOutput` doesn't impl `Display`
@@ -17,3 +22,84 @@ pub(crate) fn error_from_output(o: &CheckEmailOutput) -> String {
         Reachable::Unknown => "Unknown: We're unable to get a valid response from the recipient's email server."
     }.to_string()
 }
+
+/// Domains of well-known disposable (temporary) mail providers
+const DISPOSABLE_DOMAINS: &[&str] = &[
+    "10minutemail.com",
+    "33mail.com",
+    "dispostable.com",
+    "emailondeck.com",
+    "fakeinbox.com",
+    "getnada.com",
+    "guerrillamail.com",
+    "guerrillamail.net",
+    "mailcatch.com",
+    "maildrop.cc",
+    "mailinator.com",
+    "mailnesia.com",
+    "mintemail.com",
+    "mohmal.com",
+    "sharklasers.com",
+    "spamgourmet.com",
+    "temp-mail.org",
+    "tempmail.com",
+    "throwawaymail.com",
+    "trashmail.com",
+    "yopmail.com",
+];
+
+/// Get the domain of the first recipient of a `mailto` URI which belongs to
+/// a disposable mail provider, either from the built-in list or from the
+/// `extra` domains (in lowercase). Subdomains of listed domains match, too.
+pub(crate) fn disposable_domain(uri: &Uri, extra: &HashSet<String>) -> Option<String> {
+    let listed = |domain: &str| DISPOSABLE_DOMAINS.contains(&domain) || extra.contains(domain);
+    let recipients = percent_decode_str(uri.url.path()).decode_utf8_lossy();
+    recipients.split(',').find_map(|address| {
+        let (_, domain) = address.rsplit_once('@')?;
+        let domain = domain.trim().trim_end_matches('.').to_lowercase();
+        let disposable = listed(&domain)
+            || domain
+                .match_indices('.')
+                .any(|(i, _)| listed(&domain[i + 1..]));
+        disposable.then_some(domain)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    use super::*;
+
+    fn disposable(uri: &str, extra: &[&str]) -> Option<String> {
+        let extra = extra.iter().map(ToString::to_string).collect();
+        disposable_domain(&Uri::from(Url::parse(uri).unwrap()), &extra)
+    }
+
+    #[test]
+    fn test_disposable_domain() {
+        assert_eq!(
+            disposable("mailto:someone@Mailinator.com", &[]),
+            Some("mailinator.com".to_string())
+        );
+        assert_eq!(
+            disposable("mailto:a@example.com,b@mx.yopmail.com?subject=Hi", &[]),
+            Some("mx.yopmail.com".to_string())
+        );
+        assert_eq!(
+            disposable("mailto:someone%40throwaway.example", &["throwaway.example"]),
+            Some("throwaway.example".to_string())
+        );
+    }
+
+    #[test]
+    fn test_regular_domain() {
+        assert_eq!(disposable("mailto:someone@example.com", &[]), None);
+        // Only whole domain labels match
+        assert_eq!(disposable("mailto:someone@notmailinator.com", &[]), None);
+        assert_eq!(
+            disposable("mailto:someone@example.com", &["example.org"]),
+            None
+        );
+    }
+}
@@ -6,19 +6,29 @@ mod error;
 mod file;
 mod input;
 pub(crate) mod mail;
+mod progress;
 mod request;
 mod response;
+mod retry_jitter;
 mod status;
+mod status_filter;
+mod timings;
+mod trailing_slash;
 pub(crate) mod uri;
 
 pub use base::Base;
 pub use cache::CacheStatus;
-pub use error::ErrorKind;
+pub use error::{ErrorKind, ReportedError};
 pub use file::FileType;
 pub use input::{Input, InputContent, InputSource};
-pub use request::Request;
+pub use progress::{Progress, ProgressCallback, ProgressCounter};
+pub use request::{Request, RequestGroup};
 pub use response::{Response, ResponseBody};
-pub use status::Status;
+pub use retry_jitter::RetryJitter;
+pub use status::{ExclusionSource, Status};
+pub use status_filter::StatusFilter;
+pub use timings::Timings;
+pub use trailing_slash::TrailingSlash;
 
 /// The lychee `Result` type
 pub type Result<T> = std::result::Result<T, crate::ErrorKind>;
@@ -0,0 +1,143 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// Strategy to report the progress of the checks of a [`Client`], so that
+/// GUIs and servers can drive their own progress UI
+///
+/// A check counts towards the total as soon as it starts, so the total grows
+/// while links are still being collected and checked concurrently.
+///
+/// See [`ClientBuilder::progress`].
+///
+/// [`Client`]: crate::Client
+/// [`ClientBuilder::progress`]: crate::ClientBuilder::progress
+#[derive(Debug, Clone, Default)]
+pub enum Progress {
+    /// Don't report any progress
+    #[default]
+    None,
+    /// Call a function whenever a check starts or finishes
+    Callback(ProgressCallback),
+    /// Count the started and finished checks, e.g. to poll them from a UI
+    /// thread
+    Counter(Arc<ProgressCounter>),
+}
+
+impl Progress {
+    /// Record the start of a check
+    pub(crate) fn start(&self) {
+        match self {
+            Self::None => {}
+            Self::Callback(callback) => {
+                callback.counter.start();
+                callback.call();
+            }
+            Self::Counter(counter) => counter.start(),
+        }
+    }
+
+    /// Record the end of a check
+    pub(crate) fn finish(&self) {
+        match self {
+            Self::None => {}
+            Self::Callback(callback) => {
+                callback.counter.finish();
+                callback.call();
+            }
+            Self::Counter(counter) => counter.finish(),
+        }
+    }
+}
+
+/// Number of started (`total`) and finished checks
+#[derive(Debug, Default)]
+pub struct ProgressCounter {
+    total: AtomicUsize,
+    finished: AtomicUsize,
+}
+
+impl ProgressCounter {
+    /// Create a counter without any checks
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of checks which were started so far
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    /// Number of checks which are finished
+    #[must_use]
+    pub fn finished(&self) -> usize {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    fn start(&self) {
+        self.total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn finish(&self) {
+        self.finished.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Function which gets called with the number of finished checks and the
+/// total number of started checks, see [`Progress::Callback`]
+#[derive(Clone)]
+pub struct ProgressCallback {
+    counter: Arc<ProgressCounter>,
+    callback: Arc<ProgressFn>,
+}
+
+type ProgressFn = dyn Fn(usize, usize) + Send + Sync;
+
+impl ProgressCallback {
+    /// Create a callback, which gets called with the number of finished
+    /// checks and the total number of started checks
+    pub fn new(callback: impl Fn(usize, usize) + Send + Sync + 'static) -> Self {
+        Self {
+            counter: Arc::default(),
+            callback: Arc::new(callback),
+        }
+    }
+
+    fn call(&self) {
+        (self.callback)(self.counter.finished(), self.counter.total());
+    }
+}
+
+impl std::fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressCallback")
+            .field("counter", &self.counter)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_progress_callback() {
+        let calls = Arc::new(Mutex::new(vec![]));
+        let progress = Progress::Callback(ProgressCallback::new({
+            let calls = calls.clone();
+            move |finished, total| calls.lock().unwrap().push((finished, total))
+        }));
+
+        progress.start();
+        progress.start();
+        progress.finish();
+        progress.finish();
+
+        assert_eq!(*calls.lock().unwrap(), [(0, 1), (0, 2), (1, 2), (2, 2)]);
+    }
+}
@@ -1,5 +1,12 @@
 use std::{convert::TryFrom, fmt::Display};
 
+/// Elements which load subresources into a page (as opposed to navigation
+/// links like `<a>`)
+const SUBRESOURCE_ELEMENTS: &[&str] = &[
+    "audio", "embed", "iframe", "img", "input", "link", "object", "script", "source", "track",
+    "video",
+];
+
 use crate::{ErrorKind, Uri};
 
 use super::InputSource;
@@ -39,6 +46,24 @@ impl Request {
     }
 }
 
+impl Request {
+    /// Returns `true` if this is an insecure (`http://`) subresource, like an
+    /// image or a script, which is embedded into a secure (`https://`) page.
+    ///
+    /// Browsers block or warn about such "mixed content".
+    /// The page context is only known for remote inputs.
+    #[must_use]
+    pub fn is_mixed_content(&self) -> bool {
+        let secure_page =
+            matches!(&self.source, InputSource::RemoteUrl(url) if url.scheme() == "https");
+        let subresource = matches!(
+            self.element.as_deref(),
+            Some(element) if SUBRESOURCE_ELEMENTS.contains(&element)
+        );
+        secure_page && subresource && self.uri.scheme() == "http"
+    }
+}
+
 impl Display for Request {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} ({})", self.uri, self.source)
@@ -80,3 +105,42 @@ impl TryFrom<&str> for Request {
         ))
     }
 }
+
+/// A request with alternate URIs, e.g. mirrors of the same resource
+///
+/// The group is considered successful if any of its URIs can be reached.
+/// See [`Client::check_group`](crate::Client::check_group).
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct RequestGroup {
+    /// The primary request, which gets checked first
+    pub request: Request,
+    /// Alternate URIs, which get checked in order if the primary request fails
+    pub alternates: Vec<Uri>,
+}
+
+impl RequestGroup {
+    /// Instantiate a new `RequestGroup` object
+    #[inline]
+    #[must_use]
+    pub const fn new(request: Request, alternates: Vec<Uri>) -> Self {
+        RequestGroup {
+            request,
+            alternates,
+        }
+    }
+
+    /// Iterate over all requests of the group, starting with the primary one
+    ///
+    /// Alternate requests share the source, element and attribute of the
+    /// primary request.
+    pub fn requests(&self) -> impl Iterator<Item = Request> + '_ {
+        std::iter::once(self.request.clone()).chain(self.alternates.iter().map(|uri| {
+            Request::new(
+                uri.clone(),
+                self.request.source.clone(),
+                self.request.element.clone(),
+                self.request.attribute.clone(),
+            )
+        }))
+    }
+}
@@ -1,9 +1,13 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
 
 use http::StatusCode;
-use serde::Serialize;
+use reqwest::Url;
+use serde::{de, Deserialize, Deserializer, Serialize};
 
-use crate::{InputSource, Status, Uri};
+use crate::{ErrorKind, InputSource, Status, Timings, Uri};
 
 /// Response type returned by lychee after checking a URI
 #[derive(Debug)]
@@ -13,8 +17,74 @@ impl Response {
     #[inline]
     #[must_use]
     /// Create new response
+    ///
+    /// The number of attempts is zero, i.e. the URI is not considered to be
+    /// checked. Use [`Response::with_attempts`] to set it.
     pub const fn new(uri: Uri, status: Status, source: InputSource) -> Self {
-        Response(source, ResponseBody { uri, status })
+        Response(source, ResponseBody::new(uri, status))
+    }
+
+    /// Report a link or an input which can't be checked, because of an error
+    /// found while collecting the links (see [`ErrorKind::input_source`])
+    ///
+    /// The URI is the one of the document containing the link. Inputs
+    /// without a URL, like stdin, are reported as `about:blank`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error itself if it doesn't belong to a single link or input
+    pub fn from_input_error(error: ErrorKind) -> Result<Self, ErrorKind> {
+        let Some(source) = error.input_source().cloned() else {
+            return Err(error);
+        };
+        Ok(Self::for_input(source, error))
+    }
+
+    /// Report an input which can't be checked because of `error`, see
+    /// [`Response::from_input_error`]
+    pub(crate) fn for_input(source: InputSource, error: ErrorKind) -> Self {
+        let url = source.document_url().unwrap_or_else(about_blank);
+        Self::new(Uri { url }, Status::Error(error), source)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Set the number of attempts it took to check the URI
+    pub const fn with_attempts(mut self, attempts: u64) -> Self {
+        self.1.attempts = attempts;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Set the response headers captured while checking the URI
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.1.headers = headers;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Set the reason why the URI was excluded
+    pub fn with_exclusion_reason(mut self, reason: Option<String>) -> Self {
+        self.1.exclusion_reason = reason;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Set the URI the check ended up at after following redirects
+    pub fn with_redirect(mut self, redirect: Option<Uri>) -> Self {
+        self.1.redirect = redirect;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Set the durations of the phases of checking the URI
+    pub const fn with_timings(mut self, timings: Option<Timings>) -> Self {
+        self.1.timings = timings;
+        self
     }
 
     #[inline]
@@ -25,6 +95,11 @@ impl Response {
     }
 }
 
+/// URL of an empty document
+fn about_blank() -> Url {
+    Url::parse("about:blank").expect("valid URL")
+}
+
 impl Display for Response {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         <ResponseBody as Display>::fmt(&self.1, f)
@@ -41,14 +116,206 @@ impl Serialize for Response {
 }
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug, Serialize, Hash, PartialEq, Eq)]
+#[derive(Debug)]
+#[non_exhaustive]
 /// Encapsulates the state of a URI check
+///
+/// New fields get added over time, so use [`ResponseBody::new`] or
+/// [`Response::new`] to create one.
 pub struct ResponseBody {
-    #[serde(flatten)]
     /// The URI which was checked
     pub uri: Uri,
     /// The status of the check
     pub status: Status,
+    /// Number of attempts to check the URI, including retries
+    ///
+    /// This is zero for URIs which didn't get checked, e.g. because they
+    /// were excluded or cached.
+    pub attempts: u64,
+    /// Captured response headers as (lowercase name, value) pairs
+    ///
+    /// Only the headers configured with
+    /// [`ClientBuilder::capture_headers`](crate::ClientBuilder) are captured,
+    /// so this is usually empty.
+    pub headers: Vec<(String, String)>,
+    /// Durations of the phases of the check, only measured for websites
+    pub timings: Option<Timings>,
+    /// Why the URI was excluded, if the reason is not apparent from the
+    /// configuration (e.g. `self-link`)
+    pub exclusion_reason: Option<String>,
+    /// Final URI after following redirects, if the check of a website ended
+    /// up at a different URL
+    pub redirect: Option<Uri>,
+}
+
+impl ResponseBody {
+    #[inline]
+    #[must_use]
+    /// Create the body of a response, see [`Response::new`]
+    pub const fn new(uri: Uri, status: Status) -> Self {
+        ResponseBody {
+            uri,
+            status,
+            attempts: 0,
+            headers: vec![],
+            timings: None,
+            exclusion_reason: None,
+            redirect: None,
+        }
+    }
+}
+
+/// Timings differ between runs, so they are ignored when comparing responses
+impl PartialEq for ResponseBody {
+    fn eq(&self, other: &Self) -> bool {
+        self.uri == other.uri
+            && self.status == other.status
+            && self.attempts == other.attempts
+            && self.headers == other.headers
+            && self.exclusion_reason == other.exclusion_reason
+            && self.redirect == other.redirect
+    }
+}
+
+impl Eq for ResponseBody {}
+
+impl Hash for ResponseBody {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.uri.hash(state);
+        self.status.hash(state);
+        self.attempts.hash(state);
+        self.headers.hash(state);
+        self.exclusion_reason.hash(state);
+        self.redirect.hash(state);
+    }
+}
+
+/// Besides the URI, the status and the number of attempts, the error of failed
+/// and unsupported checks gets serialized, so that its [`ErrorKind::kind`] is
+/// available to tools. Captured headers are serialized as a map, timings in
+/// milliseconds, the reason of exclusions as `reason` and the final URL of
+/// redirected websites as `redirect`.
+impl Serialize for ResponseBody {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Body<'a> {
+            #[serde(flatten)]
+            uri: &'a Uri,
+            status: &'a Status,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            error: Option<&'a ErrorKind>,
+            attempts: u64,
+            #[serde(skip_serializing_if = "<[_]>::is_empty")]
+            #[serde(serialize_with = "serialize_headers")]
+            headers: &'a [(String, String)],
+            #[serde(skip_serializing_if = "Option::is_none")]
+            timings: Option<Timings>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            reason: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            redirect: Option<&'a str>,
+        }
+
+        Body {
+            uri: &self.uri,
+            status: &self.status,
+            error: self.status.error(),
+            attempts: self.attempts,
+            headers: &self.headers,
+            timings: self.timings,
+            reason: self.exclusion_reason.as_deref(),
+            redirect: self.redirect.as_ref().map(Uri::as_str),
+        }
+        .serialize(s)
+    }
+}
+
+fn serialize_headers<S>(headers: &&[(String, String)], s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    s.collect_map(headers.iter().map(|(name, value)| (name, value)))
+}
+
+/// Responses get deserialized from the format they are serialized to, so
+/// that reports (e.g. of an earlier run) can be read back. Errors only keep
+/// their kind and message, see [`ErrorKind::Reported`]. Fields which older
+/// releases didn't write are optional.
+impl<'de> Deserialize<'de> for ResponseBody {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Body {
+            #[serde(flatten)]
+            uri: Uri,
+            status: String,
+            #[serde(default)]
+            error: Option<ErrorKind>,
+            #[serde(default)]
+            attempts: u64,
+            #[serde(default)]
+            #[serde(deserialize_with = "deserialize_headers")]
+            headers: Vec<(String, String)>,
+            #[serde(default)]
+            timings: Option<Timings>,
+            #[serde(default)]
+            reason: Option<String>,
+            #[serde(default)]
+            redirect: Option<String>,
+        }
+
+        let body = Body::deserialize(deserializer)?;
+        let status = Status::from_serialized(&body.status, body.error).ok_or_else(|| {
+            de::Error::invalid_value(de::Unexpected::Str(&body.status), &"a response status")
+        })?;
+        Ok(ResponseBody {
+            uri: body.uri,
+            status,
+            attempts: body.attempts,
+            headers: body.headers,
+            timings: body.timings,
+            exclusion_reason: body.reason,
+            redirect: body
+                .redirect
+                .map(|redirect| Url::parse(&redirect).map(Uri::from))
+                .transpose()
+                .map_err(de::Error::custom)?,
+        })
+    }
+}
+
+/// Read the headers map, keeping the order of the headers
+fn deserialize_headers<'de, D>(d: D) -> Result<Vec<(String, String)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct HeadersVisitor;
+
+    impl<'de> de::Visitor<'de> for HeadersVisitor {
+        type Value = Vec<(String, String)>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a map of header names to values")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::MapAccess<'de>,
+        {
+            let mut headers = vec![];
+            while let Some(header) = map.next_entry()? {
+                headers.push(header);
+            }
+            Ok(headers)
+        }
+    }
+
+    d.deserialize_map(HeadersVisitor)
 }
 
 // Extract as much information from the underlying error conditions as possible
@@ -84,9 +351,21 @@ impl Display for ResponseBody {
             Status::Timeout(Some(code)) => write!(f, "Timeout [{code}]"),
             Status::Timeout(None) => write!(f, "Timeout"),
             Status::UnknownStatusCode(code) => write!(f, "Unknown status code [{code}]"),
-            Status::Excluded => write!(f, "Excluded"),
+            Status::Excluded => match self
+                .exclusion_reason
+                .as_deref()
+                .or_else(|| self.uri.opaque_reason())
+            {
+                Some(reason) => write!(f, "Excluded ({reason})"),
+                None => write!(f, "Excluded"),
+            },
             Status::Unsupported(e) => write!(f, "Unsupported {e}"),
+            Status::Skipped(e) => write!(f, "{e}"),
             Status::Cached(status) => write!(f, "{status}"),
+            Status::Warning(e) => match e.details() {
+                Some(details) => write!(f, "{e}: {details}"),
+                None => write!(f, "{e}"),
+            },
             Status::Error(e) => {
                 if let Some(details) = e.details() {
                     write!(f, "{e}: {details}")
@@ -94,6 +373,82 @@ impl Display for ResponseBody {
                     write!(f, "{e}")
                 }
             }
+        }?;
+
+        if !self.headers.is_empty() {
+            let headers: Vec<_> = self
+                .headers
+                .iter()
+                .map(|(name, value)| format!("{name}: {value}"))
+                .collect();
+            write!(f, " ({})", headers.join(", "))?;
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_error() {
+        let body = ResponseBody {
+            uri: Uri::try_from("https://example.com/").unwrap(),
+            status: Status::Error(ErrorKind::InvalidUrlHost),
+            attempts: 1,
+            headers: vec![],
+            timings: None,
+            exclusion_reason: None,
+            redirect: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "url": "https://example.com/",
+                "status": "Failed: URL is missing a host",
+                "error": {"kind": "InvalidUrlHost", "message": "URL is missing a host"},
+                "attempts": 1
+            })
+        );
+
+        let body = ResponseBody {
+            uri: Uri::try_from("https://example.com/").unwrap(),
+            status: Status::Ok(StatusCode::OK),
+            attempts: 1,
+            headers: vec![],
+            timings: None,
+            exclusion_reason: None,
+            redirect: None,
+        };
+        assert!(serde_json::to_value(&body).unwrap().get("error").is_none());
+    }
+
+    #[test]
+    fn test_display_opaque_reason() {
+        let body = ResponseBody {
+            uri: Uri::try_from("magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a")
+                .unwrap(),
+            status: Status::Excluded,
+            attempts: 0,
+            headers: vec![],
+            timings: None,
+            exclusion_reason: None,
+            redirect: None,
+        };
+        assert!(body
+            .to_string()
+            .ends_with("| Excluded (magnet links identify files in peer-to-peer networks)"));
+
+        let body = ResponseBody {
+            uri: Uri::try_from("https://example.com/").unwrap(),
+            status: Status::Excluded,
+            attempts: 0,
+            headers: vec![],
+            timings: None,
+            exclusion_reason: None,
+            redirect: None,
+        };
+        assert!(body.to_string().ends_with("| Excluded"));
     }
 }
@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+/// Randomization of the wait time between retries, so that links which
+/// failed at the same time (e.g. because their host was overloaded) don't get
+/// retried at the same time again
+///
+/// See <https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/>
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RetryJitter {
+    /// Wait for a random time between zero and the backoff time
+    Full,
+    /// Wait for half of the backoff time plus a random time up to the other
+    /// half
+    Equal,
+}
+
+impl RetryJitter {
+    /// Get a random wait time for the given backoff time
+    #[must_use]
+    pub fn apply(self, wait: Duration) -> Duration {
+        let random = |max: Duration| max.mul_f64(fastrand::f64());
+        match self {
+            Self::Full => random(wait),
+            Self::Equal => wait / 2 + random(wait / 2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn samples(jitter: RetryJitter, wait: Duration) -> Vec<Duration> {
+        (0..100).map(|_| jitter.apply(wait)).collect()
+    }
+
+    #[test]
+    fn test_full_jitter() {
+        let wait = Duration::from_secs(4);
+        let samples = samples(RetryJitter::Full, wait);
+        assert!(samples.iter().all(|sample| *sample <= wait));
+        // The retries are spread out
+        assert!(samples.iter().collect::<HashSet<_>>().len() > 1);
+        assert!(samples.iter().any(|sample| *sample < wait / 2));
+    }
+
+    #[test]
+    fn test_equal_jitter() {
+        let wait = Duration::from_secs(4);
+        let samples = samples(RetryJitter::Equal, wait);
+        assert!(samples
+            .iter()
+            .all(|sample| (wait / 2..=wait).contains(sample)));
+        assert!(samples.iter().collect::<HashSet<_>>().len() > 1);
+    }
+}
@@ -4,7 +4,7 @@ use http::StatusCode;
 use reqwest::Response;
 use serde::{Serialize, Serializer};
 
-use crate::ErrorKind;
+use crate::{helpers::reqwest::is_dns_error, ErrorKind, ReportedError};
 
 use super::CacheStatus;
 
@@ -16,6 +16,8 @@ const ICON_UNKNOWN: &str = "\u{003f}"; // ?
 const ICON_ERROR: &str = "\u{2717}"; // ✗
 const ICON_TIMEOUT: &str = "\u{29d6}"; // ⧖
 const ICON_CACHED: &str = "\u{21bb}"; // ↻
+const ICON_WARNING: &str = "\u{26a0}"; // ⚠
+const ICON_SKIPPED: &str = "\u{23ed}"; // ⏭
 
 /// Response status of the request.
 #[allow(variant_size_differences)]
@@ -34,18 +36,78 @@ pub enum Status {
     /// Resource was excluded from checking
     Excluded,
     /// The request type is currently not supported,
-    /// for example when the URL scheme is `slack://`.
+    /// for example when the URL scheme is `irc://`.
     /// See https://github.com/lycheeverse/lychee/issues/199
     Unsupported(ErrorKind),
     /// Cached request status from previous run
     Cached(CacheStatus),
+    /// The check succeeded, but revealed an issue which should be fixed
+    /// (e.g. a redirect to a different URL). Unlike errors, warnings are not
+    /// considered failures.
+    Warning(Box<ErrorKind>),
+    /// The link was not checked at all, e.g. because the run was stopped
+    /// after exceeding its time budget or its maximum number of checks. Like
+    /// excluded links, skipped links are not considered failures.
+    Skipped(Box<ErrorKind>),
 }
 
+/// Where the decision to exclude a link came from
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize)]
+pub enum ExclusionSource {
+    /// The link matches the exclusion rules of the current run, e.g. an
+    /// `--exclude` pattern or `--exclude-mail`
+    Config,
+    /// The link was recorded as excluded in the cache of a previous run
+    Cache,
+}
+
+const fn is_permanent_redirect(code: StatusCode) -> bool {
+    matches!(
+        code,
+        StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+    )
+}
+
+const fn is_temporary_redirect(code: StatusCode) -> bool {
+    matches!(
+        code,
+        StatusCode::FOUND | StatusCode::SEE_OTHER | StatusCode::TEMPORARY_REDIRECT
+    )
+}
+
+/// Category of a redirect, so that permanent redirects (which should be
+/// fixed) stand out from temporary ones
+const fn redirect_category(code: StatusCode) -> &'static str {
+    if is_permanent_redirect(code) {
+        "Permanent redirect"
+    } else if is_temporary_redirect(code) {
+        "Temporary redirect"
+    } else {
+        "Redirect"
+    }
+}
+
+/// The alternate form (`{:#}`) only prints the status category,
+/// e.g. `OK` or `Failed`, without any status code or error details
 impl Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return f.write_str(match self {
+                Status::Ok(_) => "OK",
+                Status::Redirected(code) => redirect_category(*code),
+                Status::UnknownStatusCode(_) => "Unknown status",
+                Status::Excluded => "Excluded",
+                Status::Timeout(_) => "Timeout",
+                Status::Unsupported(_) => "Unsupported",
+                Status::Error(_) => "Failed",
+                Status::Cached(_) => "Cached",
+                Status::Warning(_) => "Warning",
+                Status::Skipped(_) => "Skipped",
+            });
+        }
         match self {
             Status::Ok(c) => write!(f, "OK ({c})"),
-            Status::Redirected(c) => write!(f, "Redirect ({c})"),
+            Status::Redirected(c) => write!(f, "{} ({c})", redirect_category(*c)),
             Status::UnknownStatusCode(c) => write!(f, "Unknown status: {c}"),
             Status::Excluded => f.write_str("Excluded"),
             Status::Timeout(Some(c)) => write!(f, "Timeout ({c})"),
@@ -53,6 +115,8 @@ impl Display for Status {
             Status::Unsupported(e) => write!(f, "Unsupported: {e}"),
             Status::Error(e) => write!(f, "Failed: {e}"),
             Status::Cached(s) => write!(f, "Cached: {s}"),
+            Status::Warning(e) => write!(f, "Warning: {e}"),
+            Status::Skipped(e) => write!(f, "Skipped: {e}"),
         }
     }
 }
@@ -67,6 +131,52 @@ impl Serialize for Status {
 }
 
 impl Status {
+    /// Restore a status from its serialized (display) form
+    ///
+    /// Statuses with an error take it from `error`, as errors can't be
+    /// restored from their message alone. Reports of older releases don't
+    /// contain the error, so its message is taken from the status then. The
+    /// status codes of cached results are not part of the display form, so
+    /// they get lost.
+    pub(crate) fn from_serialized(status: &str, error: Option<ErrorKind>) -> Option<Self> {
+        // Status codes are displayed with their reason, e.g. `404 Not Found`
+        let parse_code = |s: &str| {
+            let code = s.split_whitespace().next()?.parse().ok()?;
+            StatusCode::from_u16(code).ok()
+        };
+        let (category, details) = status.split_once(": ").unwrap_or((status, ""));
+        let error = || {
+            error.unwrap_or_else(|| ErrorKind::Reported(ReportedError::new(None, details.into())))
+        };
+        let status = match category {
+            "Excluded" => Status::Excluded,
+            "Timeout" => Status::Timeout(None),
+            "Unknown status" => Status::UnknownStatusCode(parse_code(details)?),
+            "Failed" => Status::Error(error()),
+            "Unsupported" => Status::Unsupported(error()),
+            "Warning" => Status::Warning(Box::new(error())),
+            "Skipped" => Status::Skipped(Box::new(error())),
+            "Cached" => Status::Cached(match details {
+                "OK (cached)" => CacheStatus::Ok(StatusCode::OK.as_u16()),
+                "Excluded (cached)" => CacheStatus::Excluded,
+                "Unsupported (cached)" => CacheStatus::Unsupported,
+                _ => CacheStatus::Error(None),
+            }),
+            _ => {
+                let (category, code) = status.strip_suffix(')')?.split_once(" (")?;
+                match category {
+                    "OK" => Status::Ok(parse_code(code)?),
+                    "Redirect" | "Permanent redirect" | "Temporary redirect" => {
+                        Status::Redirected(parse_code(code)?)
+                    }
+                    "Timeout" => Status::Timeout(Some(parse_code(code)?)),
+                    _ => return None,
+                }
+            }
+        };
+        Some(status)
+    }
+
     #[must_use]
     /// Create a status object from a response and the set of accepted status codes
     pub fn new(response: &Response, accepted: Option<HashSet<StatusCode>>) -> Self {
@@ -111,6 +221,49 @@ impl Status {
         )
     }
 
+    #[inline]
+    #[must_use]
+    /// Returns where the decision to exclude the link came from, or `None` if
+    /// it wasn't excluded
+    pub const fn exclusion_source(&self) -> Option<ExclusionSource> {
+        match self {
+            Status::Excluded => Some(ExclusionSource::Config),
+            Status::Cached(CacheStatus::Excluded) => Some(ExclusionSource::Cache),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns `true` if a redirect (which was not followed) is permanent,
+    /// i.e. `301 Moved Permanently` or `308 Permanent Redirect`. The link
+    /// should be updated to the new location.
+    pub const fn is_permanent_redirect(&self) -> bool {
+        matches!(self, Status::Redirected(code) if is_permanent_redirect(*code))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns `true` if a redirect (which was not followed) is temporary,
+    /// i.e. `302 Found`, `303 See Other` or `307 Temporary Redirect`
+    pub const fn is_temporary_redirect(&self) -> bool {
+        matches!(self, Status::Redirected(code) if is_temporary_redirect(*code))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns `true` if the check succeeded with a warning
+    pub const fn is_warning(&self) -> bool {
+        matches!(self, Status::Warning(_))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns `true` if the link was not checked at all
+    pub const fn is_skipped(&self) -> bool {
+        matches!(self, Status::Skipped(_))
+    }
+
     #[inline]
     #[must_use]
     /// Returns `true` if a check took too long to complete
@@ -128,6 +281,69 @@ impl Status {
         )
     }
 
+    #[inline]
+    #[must_use]
+    /// Returns the error of a failed or unsupported check, the issue of a
+    /// warning or the reason for skipping a check
+    pub const fn error(&self) -> Option<&ErrorKind> {
+        match self {
+            Status::Error(e) | Status::Unsupported(e) => Some(e),
+            Status::Warning(e) | Status::Skipped(e) => Some(&**e),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns `true` if the check failed, because the server requires
+    /// authentication (`401 Unauthorized` or `407 Proxy Authentication
+    /// Required`)
+    ///
+    /// Unlike other client errors, this doesn't mean that the link is
+    /// broken. It can be checked once credentials are configured (e.g. basic
+    /// auth or an `Authorization` header).
+    pub fn is_auth_required(&self) -> bool {
+        let code = match self {
+            Status::Error(ErrorKind::NetworkRequest(e)) => e.status(),
+            Status::Cached(CacheStatus::Error(Some(code))) => StatusCode::from_u16(*code).ok(),
+            _ => None,
+        };
+        matches!(
+            code,
+            Some(StatusCode::UNAUTHORIZED | StatusCode::PROXY_AUTHENTICATION_REQUIRED)
+        )
+    }
+
+    #[must_use]
+    /// Returns `true` if the check might succeed when tried again
+    ///
+    /// This is the case for timeouts, server errors (5xx) and network errors
+    /// without a response (e.g. DNS failures or reset connections), which
+    /// are usually temporary. Client errors (4xx), unsupported or excluded
+    /// URIs and successful checks are not retryable.
+    pub fn retryable(&self) -> bool {
+        self.retryable_if(|code| code.is_server_error())
+    }
+
+    #[must_use]
+    /// Returns `true` if the check might succeed when tried again, with a
+    /// custom set of retryable status codes instead of server errors (5xx)
+    ///
+    /// Timeouts and network errors without a response are always retryable.
+    pub fn retryable_on(&self, codes: &HashSet<StatusCode>) -> bool {
+        self.retryable_if(|code| codes.contains(&code))
+    }
+
+    fn retryable_if(&self, retryable: impl Fn(StatusCode) -> bool) -> bool {
+        match self {
+            Status::Timeout(_) | Status::Error(ErrorKind::Dns { .. }) => true,
+            Status::UnknownStatusCode(code) => retryable(*code),
+            Status::Error(ErrorKind::NetworkRequest(e)) => e
+                .status()
+                .map_or_else(|| e.is_connect() || e.is_request(), retryable),
+            _ => false,
+        }
+    }
+
     #[must_use]
     /// Return a unicode icon to visualize the status
     pub const fn icon(&self) -> &str {
@@ -140,6 +356,39 @@ impl Status {
             Status::Timeout(_) => ICON_TIMEOUT,
             Status::Unsupported(_) => ICON_UNSUPPORTED,
             Status::Cached(_) => ICON_CACHED,
+            Status::Warning(_) => ICON_WARNING,
+            Status::Skipped(_) => ICON_SKIPPED,
+        }
+    }
+
+    #[must_use]
+    /// Merge the statuses of the same URI from different runs (e.g. shards
+    /// of a CI job), keeping the more severe one.
+    ///
+    /// Errors win over timeouts, which win over redirects and unknown status
+    /// codes, then warnings, successful checks, excluded or skipped links and
+    /// finally unsupported links. Fresh results win over cached ones of the
+    /// same kind. If both are equally severe, `self` is kept.
+    pub fn merge(self, other: Status) -> Status {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Rank of the status for [`Status::merge`]
+    const fn severity(&self) -> u8 {
+        match self {
+            Status::Error(_) => 8,
+            Status::Cached(CacheStatus::Error(_)) => 7,
+            Status::Timeout(_) => 6,
+            Status::Redirected(_) | Status::UnknownStatusCode(_) => 5,
+            Status::Warning(_) => 4,
+            Status::Ok(_) => 3,
+            Status::Cached(CacheStatus::Ok(_)) => 2,
+            Status::Excluded | Status::Skipped(_) | Status::Cached(CacheStatus::Excluded) => 1,
+            Status::Unsupported(_) | Status::Cached(CacheStatus::Unsupported) => 0,
         }
     }
 
@@ -158,6 +407,7 @@ impl Status {
                     Some(code) => code.as_str().to_string(),
                     None => "ERR".to_string(),
                 },
+                ErrorKind::Archived(code, _) => code.as_str().to_string(),
                 _ => "ERR".to_string(),
             },
             Status::Timeout(code) => match code {
@@ -165,6 +415,8 @@ impl Status {
                 None => "TIMEOUT".to_string(),
             },
             Status::Unsupported(_) => "IGNORED".to_string(),
+            Status::Warning(_) => "WARN".to_string(),
+            Status::Skipped(_) => "SKIPPED".to_string(),
             Status::Cached(cache_status) => match cache_status {
                 CacheStatus::Ok(code) => code.to_string(),
                 CacheStatus::Error(code) => match code {
@@ -188,6 +440,13 @@ impl From<reqwest::Error> for Status {
     fn from(e: reqwest::Error) -> Self {
         if e.is_timeout() {
             Self::Timeout(e.status())
+        } else if is_dns_error(&e) {
+            let host = e
+                .url()
+                .and_then(|url| url.host_str())
+                .unwrap_or_default()
+                .to_string();
+            Self::Error(ErrorKind::Dns { host })
         } else if e.is_builder() {
             Self::Unsupported(ErrorKind::BuildRequestClient(e))
         } else if e.is_body() || e.is_decode() {
@@ -203,3 +462,283 @@ impl From<CacheStatus> for Status {
         Self::Cached(s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+
+    #[test]
+    fn test_retryable() {
+        assert!(Status::Timeout(None).retryable());
+        assert!(Status::Timeout(Some(StatusCode::GATEWAY_TIMEOUT)).retryable());
+        assert!(Status::UnknownStatusCode(StatusCode::SERVICE_UNAVAILABLE).retryable());
+
+        assert!(!Status::UnknownStatusCode(StatusCode::NOT_FOUND).retryable());
+        assert!(!Status::Ok(StatusCode::OK).retryable());
+        assert!(!Status::Redirected(StatusCode::MOVED_PERMANENTLY).retryable());
+        assert!(!Status::Excluded.retryable());
+        assert!(!Status::Unsupported(ErrorKind::InvalidUrlHost).retryable());
+        assert!(!Status::Error(ErrorKind::MissingGitHubToken).retryable());
+        assert!(!Status::Cached(CacheStatus::Error(Some(503))).retryable());
+        assert!(Status::Error(ErrorKind::Dns {
+            host: "example.invalid".to_string()
+        })
+        .retryable());
+    }
+
+    #[test]
+    fn test_retryable_on() {
+        let codes = HashSet::from([StatusCode::FORBIDDEN, StatusCode::TOO_MANY_REQUESTS]);
+        assert!(Status::UnknownStatusCode(StatusCode::FORBIDDEN).retryable_on(&codes));
+        assert!(!Status::UnknownStatusCode(StatusCode::INTERNAL_SERVER_ERROR).retryable_on(&codes));
+        // Timeouts are always retryable
+        assert!(Status::Timeout(None).retryable_on(&HashSet::new()));
+        assert!(Status::Error(ErrorKind::Dns {
+            host: "example.invalid".to_string()
+        })
+        .retryable_on(&HashSet::new()));
+        assert!(!Status::Ok(StatusCode::OK).retryable_on(&HashSet::from([StatusCode::OK])));
+    }
+
+    #[tokio::test]
+    async fn test_retryable_server_error() {
+        let client = crate::ClientBuilder::builder()
+            .max_retries(0u64)
+            .build()
+            .client()
+            .unwrap();
+
+        let mock_server = crate::mock_server!(StatusCode::INTERNAL_SERVER_ERROR);
+        let res = client.check(mock_server.uri()).await.unwrap();
+        assert!(res.status().retryable());
+
+        let mock_server = crate::mock_server!(StatusCode::NOT_FOUND);
+        let res = client.check(mock_server.uri()).await.unwrap();
+        assert!(!res.status().retryable());
+
+        // Refused connections are usually temporary, too
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let res = client
+            .check(format!("http://127.0.0.1:{port}"))
+            .await
+            .unwrap();
+        assert!(res.status().retryable());
+        assert!(res.status().retryable_on(&HashSet::new()));
+    }
+
+    #[tokio::test]
+    async fn test_auth_required() {
+        let client = crate::ClientBuilder::builder()
+            .max_retries(0u64)
+            .build()
+            .client()
+            .unwrap();
+        for code in [
+            StatusCode::UNAUTHORIZED,
+            StatusCode::PROXY_AUTHENTICATION_REQUIRED,
+        ] {
+            let mock_server = crate::mock_server!(code);
+            let res = client.check(mock_server.uri()).await.unwrap();
+            assert!(res.status().is_failure());
+            assert!(res.status().is_auth_required());
+            assert_eq!(res.status().code(), code.as_str());
+        }
+
+        let mock_server = crate::mock_server!(StatusCode::FORBIDDEN);
+        let res = client.check(mock_server.uri()).await.unwrap();
+        assert!(!res.status().is_auth_required());
+
+        assert!(Status::Cached(CacheStatus::Error(Some(401))).is_auth_required());
+        assert!(!Status::Cached(CacheStatus::Error(None)).is_auth_required());
+        assert!(!Status::Ok(StatusCode::OK).is_auth_required());
+    }
+
+    #[test]
+    fn test_warning() {
+        let warning = Status::Warning(Box::new(ErrorKind::InvalidUrlHost));
+        assert!(warning.is_warning());
+        assert!(!warning.is_success());
+        assert!(!warning.is_failure());
+        assert!(!warning.retryable());
+        assert_eq!(warning.icon(), ICON_WARNING);
+        assert_eq!(warning.code(), "WARN");
+        assert_eq!(warning.error().map(ErrorKind::kind), Some("InvalidUrlHost"));
+    }
+
+    #[test]
+    fn test_error() {
+        fn kind(status: &Status) -> Option<&str> {
+            status.error().map(ErrorKind::kind)
+        }
+        assert_eq!(
+            kind(&Status::Error(ErrorKind::InvalidUrlHost)),
+            Some("InvalidUrlHost")
+        );
+        assert_eq!(
+            kind(&Status::Unsupported(ErrorKind::InvalidUrlHost)),
+            Some("InvalidUrlHost")
+        );
+        assert_eq!(
+            kind(&Status::Warning(Box::new(ErrorKind::InvalidUrlHost))),
+            Some("InvalidUrlHost")
+        );
+        assert_eq!(
+            kind(&Status::Skipped(Box::new(ErrorKind::TimeBudgetExceeded(
+                std::time::Duration::from_secs(1)
+            )))),
+            Some("TimeBudgetExceeded")
+        );
+
+        for status in [
+            Status::Ok(StatusCode::OK),
+            Status::Redirected(StatusCode::MOVED_PERMANENTLY),
+            Status::UnknownStatusCode(StatusCode::from_u16(999).unwrap()),
+            Status::Timeout(None),
+            Status::Excluded,
+            Status::Cached(CacheStatus::Error(Some(404))),
+        ] {
+            assert_eq!(status.error(), None, "{status}");
+        }
+    }
+
+    #[test]
+    fn test_exclusion_source() {
+        assert_eq!(
+            Status::Excluded.exclusion_source(),
+            Some(ExclusionSource::Config)
+        );
+        assert_eq!(
+            Status::Cached(CacheStatus::Excluded).exclusion_source(),
+            Some(ExclusionSource::Cache)
+        );
+        assert_eq!(Status::Ok(StatusCode::OK).exclusion_source(), None);
+        assert_eq!(
+            Status::Cached(CacheStatus::Ok(200)).exclusion_source(),
+            None
+        );
+        let skipped = Status::Skipped(Box::new(ErrorKind::TimeBudgetExceeded(
+            std::time::Duration::from_secs(1),
+        )));
+        assert_eq!(skipped.exclusion_source(), None);
+    }
+
+    #[test]
+    fn test_from_serialized() {
+        for status in [
+            Status::Ok(StatusCode::OK),
+            Status::Redirected(StatusCode::PERMANENT_REDIRECT),
+            Status::Redirected(StatusCode::FOUND),
+            Status::Redirected(StatusCode::MULTIPLE_CHOICES),
+            Status::UnknownStatusCode(StatusCode::from_u16(999).unwrap()),
+            Status::Excluded,
+            Status::Timeout(Some(StatusCode::GATEWAY_TIMEOUT)),
+            Status::Timeout(None),
+            Status::Cached(CacheStatus::Excluded),
+        ] {
+            assert_eq!(
+                Status::from_serialized(&status.to_string(), None),
+                Some(status)
+            );
+        }
+
+        let error = ErrorKind::Reported(ReportedError::new(
+            Some("InvalidUrlHost"),
+            "URL is missing a host".to_string(),
+        ));
+        let status = Status::Error(ErrorKind::InvalidUrlHost);
+        let deserialized = Status::from_serialized(&status.to_string(), Some(error)).unwrap();
+        assert_eq!(deserialized.to_string(), status.to_string());
+        assert_eq!(
+            deserialized.error().map(ErrorKind::kind),
+            Some("InvalidUrlHost")
+        );
+
+        // Reports of older releases only contain the message
+        let deserialized = Status::from_serialized(&status.to_string(), None).unwrap();
+        assert_eq!(deserialized.to_string(), status.to_string());
+        assert_eq!(deserialized.error().map(ErrorKind::kind), Some("Reported"));
+        assert_eq!(Status::from_serialized("Unknown", None), None);
+    }
+
+    #[test]
+    fn test_merge() {
+        let ok = || Status::Ok(StatusCode::OK);
+        let error = || Status::Error(ErrorKind::InvalidUrlHost);
+
+        assert_eq!(ok().merge(error()), error());
+        assert_eq!(error().merge(ok()), error());
+        assert_eq!(Status::Timeout(None).merge(ok()), Status::Timeout(None));
+        assert_eq!(Status::Excluded.merge(ok()), ok());
+        assert_eq!(
+            Status::Cached(CacheStatus::Error(Some(404))).merge(error()),
+            error()
+        );
+        assert_eq!(Status::Cached(CacheStatus::Ok(200)).merge(ok()), ok());
+        // Equally severe statuses keep the first one
+        assert_eq!(
+            Status::Ok(StatusCode::NO_CONTENT).merge(ok()),
+            Status::Ok(StatusCode::NO_CONTENT)
+        );
+    }
+
+    #[test]
+    fn test_alternate_display() {
+        let cases = [
+            (Status::Ok(StatusCode::OK), "OK (200 OK)", "OK"),
+            (
+                Status::Redirected(StatusCode::MOVED_PERMANENTLY),
+                "Permanent redirect (301 Moved Permanently)",
+                "Permanent redirect",
+            ),
+            (
+                Status::Redirected(StatusCode::FOUND),
+                "Temporary redirect (302 Found)",
+                "Temporary redirect",
+            ),
+            (
+                Status::Redirected(StatusCode::MULTIPLE_CHOICES),
+                "Redirect (300 Multiple Choices)",
+                "Redirect",
+            ),
+            (Status::Excluded, "Excluded", "Excluded"),
+            (
+                Status::Timeout(Some(StatusCode::REQUEST_TIMEOUT)),
+                "Timeout (408 Request Timeout)",
+                "Timeout",
+            ),
+            (
+                Status::Error(ErrorKind::InvalidUrlHost),
+                "Failed: URL is missing a host",
+                "Failed",
+            ),
+            (
+                Status::Cached(CacheStatus::Error(Some(404))),
+                "Cached: Error (cached)",
+                "Cached",
+            ),
+            (
+                Status::Warning(Box::new(ErrorKind::InvalidUrlHost)),
+                "Warning: URL is missing a host",
+                "Warning",
+            ),
+            (
+                Status::Skipped(Box::new(ErrorKind::TimeBudgetExceeded(
+                    std::time::Duration::from_secs(10),
+                ))),
+                "Skipped: Not checked, time budget of 10s exceeded",
+                "Skipped",
+            ),
+        ];
+
+        for (status, default, alternate) in cases {
+            assert_eq!(format!("{status}"), default);
+            assert_eq!(format!("{status:#}"), alternate);
+        }
+    }
+}
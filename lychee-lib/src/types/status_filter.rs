@@ -0,0 +1,229 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{CacheStatus, ErrorKind, Status};
+
+type Predicate = dyn Fn(&Status) -> bool + Send + Sync;
+
+/// A composable predicate over [`Status`] values.
+///
+/// This is helpful for post-filtering check results without having to
+/// re-implement the matching logic for every use-case.
+///
+/// ```
+/// use lychee_lib::{Status, StatusFilter};
+/// use http::StatusCode;
+///
+/// // All failures except for "429 Too Many Requests"
+/// let filter = StatusFilter::failures().and_not_code(429);
+/// assert!(filter.matches(&Status::Error(lychee_lib::ErrorKind::InvalidUrlHost)));
+/// assert!(!filter.matches(&Status::Ok(StatusCode::OK)));
+///
+/// // Successful requests or redirects
+/// let filter = StatusFilter::successes().or(StatusFilter::redirects());
+/// assert!(filter.matches(&Status::Redirected(StatusCode::MOVED_PERMANENTLY)));
+/// ```
+#[derive(Clone)]
+pub struct StatusFilter {
+    predicate: Arc<Predicate>,
+}
+
+impl StatusFilter {
+    /// Create a new filter from an arbitrary predicate
+    #[must_use]
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&Status) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    /// Match every status
+    #[must_use]
+    pub fn any() -> Self {
+        Self::new(|_| true)
+    }
+
+    /// Match successful checks (see [`Status::is_success`])
+    #[must_use]
+    pub fn successes() -> Self {
+        Self::new(Status::is_success)
+    }
+
+    /// Match failed checks (see [`Status::is_failure`])
+    #[must_use]
+    pub fn failures() -> Self {
+        Self::new(Status::is_failure)
+    }
+
+    /// Match excluded checks (see [`Status::is_excluded`])
+    #[must_use]
+    pub fn excluded() -> Self {
+        Self::new(Status::is_excluded)
+    }
+
+    /// Match timed out checks (see [`Status::is_timeout`])
+    #[must_use]
+    pub fn timeouts() -> Self {
+        Self::new(Status::is_timeout)
+    }
+
+    /// Match unsupported checks (see [`Status::is_unsupported`])
+    #[must_use]
+    pub fn unsupported() -> Self {
+        Self::new(Status::is_unsupported)
+    }
+
+    /// Match checks with warnings (see [`Status::is_warning`])
+    #[must_use]
+    pub fn warnings() -> Self {
+        Self::new(Status::is_warning)
+    }
+
+    /// Match redirects
+    #[must_use]
+    pub fn redirects() -> Self {
+        Self::new(|status| matches!(status, Status::Redirected(_)))
+    }
+
+    /// Match any status carrying the given HTTP status code
+    #[must_use]
+    pub fn code(code: u16) -> Self {
+        Self::new(move |status| status_code(status) == Some(code))
+    }
+
+    /// Match if either this filter or `other` matches
+    #[must_use]
+    pub fn or(self, other: Self) -> Self {
+        Self::new(move |status| self.matches(status) || other.matches(status))
+    }
+
+    /// Match if both this filter and `other` match
+    #[must_use]
+    pub fn and(self, other: Self) -> Self {
+        Self::new(move |status| self.matches(status) && other.matches(status))
+    }
+
+    /// Match if this filter matches, but `other` doesn't
+    #[must_use]
+    pub fn and_not(self, other: Self) -> Self {
+        self.and(other.negate())
+    }
+
+    /// Invert this filter
+    #[must_use]
+    pub fn negate(self) -> Self {
+        Self::new(move |status| !self.matches(status))
+    }
+
+    /// Additionally match statuses with the given HTTP status code
+    #[must_use]
+    pub fn or_code(self, code: u16) -> Self {
+        self.or(Self::code(code))
+    }
+
+    /// Don't match statuses with the given HTTP status code
+    #[must_use]
+    pub fn and_not_code(self, code: u16) -> Self {
+        self.and_not(Self::code(code))
+    }
+
+    /// Returns `true` if the given status matches this filter
+    #[must_use]
+    pub fn matches(&self, status: &Status) -> bool {
+        (self.predicate)(status)
+    }
+
+    /// Convert the filter into a plain closure, e.g. for use with
+    /// [`Iterator::filter`]
+    pub fn into_fn(self) -> impl Fn(&Status) -> bool + Send + Sync + Clone {
+        move |status| self.matches(status)
+    }
+}
+
+impl Debug for StatusFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatusFilter").finish_non_exhaustive()
+    }
+}
+
+/// Extract the numeric HTTP status code of a status (if any)
+fn status_code(status: &Status) -> Option<u16> {
+    match status {
+        Status::Ok(code) | Status::Redirected(code) | Status::UnknownStatusCode(code) => {
+            Some(code.as_u16())
+        }
+        Status::Timeout(code) => code.map(|code| code.as_u16()),
+        Status::Error(
+            ErrorKind::NetworkRequest(e)
+            | ErrorKind::ReadResponseBody(e)
+            | ErrorKind::BuildRequestClient(e),
+        ) => e.status().map(|code| code.as_u16()),
+        Status::Cached(CacheStatus::Ok(code)) => Some(*code),
+        Status::Cached(CacheStatus::Error(code)) => *code,
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+
+    #[test]
+    fn test_simple_filters() {
+        let ok = Status::Ok(StatusCode::OK);
+        let error = Status::Error(ErrorKind::InvalidUrlHost);
+
+        assert!(StatusFilter::any().matches(&ok));
+        assert!(StatusFilter::successes().matches(&ok));
+        assert!(!StatusFilter::successes().matches(&error));
+        assert!(StatusFilter::failures().matches(&error));
+        assert!(StatusFilter::excluded().matches(&Status::Excluded));
+        assert!(StatusFilter::timeouts().matches(&Status::Timeout(None)));
+        assert!(
+            StatusFilter::warnings().matches(&Status::Warning(Box::new(ErrorKind::InvalidUrlHost)))
+        );
+    }
+
+    #[test]
+    fn test_code_filter() {
+        let filter = StatusFilter::code(429);
+        assert!(filter.matches(&Status::Cached(CacheStatus::Error(Some(429)))));
+        assert!(filter.matches(&Status::UnknownStatusCode(StatusCode::TOO_MANY_REQUESTS)));
+        assert!(!filter.matches(&Status::Ok(StatusCode::OK)));
+        assert!(!filter.matches(&Status::Excluded));
+    }
+
+    #[test]
+    fn test_composed_filters() {
+        let filter = StatusFilter::failures().and_not_code(404);
+        assert!(filter.matches(&Status::Cached(CacheStatus::Error(Some(500)))));
+        assert!(!filter.matches(&Status::Cached(CacheStatus::Error(Some(404)))));
+        assert!(!filter.matches(&Status::Ok(StatusCode::OK)));
+
+        let filter = StatusFilter::successes()
+            .or(StatusFilter::redirects())
+            .or_code(429);
+        assert!(filter.matches(&Status::Ok(StatusCode::OK)));
+        assert!(filter.matches(&Status::Redirected(StatusCode::FOUND)));
+        assert!(filter.matches(&Status::Timeout(Some(StatusCode::TOO_MANY_REQUESTS))));
+        assert!(!filter.matches(&Status::Timeout(None)));
+        assert!(!filter.clone().negate().matches(&Status::Ok(StatusCode::OK)));
+    }
+
+    #[test]
+    fn test_into_fn() {
+        let statuses = [
+            Status::Ok(StatusCode::OK),
+            Status::Excluded,
+            Status::Error(ErrorKind::InvalidUrlHost),
+        ];
+        let filter = StatusFilter::successes()
+            .or(StatusFilter::excluded())
+            .into_fn();
+        assert_eq!(statuses.iter().filter(|s| filter(s)).count(), 2);
+    }
+}
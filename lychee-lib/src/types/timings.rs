@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Durations of the phases of a website check, for performance diagnosis
+///
+/// Phases which couldn't be measured are `None`. reqwest doesn't expose the
+/// DNS lookup and connection setup, so both are part of `ttfb` for new
+/// connections.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "TimingsMillis", into = "TimingsMillis")]
+pub struct Timings {
+    /// Time from sending the request until the response headers arrived
+    /// (time to first byte)
+    pub ttfb: Option<Duration>,
+    /// Time of the whole check, including reading the body if needed
+    pub total: Option<Duration>,
+}
+
+/// Serialized form of [`Timings`], in whole milliseconds
+#[derive(Serialize, Deserialize)]
+struct TimingsMillis {
+    #[serde(rename = "ttfb_ms", default, skip_serializing_if = "Option::is_none")]
+    ttfb: Option<u64>,
+    #[serde(rename = "total_ms", default, skip_serializing_if = "Option::is_none")]
+    total: Option<u64>,
+}
+
+impl From<Timings> for TimingsMillis {
+    fn from(timings: Timings) -> Self {
+        let millis =
+            |d: Option<Duration>| d.map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+        Self {
+            ttfb: millis(timings.ttfb),
+            total: millis(timings.total),
+        }
+    }
+}
+
+impl From<TimingsMillis> for Timings {
+    fn from(timings: TimingsMillis) -> Self {
+        Self {
+            ttfb: timings.ttfb.map(Duration::from_millis),
+            total: timings.total.map(Duration::from_millis),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let timings = Timings {
+            ttfb: Some(Duration::from_millis(12)),
+            total: Some(Duration::from_micros(15_900)),
+        };
+        let value = serde_json::to_value(timings).unwrap();
+        assert_eq!(value, serde_json::json!({"ttfb_ms": 12, "total_ms": 15}));
+
+        let timings: Timings = serde_json::from_value(value).unwrap();
+        assert_eq!(timings.ttfb, Some(Duration::from_millis(12)));
+        assert_eq!(timings.total, Some(Duration::from_millis(15)));
+
+        let timings: Timings = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(timings, Timings::default());
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use reqwest::Url;
+
+/// Policy to unify links which only differ by a trailing slash, like
+/// `https://example.com/page` and `https://example.com/page/`, so that they
+/// only get checked and reported once. The policy decides which of the two
+/// forms is canonical.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TrailingSlash {
+    /// Add a trailing slash, unless the last path segment looks like a file
+    /// name (e.g. `index.html`)
+    Add,
+    /// Remove the trailing slash, except from the root path
+    Remove,
+}
+
+impl TrailingSlash {
+    /// Apply the policy to the path of a website URL. Other URLs (e.g. files
+    /// or mail addresses) are left unchanged.
+    pub fn apply(self, url: &mut Url) {
+        if !matches!(url.scheme(), "http" | "https") {
+            return;
+        }
+        let path = url.path();
+        let path = match self {
+            Self::Add if !path.ends_with('/') && Path::new(path).extension().is_none() => {
+                format!("{path}/")
+            }
+            Self::Remove if path.len() > 1 && path.ends_with('/') => {
+                path.trim_end_matches('/').to_string()
+            }
+            _ => return,
+        };
+        url.set_path(&path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(policy: TrailingSlash, url: &str) -> String {
+        let mut url = Url::parse(url).unwrap();
+        policy.apply(&mut url);
+        url.to_string()
+    }
+
+    #[test]
+    fn test_add() {
+        let add = |url| apply(TrailingSlash::Add, url);
+        assert_eq!(add("https://example.com/page"), "https://example.com/page/");
+        assert_eq!(
+            add("https://example.com/page/"),
+            "https://example.com/page/"
+        );
+        assert_eq!(
+            add("https://example.com/page?q=1#top"),
+            "https://example.com/page/?q=1#top"
+        );
+        assert_eq!(add("https://example.com"), "https://example.com/");
+        assert_eq!(
+            add("https://example.com/index.html"),
+            "https://example.com/index.html"
+        );
+        assert_eq!(add("file:///tmp/page"), "file:///tmp/page");
+    }
+
+    #[test]
+    fn test_remove() {
+        let remove = |url| apply(TrailingSlash::Remove, url);
+        assert_eq!(
+            remove("https://example.com/page/"),
+            "https://example.com/page"
+        );
+        assert_eq!(
+            remove("https://example.com/page"),
+            "https://example.com/page"
+        );
+        assert_eq!(
+            remove("https://example.com/page/?q=1"),
+            "https://example.com/page?q=1"
+        );
+        assert_eq!(remove("https://example.com/"), "https://example.com/");
+        assert_eq!(remove("mailto:foo@example.com"), "mailto:foo@example.com");
+    }
+}
@@ -1,3 +1,5 @@
 pub(crate) mod github;
+pub(crate) mod opaque;
 pub(crate) mod raw;
+pub(crate) mod slack;
 pub(crate) mod valid;
@@ -0,0 +1,86 @@
+/// Known URI schemes which are syntactically valid, but can't be checked,
+/// because they don't point to a resource which could be requested, along
+/// with the reason why
+///
+/// URIs with other unknown schemes are unsupported instead.
+const OPAQUE_SCHEMES: &[(&str, &str)] = &[
+    (
+        "magnet",
+        "magnet links identify files in peer-to-peer networks",
+    ),
+    ("tel", "phone numbers can't be checked"),
+    ("sms", "phone numbers can't be checked"),
+    ("geo", "geographic locations can't be checked"),
+    ("data", "data URIs contain the resource itself"),
+    (
+        "chrome-extension",
+        "browser extension pages are only available inside the browser",
+    ),
+    (
+        "moz-extension",
+        "browser extension pages are only available inside the browser",
+    ),
+    (
+        "safari-web-extension",
+        "browser extension pages are only available inside the browser",
+    ),
+    ("chrome", "browser-internal pages can't be checked"),
+    ("edge", "browser-internal pages can't be checked"),
+    ("brave", "browser-internal pages can't be checked"),
+    ("opera", "browser-internal pages can't be checked"),
+    ("vivaldi", "browser-internal pages can't be checked"),
+    ("about", "browser-internal pages can't be checked"),
+    (
+        "view-source",
+        "view-source links only show the source of a page in the browser",
+    ),
+    (
+        "vscode",
+        "application links are only handled by the installed application",
+    ),
+    (
+        "zoommtg",
+        "application links are only handled by the installed application",
+    ),
+];
+
+/// Get the reason why URIs with the given scheme can't be checked, or `None`
+/// if the scheme is not a known opaque scheme
+pub(crate) fn reason(scheme: &str) -> Option<&'static str> {
+    OPAQUE_SCHEMES
+        .iter()
+        .find(|(opaque, _)| opaque.eq_ignore_ascii_case(scheme))
+        .map(|(_, reason)| *reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reason() {
+        assert_eq!(
+            reason("magnet"),
+            Some("magnet links identify files in peer-to-peer networks")
+        );
+        assert_eq!(reason("TEL"), Some("phone numbers can't be checked"));
+        assert_eq!(
+            reason("chrome-extension"),
+            Some("browser extension pages are only available inside the browser")
+        );
+        assert_eq!(
+            reason("vscode"),
+            Some("application links are only handled by the installed application")
+        );
+        assert_eq!(
+            reason("about"),
+            Some("browser-internal pages can't be checked")
+        );
+        assert_eq!(
+            reason("view-source"),
+            Some("view-source links only show the source of a page in the browser")
+        );
+        assert_eq!(reason("foo"), None);
+        assert_eq!(reason("https"), None);
+    }
+}
@@ -24,6 +24,12 @@ impl RawUri {
     pub(crate) fn is_anchor(&self) -> bool {
         self.text.starts_with('#')
     }
+
+    /// Check if the URI has no target, e.g. `href=""`, `href="   "` or a
+    /// bare fragment (`href="#"`)
+    pub(crate) fn is_empty(&self) -> bool {
+        matches!(self.text.trim(), "" | "#")
+    }
 }
 impl Display for RawUri {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
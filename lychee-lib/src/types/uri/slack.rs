@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use crate::{ErrorKind, Result, Uri};
+
+/// Check that a `slack://` deep link is well-formed
+///
+/// Deep links can't be resolved without the Slack app, so only their shape is
+/// validated against the
+/// [documented formats](https://api.slack.com/reference/deep-linking):
+///
+/// - `slack://open` and `slack://open?team={TEAM_ID}`
+/// - `slack://channel?team={TEAM_ID}&id={CHANNEL_ID}`
+/// - `slack://user?team={TEAM_ID}&id={USER_ID}`
+/// - `slack://file?team={TEAM_ID}&id={FILE_ID}`
+/// - `slack://share-file?team={TEAM_ID}&id={FILE_ID}`
+/// - `slack://app?team={TEAM_ID}&id={APP_ID}`
+pub(crate) fn validate(uri: &Uri) -> Result<()> {
+    check(uri).map_err(|reason| ErrorKind::InvalidSlackUri(uri.clone(), reason.to_string()))
+}
+
+fn check(uri: &Uri) -> std::result::Result<(), &'static str> {
+    if !matches!(uri.url.path(), "" | "/") {
+        return Err("unexpected path");
+    }
+    let params: HashMap<_, _> = uri.url.query_pairs().collect();
+    let team = params.get("team");
+    let id = params.get("id");
+
+    // Valid prefixes of the `id` parameter
+    let id_prefixes: &[char] = match uri.url.host_str() {
+        Some("open") => {
+            return match team {
+                Some(team) if !is_id(team, &['T', 'E']) => Err("invalid team ID"),
+                _ => Ok(()),
+            }
+        }
+        Some("channel") => &['C', 'G', 'D'],
+        Some("user") => &['U', 'W'],
+        Some("file" | "share-file") => &['F'],
+        Some("app") => &['A'],
+        _ => return Err("unknown action"),
+    };
+    match (team, id) {
+        (None, _) => Err("missing team ID"),
+        (_, None) => Err("missing ID"),
+        (Some(team), _) if !is_id(team, &['T', 'E']) => Err("invalid team ID"),
+        (_, Some(id)) if !is_id(id, id_prefixes) => Err("invalid ID"),
+        _ => Ok(()),
+    }
+}
+
+/// Slack IDs consist of uppercase letters and digits, starting with a letter
+/// for the type of the object (e.g. `C` for channels)
+fn is_id(id: &str, prefixes: &[char]) -> bool {
+    id.len() > 1
+        && id.starts_with(prefixes)
+        && id
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate_str(uri: &str) -> std::result::Result<(), &'static str> {
+        check(&Uri::try_from(uri).unwrap())
+    }
+
+    #[test]
+    fn test_valid_slack_uris() {
+        for uri in [
+            "slack://open",
+            "slack://open?team=T12345",
+            "slack://channel?team=T12345&id=C12345",
+            "slack://channel?id=G12345&team=E12345",
+            "slack://user?team=T12345&id=U12345",
+            "slack://file?team=T12345&id=F12345",
+            "slack://share-file?team=T12345&id=F12345",
+            "slack://app?team=T12345&id=A12345&tab=home",
+        ] {
+            assert_eq!(validate_str(uri), Ok(()), "{uri}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_slack_uris() {
+        assert_eq!(
+            validate_str("slack://channel?id=C12345"),
+            Err("missing team ID")
+        );
+        assert_eq!(validate_str("slack://user"), Err("missing team ID"));
+        assert_eq!(validate_str("slack://user?team=T12345"), Err("missing ID"));
+        assert_eq!(
+            validate_str("slack://channel?team=T12345&id=U12345"),
+            Err("invalid ID")
+        );
+        assert_eq!(
+            validate_str("slack://channel?team=t12345&id=C12345"),
+            Err("invalid team ID")
+        );
+        assert_eq!(validate_str("slack://open?team=X"), Err("invalid team ID"));
+        assert_eq!(
+            validate_str("slack://foo?team=T12345"),
+            Err("unknown action")
+        );
+        assert_eq!(
+            validate_str("slack://channel/general?team=T12345&id=C12345"),
+            Err("unexpected path")
+        );
+    }
+
+    #[test]
+    fn test_validate_error() {
+        let uri = Uri::try_from("slack://user").unwrap();
+        assert_eq!(
+            validate(&uri),
+            Err(ErrorKind::InvalidSlackUri(
+                uri,
+                "missing team ID".to_string()
+            ))
+        );
+    }
+}
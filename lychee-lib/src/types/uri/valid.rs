@@ -62,6 +62,67 @@ impl Uri {
         self.url.path_segments()
     }
 
+    #[must_use]
+    /// Returns a normalized copy of the URI, so that equivalent URIs compare
+    /// equal.
+    ///
+    /// The following rules are applied:
+    ///
+    /// - The scheme and host are lowercased
+    ///   (`HTTPS://Example.COM/` becomes `https://example.com/`).
+    /// - Internationalized domain names are converted to their ASCII form as
+    ///   per IDNA (`https://bücher.de/` becomes `https://xn--bcher-kva.de/`).
+    /// - The default port of the scheme is removed
+    ///   (`https://example.com:443/` becomes `https://example.com/`).
+    /// - Dot-segments in the path are collapsed
+    ///   (`https://example.com/a/./b/../c` becomes `https://example.com/a/c`).
+    /// - An empty query or fragment is removed
+    ///   (`https://example.com/?#` becomes `https://example.com/`).
+    /// - If `strip_fragment` is `true`, the fragment is removed
+    ///   (`https://example.com/#top` becomes `https://example.com/`).
+    ///
+    /// The first four rules are applied by the URL parser for URLs with a
+    /// special scheme (like `http`, `https`, `ws` or `file`), so they only
+    /// take effect for other schemes as far as the URL standard allows.
+    /// Paths are not percent-decoded and the query is left as-is otherwise.
+    pub fn normalize(&self, strip_fragment: bool) -> Self {
+        // Parsing the serialization again applies the parser's normalization
+        // rules, e.g. for URLs which were modified after parsing
+        let mut url = Url::parse(self.url.as_str()).unwrap_or_else(|_| self.url.clone());
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+        if strip_fragment || url.fragment() == Some("") {
+            url.set_fragment(None);
+        }
+        Self { url }
+    }
+
+    #[must_use]
+    /// Returns a copy of a mail address with a lowercase domain
+    /// (`mailto:Foo@Example.COM` becomes `mailto:Foo@example.com`), so that
+    /// addresses which only differ in case compare equal.
+    ///
+    /// If `local_part` is `true`, the part before the `@` is lowercased as
+    /// well. Strictly speaking, it is case-sensitive, but almost all mail
+    /// servers treat it as case-insensitive.
+    ///
+    /// Other URIs are returned unchanged.
+    pub fn normalize_mail(&self, local_part: bool) -> Self {
+        if !self.is_mail() {
+            return self.clone();
+        }
+        let path = self.url.path();
+        let path = match path.rsplit_once('@') {
+            Some(_) if local_part => path.to_ascii_lowercase(),
+            Some((local, domain)) => format!("{local}@{}", domain.to_ascii_lowercase()),
+            None => return self.clone(),
+        };
+        let mut url = self.url.clone();
+        url.set_path(&path);
+        Self { url }
+    }
+
     #[must_use]
     /// Returns the IP address (either IPv4 or IPv6) of the URI,
     /// or `None` if it is a domain
@@ -87,6 +148,39 @@ impl Uri {
         self.scheme() == "file"
     }
 
+    #[inline]
+    #[must_use]
+    /// Check if the URI is a WebSocket endpoint (`ws` or `wss` scheme)
+    pub fn is_websocket(&self) -> bool {
+        matches!(self.scheme(), "ws" | "wss")
+    }
+
+    #[inline]
+    #[must_use]
+    /// Check if the URI is a Slack deep link (`slack` scheme)
+    pub fn is_slack(&self) -> bool {
+        self.scheme() == "slack"
+    }
+
+    #[inline]
+    #[must_use]
+    /// If the URI has a known opaque scheme like `magnet`, which is valid but
+    /// can't be checked, return the reason why it can't be checked
+    pub fn opaque_reason(&self) -> Option<&'static str> {
+        super::opaque::reason(self.scheme())
+    }
+
+    #[inline]
+    #[must_use]
+    /// Check if the URI points to GitHub, including its API and raw file
+    /// hosts (e.g. `api.github.com` and `raw.githubusercontent.com`)
+    pub fn is_github(&self) -> bool {
+        matches!(
+            self.domain(),
+            Some("github.com" | "www.github.com" | "api.github.com" | "raw.githubusercontent.com")
+        )
+    }
+
     #[inline]
     #[must_use]
     /// Returns `true` if this is a loopback address.
@@ -229,6 +323,125 @@ mod tests {
         net::{IpAddr, Ipv4Addr, Ipv6Addr},
     };
 
+    fn normalize(uri: &str, strip_fragment: bool) -> String {
+        Uri::try_from(uri)
+            .unwrap()
+            .normalize(strip_fragment)
+            .to_string()
+    }
+
+    #[test]
+    fn test_normalize_case() {
+        assert_eq!(
+            normalize("HTTPS://Example.COM/Path", false),
+            "https://example.com/Path"
+        );
+    }
+
+    #[test]
+    fn test_normalize_idna() {
+        assert_eq!(
+            normalize("https://bücher.de/", false),
+            "https://xn--bcher-kva.de/"
+        );
+    }
+
+    #[test]
+    fn test_normalize_default_port() {
+        assert_eq!(
+            normalize("https://example.com:443/", false),
+            "https://example.com/"
+        );
+        assert_eq!(
+            normalize("http://example.com:80/", false),
+            "http://example.com/"
+        );
+        assert_eq!(
+            normalize("https://example.com:8443/", false),
+            "https://example.com:8443/"
+        );
+    }
+
+    #[test]
+    fn test_normalize_dot_segments() {
+        assert_eq!(
+            normalize("https://example.com/a/./b/../c", false),
+            "https://example.com/a/c"
+        );
+    }
+
+    #[test]
+    fn test_normalize_empty_query_and_fragment() {
+        assert_eq!(
+            normalize("https://example.com/?#", false),
+            "https://example.com/"
+        );
+        assert_eq!(
+            normalize("https://example.com/?q=1", false),
+            "https://example.com/?q=1"
+        );
+    }
+
+    #[test]
+    fn test_normalize_fragment() {
+        assert_eq!(
+            normalize("https://example.com/#top", false),
+            "https://example.com/#top"
+        );
+        assert_eq!(
+            normalize("https://example.com/#top", true),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn test_normalize_mail() {
+        let uri = mail("Foo.Bar@Example.COM");
+        assert_eq!(uri.normalize_mail(false), mail("Foo.Bar@example.com"));
+        assert_eq!(uri.normalize_mail(true), mail("foo.bar@example.com"));
+        assert_eq!(
+            mail("foo.bar@example.com").normalize_mail(false),
+            mail("foo.bar@example.com")
+        );
+        assert_eq!(
+            Uri::from(Url::parse("mailto:Foo@Example.COM?subject=Hi").unwrap())
+                .normalize_mail(true)
+                .as_str(),
+            "foo@example.com?subject=Hi"
+        );
+        let website = website("https://example.com/Foo@Bar");
+        assert_eq!(website.normalize_mail(true), website);
+    }
+
+    #[test]
+    fn test_normalize_combined() {
+        let normalized = normalize("HTTPS://BÜCHER.de:443/a/../b/?#Section", true);
+        assert_eq!(normalized, "https://xn--bcher-kva.de/b/");
+        // Normalizing is idempotent
+        assert_eq!(normalize(&normalized, true), normalized);
+        assert_eq!(
+            Uri::try_from("https://example.com:443/x/..")
+                .unwrap()
+                .normalize(false),
+            Uri::try_from("HTTPS://EXAMPLE.COM/").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_github() {
+        for uri in [
+            "https://github.com/lycheeverse/lychee",
+            "https://www.github.com/lycheeverse",
+            "https://api.github.com/repos/lycheeverse/lychee",
+            "https://raw.githubusercontent.com/lycheeverse/lychee/master/README.md",
+        ] {
+            assert!(website(uri).is_github(), "{uri}");
+        }
+        assert!(!website("https://github.io").is_github());
+        assert!(!website("https://example.com/github.com").is_github());
+        assert!(!mail("mail@github.com").is_github());
+    }
+
     #[test]
     fn test_ipv4_uri_is_loopback() {
         let uri = Uri::try_from("http://127.0.0.0").unwrap();